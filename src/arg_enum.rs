@@ -0,0 +1,141 @@
+//! Enum-valued arguments for the pam-args library.
+//!
+//! This module provides the `arg_enum!` macro, which declares an enum whose
+//! variants map to fixed argument values. The generated type implements
+//! `FromArgValue` and exposes its values as a list that can be passed to
+//! `KeyValue::allowed_values`, so the two never drift apart.
+
+/// Declares an enum that can be used as the value of a key-value argument
+///
+/// Each variant is given the text that selects it. The macro generates:
+///
+/// * the enum itself, with any attributes given before it
+/// * `VARIANTS`, the accepted values in declaration order
+/// * `as_str()`, returning the value that selects a variant
+/// * a `FromArgValue` implementation
+///
+/// Values are matched exactly unless `ConverterConfig::case_insensitive_enums`
+/// is set. A value that matches no variant is reported as
+/// `Error::InvalidValue` together with the list of valid values.
+///
+/// # Examples
+///
+/// ```
+/// use pam_args::{arg_enum, ArgumentParser, KeyValue};
+///
+/// arg_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub enum Align {
+///         Left => "LEFT",
+///         Center => "CENTER",
+///         Right => "RIGHT",
+///     }
+/// }
+///
+/// let parser = ArgumentParser::new()
+///     .key_value(KeyValue::new("ALIGN", "Text alignment").allowed_values(Align::VARIANTS));
+///
+/// let result = parser.parse(vec!["ALIGN=CENTER".to_string()]).unwrap();
+/// assert_eq!(result.value_of::<Align>("ALIGN"), Some(Align::Center));
+/// assert_eq!(Align::Right.as_str(), "RIGHT");
+/// ```
+#[macro_export]
+macro_rules! arg_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident => $text:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            /// The accepted values, in declaration order
+            pub const VARIANTS: &'static [&'static str] = &[$($text),+];
+
+            /// Returns the value that selects this variant
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $text),+
+                }
+            }
+        }
+
+        impl $crate::FromArgValue for $name {
+            fn from_arg_value_with_config(
+                value: &str,
+                config: ::std::option::Option<&$crate::ConverterConfig>,
+            ) -> $crate::Result<Self> {
+                Self::from_arg_value_for_key(value, stringify!($name), config)
+            }
+
+            fn from_arg_value_for_key(
+                value: &str,
+                key: &str,
+                config: ::std::option::Option<&$crate::ConverterConfig>,
+            ) -> $crate::Result<Self> {
+                let case_insensitive = config.is_some_and(|c| c.case_insensitive_enums);
+                $(
+                    if value == $text || (case_insensitive && value.to_lowercase() == $text.to_lowercase()) {
+                        return Ok(Self::$variant);
+                    }
+                )+
+                Err($crate::Error::InvalidValue(
+                    key.to_string(),
+                    format!("{} (expected one of: {})", value, Self::VARIANTS.join(", ")),
+                ))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conversion::{converter, ConverterConfig, FromArgValue};
+    use crate::error::Error;
+
+    arg_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Align {
+            Left => "LEFT",
+            Center => "CENTER",
+            Right => "RIGHT",
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(Align::VARIANTS, ["LEFT", "CENTER", "RIGHT"]);
+
+        for align in [Align::Left, Align::Center, Align::Right] {
+            let converted: Align = converter::convert(align.as_str(), None).unwrap();
+            assert_eq!(converted, align);
+        }
+    }
+
+    #[test]
+    fn test_case_insensitive_matching() {
+        assert!(converter::convert::<Align>("left", None).is_err());
+
+        let config = ConverterConfig {
+            case_insensitive_enums: true,
+            ..ConverterConfig::default()
+        };
+        assert_eq!(converter::convert::<Align>("left", Some(&config)).unwrap(), Align::Left);
+        assert_eq!(converter::convert::<Align>("Center", Some(&config)).unwrap(), Align::Center);
+    }
+
+    #[test]
+    fn test_invalid_value_lists_variants() {
+        match Align::from_arg_value_for_key("TOP", "ALIGN", None) {
+            Err(Error::InvalidValue(key, value)) => {
+                assert_eq!(key, "ALIGN");
+                assert_eq!(value, "TOP (expected one of: LEFT, CENTER, RIGHT)");
+            },
+            other => panic!("Expected InvalidValue error, got {:?}", other),
+        }
+    }
+}
@@ -6,10 +6,23 @@
 
 use std::any::Any;
 use std::fmt;
+use std::sync::Arc;
 use crate::error::{Error, Result};
 
+/// Callback that receives whether a bound flag was present
+type FlagBinding = Arc<dyn Fn(bool) + Send + Sync>;
+
+/// A converted value of any type, as produced by a key-value type converter
+pub(crate) type TypedValue = Arc<dyn Any + Send + Sync>;
+
+/// Type-erased converter registered with `KeyValue::type_converter`
+type TypedConverter = Arc<dyn Fn(&str) -> Result<TypedValue> + Send + Sync>;
+
 /// Represents a flag (boolean) command-line argument
-#[derive(Debug, Clone)]
+///
+/// A flag is given bare (`DEBUG`) or with an explicit boolean value
+/// (`DEBUG=false`); see `ParseResult::flag_value`.
+#[derive(Clone)]
 pub struct Flag {
     /// The name of the flag
     name: String,
@@ -22,6 +35,34 @@ pub struct Flag {
     
     /// List of arguments that this flag conflicts with
     exclusions: Vec<String>,
+    
+    /// Usage examples for help text
+    examples: Vec<String>,
+    
+    /// Key-value pairs set when this flag is present, as (key, value)
+    implications: Vec<(String, String)>,
+    
+    /// Alternative names that also match this flag
+    aliases: Vec<String>,
+    
+    /// Callback run with the flag's presence after a successful parse
+    binding: Option<FlagBinding>,
+}
+
+// Manual implementation of Debug for Flag, since the binding is a closure
+impl fmt::Debug for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Flag")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("dependencies", &self.dependencies)
+            .field("exclusions", &self.exclusions)
+            .field("examples", &self.examples)
+            .field("implications", &self.implications)
+            .field("aliases", &self.aliases)
+            .field("has_binding", &self.binding.is_some())
+            .finish()
+    }
 }
 
 /// Represents a key-value pair command-line argument
@@ -47,8 +88,17 @@ pub struct KeyValue {
     /// Allowed values for this key-value pair (if restricted)
     allowed_values: Option<Vec<String>>,
     
-    /// Whether this key-value pair has a type converter
-    has_type_converter: bool,
+    /// Converter producing the typed value, if one was registered
+    type_converter: Option<TypedConverter>,
+    
+    /// Value used when the key is not given
+    default_value: Option<String>,
+    
+    /// Alternative names that also match this key
+    aliases: Vec<String>,
+    
+    /// Usage examples for help text
+    examples: Vec<String>,
 }
 
 // Manual implementation of Debug for KeyValue
@@ -62,7 +112,10 @@ impl fmt::Debug for KeyValue {
             .field("exclusions", &self.exclusions)
             .field("allowed_formats", &self.allowed_formats)
             .field("allowed_values", &self.allowed_values)
-            .field("has_type_converter", &self.has_type_converter)
+            .field("has_type_converter", &self.type_converter.is_some())
+            .field("default_value", &self.default_value)
+            .field("aliases", &self.aliases)
+            .field("examples", &self.examples)
             .finish()
     }
 }
@@ -78,7 +131,10 @@ impl Clone for KeyValue {
             exclusions: self.exclusions.clone(),
             allowed_formats: self.allowed_formats.clone(),
             allowed_values: self.allowed_values.clone(),
-            has_type_converter: self.has_type_converter,
+            type_converter: self.type_converter.clone(),
+            default_value: self.default_value.clone(),
+            aliases: self.aliases.clone(),
+            examples: self.examples.clone(),
         }
     }
 }
@@ -160,6 +216,32 @@ impl AllowedKeyValueFormats {
         })
     }
     
+    /// Returns the canonical machine-readable name of this format
+    ///
+    /// Unlike the `Display` output, which shows the syntax (e.g. `KEY=VALUE`),
+    /// these names are stable identifiers meant for help output consumed by
+    /// tools and for JSON.
+    ///
+    /// # Returns
+    ///
+    /// The lowercase, underscore-separated name of the format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::AllowedKeyValueFormats;
+    ///
+    /// assert_eq!(AllowedKeyValueFormats::KeyEquals.as_str(), "key_equals");
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllowedKeyValueFormats::KeyValue => "key_value",
+            AllowedKeyValueFormats::KeyOnly => "key_only",
+            AllowedKeyValueFormats::KeyEquals => "key_equals",
+            AllowedKeyValueFormats::KeyAll => "key_all",
+        }
+    }
+    
     /// Returns all possible formats
     ///
     /// # Returns
@@ -183,6 +265,59 @@ impl AllowedKeyValueFormats {
     }
 }
 
+impl fmt::Display for AllowedKeyValueFormats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllowedKeyValueFormats::KeyValue => write!(f, "KEY=VALUE"),
+            AllowedKeyValueFormats::KeyOnly => write!(f, "KEY"),
+            AllowedKeyValueFormats::KeyEquals => write!(f, "KEY="),
+            AllowedKeyValueFormats::KeyAll => write!(f, "KEY=VALUE, KEY=, KEY"),
+        }
+    }
+}
+
+/// Named presets for common combinations of allowed formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatPreset {
+    /// Only KEY=VALUE is accepted
+    ValueOnly,
+    
+    /// Either a bare KEY or KEY=VALUE is accepted
+    FlagOrValue,
+    
+    /// KEY, KEY= and KEY=VALUE are all accepted
+    Any,
+}
+
+impl FormatPreset {
+    /// Returns the concrete formats this preset stands for
+    ///
+    /// # Returns
+    ///
+    /// A vector of the allowed formats
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{AllowedKeyValueFormats, FormatPreset};
+    ///
+    /// assert_eq!(
+    ///     FormatPreset::FlagOrValue.formats(),
+    ///     vec![AllowedKeyValueFormats::KeyOnly, AllowedKeyValueFormats::KeyValue]
+    /// );
+    /// ```
+    pub fn formats(&self) -> Vec<AllowedKeyValueFormats> {
+        match self {
+            FormatPreset::ValueOnly => vec![AllowedKeyValueFormats::KeyValue],
+            FormatPreset::FlagOrValue => vec![
+                AllowedKeyValueFormats::KeyOnly,
+                AllowedKeyValueFormats::KeyValue,
+            ],
+            FormatPreset::Any => AllowedKeyValueFormats::all(),
+        }
+    }
+}
+
 impl Flag {
     /// Creates a new flag with the given name and description
     ///
@@ -208,6 +343,10 @@ impl Flag {
             description: description.into(),
             dependencies: Vec::new(),
             exclusions: Vec::new(),
+            examples: Vec::new(),
+            implications: Vec::new(),
+            aliases: Vec::new(),
+            binding: None,
         }
     }
     
@@ -261,6 +400,176 @@ impl Flag {
         self
     }
     
+    /// Adds several exclusions to this flag at once
+    ///
+    /// Equivalent to calling `excludes` for each name.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names of the arguments that this flag excludes
+    ///
+    /// # Returns
+    ///
+    /// The flag with the exclusions added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Flag;
+    ///
+    /// let flag = Flag::new("DEBUG", "Enable debug mode")
+    ///     .conflicts_with_all(&["QUIET", "SILENT", "PRODUCTION"]);
+    /// ```
+    pub fn conflicts_with_all(mut self, names: &[&str]) -> Self {
+        self.exclusions.extend(names.iter().map(|name| name.to_string()));
+        self
+    }
+    
+    /// Declares that this flag implies a key-value pair
+    ///
+    /// When the flag is present, the key is set to the given value unless
+    /// the user supplied the key explicitly; an explicit value always wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to set when the flag is present
+    /// * `value` - The value to give the key
+    ///
+    /// # Returns
+    ///
+    /// The flag with the implication added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Flag;
+    ///
+    /// let flag = Flag::new("PARANOID", "Enable all hardening options")
+    ///     .implies("STRICT", "true");
+    /// ```
+    pub fn implies<S1, S2>(mut self, key: S1, value: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        self.implications.push((key.into(), value.into()));
+        self
+    }
+    
+    /// Adds a usage example to this flag
+    ///
+    /// Examples are shown in help output. Calling this more than once
+    /// accumulates examples in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The example, as it would appear in a PAM configuration line
+    ///
+    /// # Returns
+    ///
+    /// The flag with the example added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Flag;
+    ///
+    /// let flag = Flag::new("DEBUG", "Enable debug mode")
+    ///     .example("auth required pam_example.so DEBUG");
+    /// ```
+    pub fn example<S: Into<String>>(mut self, text: S) -> Self {
+        self.examples.push(text.into());
+        self
+    }
+    
+    /// Adds an alternative name for this flag
+    ///
+    /// An argument given under the alias is recorded under the canonical
+    /// name. Aliases take part in duplicate name detection like names do.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The alternative name
+    ///
+    /// # Returns
+    ///
+    /// The flag with the alias added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Flag;
+    ///
+    /// let flag = Flag::new("DEBUG", "Enable debug mode")
+    ///     .alias("D");
+    /// ```
+    pub fn alias<S: Into<String>>(mut self, name: S) -> Self {
+        self.aliases.push(name.into());
+        self
+    }
+    
+    /// Adds several alternative names for this flag
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The alternative names
+    ///
+    /// # Returns
+    ///
+    /// The flag with the aliases added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Flag;
+    ///
+    /// let flag = Flag::new("DEBUG", "Enable debug mode")
+    ///     .aliases(&["D", "DBG"]);
+    /// ```
+    pub fn aliases<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.aliases.extend(names.iter().map(|name| name.as_ref().to_string()));
+        self
+    }
+    
+    /// Binds this flag to a setter called after each successful parse
+    ///
+    /// The setter receives `true` if the flag was present and `false`
+    /// otherwise. It is not called when parsing fails. Since the parser
+    /// outlives any single borrow, the target is usually shared, for example
+    /// an `AtomicBool` inside an `Arc`. Binding again replaces the setter.
+    ///
+    /// # Arguments
+    ///
+    /// * `setter` - Function receiving the flag's presence
+    ///
+    /// # Returns
+    ///
+    /// The flag with the binding set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let debug = Arc::new(AtomicBool::new(false));
+    /// let target = debug.clone();
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode")
+    ///         .bind(move |present| target.store(present, Ordering::Relaxed)));
+    ///
+    /// parser.parse(vec!["DEBUG".to_string()]).unwrap();
+    /// assert!(debug.load(Ordering::Relaxed));
+    /// ```
+    pub fn bind<F>(mut self, setter: F) -> Self
+    where
+        F: Fn(bool) + Send + Sync + 'static,
+    {
+        self.binding = Some(Arc::new(setter));
+        self
+    }
+    
     /// Returns the name of this flag
     ///
     /// # Returns
@@ -297,14 +606,47 @@ impl Flag {
         &self.exclusions
     }
     
+    /// Returns the alternative names of this flag
+    ///
+    /// # Returns
+    ///
+    /// A slice of the aliases in the order they were added
+    pub fn get_aliases(&self) -> &[String] {
+        &self.aliases
+    }
+    
+    /// Returns the usage examples of this flag
+    ///
+    /// # Returns
+    ///
+    /// A slice of the examples
+    pub fn examples(&self) -> &[String] {
+        &self.examples
+    }
+    
+    /// Returns the key-value pairs implied by this flag
+    ///
+    /// # Returns
+    ///
+    /// A slice of (key, value) pairs in the order they were declared
+    pub fn implications(&self) -> &[(String, String)] {
+        &self.implications
+    }
+    
     /// Returns whether this flag has a binding
     ///
     /// # Returns
     ///
     /// true if the flag has a binding, false otherwise
     pub fn has_binding(&self) -> bool {
-        // For simplicity, we're not implementing bindings in this version
-        false
+        self.binding.is_some()
+    }
+    
+    /// Passes the flag's presence to its binding, if any
+    pub(crate) fn apply_binding(&self, present: bool) {
+        if let Some(binding) = &self.binding {
+            binding(present);
+        }
     }
 }
 
@@ -336,7 +678,10 @@ impl KeyValue {
             exclusions: Vec::new(),
             allowed_formats: vec![AllowedKeyValueFormats::KeyValue],
             allowed_values: None,
-            has_type_converter: false,
+            type_converter: None,
+            default_value: None,
+            aliases: Vec::new(),
+            examples: Vec::new(),
         }
     }
     
@@ -359,6 +704,85 @@ impl KeyValue {
         self
     }
     
+    /// Adds an alternative name for this key-value pair
+    ///
+    /// An argument given under the alias is recorded under the canonical
+    /// name. Aliases take part in duplicate name detection like names do.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The alternative name
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the alias added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::KeyValue;
+    ///
+    /// let kv = KeyValue::new("USER", "Username for authentication")
+    ///     .alias("U");
+    /// ```
+    pub fn alias<S: Into<String>>(mut self, name: S) -> Self {
+        self.aliases.push(name.into());
+        self
+    }
+    
+    /// Adds several alternative names for this key-value pair
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The alternative names
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the aliases added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::KeyValue;
+    ///
+    /// let kv = KeyValue::new("USER", "Username for authentication")
+    ///     .aliases(&["U", "USERNAME"]);
+    /// ```
+    pub fn aliases<S: AsRef<str>>(mut self, names: &[S]) -> Self {
+        self.aliases.extend(names.iter().map(|name| name.as_ref().to_string()));
+        self
+    }
+    
+    /// Sets the value used when this key is not given
+    ///
+    /// The default must be one of the allowed values and fit the allowed
+    /// formats; otherwise parsing fails with `Error::InvalidValue` before any
+    /// argument is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The default value
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the default value set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("WIDTH", "Output width").default_value("80"));
+    ///
+    /// let result = parser.parse(Vec::new()).unwrap();
+    /// assert_eq!(result.value_of::<u32>("WIDTH"), Some(80));
+    /// ```
+    pub fn default_value<S: Into<String>>(mut self, value: S) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+    
     /// Adds a dependency to this key-value pair
     ///
     /// The key-value pair will only be considered if the dependency is present.
@@ -409,6 +833,58 @@ impl KeyValue {
         self
     }
     
+    /// Adds several exclusions to this key-value pair at once
+    ///
+    /// Equivalent to calling `excludes` for each name.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names of the arguments that this key-value pair excludes
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the exclusions added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::KeyValue;
+    ///
+    /// let kv = KeyValue::new("HOST", "Host name")
+    ///     .conflicts_with_all(&["LOCAL", "SOCKET", "PIPE"]);
+    /// ```
+    pub fn conflicts_with_all(mut self, names: &[&str]) -> Self {
+        self.exclusions.extend(names.iter().map(|name| name.to_string()));
+        self
+    }
+    
+    /// Adds a usage example to this key-value pair
+    ///
+    /// Examples are shown in help output. Calling this more than once
+    /// accumulates examples in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The example, as it would appear in a PAM configuration line
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the example added
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::KeyValue;
+    ///
+    /// let kv = KeyValue::new("USER", "Username for authentication")
+    ///     .example("USER=admin")
+    ///     .example("USER='service account'");
+    /// ```
+    pub fn example<S: Into<String>>(mut self, text: S) -> Self {
+        self.examples.push(text.into());
+        self
+    }
+    
     /// Sets the allowed formats for this key-value pair
     ///
     /// # Arguments
@@ -435,6 +911,29 @@ impl KeyValue {
         self
     }
     
+    /// Sets the allowed formats for this key-value pair from a named preset
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The preset to expand into allowed formats
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the allowed formats set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{KeyValue, FormatPreset};
+    ///
+    /// let kv = KeyValue::new("DEBUG", "Enable debug mode")
+    ///     .format_preset(FormatPreset::FlagOrValue);
+    /// ```
+    pub fn format_preset(mut self, preset: FormatPreset) -> Self {
+        self.allowed_formats = preset.formats();
+        self
+    }
+    
     /// Sets the allowed values for this key-value pair
     ///
     /// # Arguments
@@ -458,8 +957,42 @@ impl KeyValue {
         self
     }
     
+    /// Sets the allowed values for this key-value pair from owned strings
+    ///
+    /// This is convenient when the allowed values are computed at runtime,
+    /// for example from the groups defined on the system.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Iterator of allowed values
+    ///
+    /// # Returns
+    ///
+    /// The key-value pair with the allowed values set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::KeyValue;
+    ///
+    /// let groups = vec!["wheel".to_string(), "staff".to_string()];
+    /// let kv = KeyValue::new("GROUP", "Required group")
+    ///     .allowed_values_iter(groups);
+    /// assert!(kv.is_value_allowed("wheel"));
+    /// ```
+    pub fn allowed_values_iter<I: IntoIterator<Item = String>>(mut self, values: I) -> Self {
+        self.allowed_values = Some(values.into_iter().collect());
+        self
+    }
+    
     /// Sets the type converter function for this key-value pair
     ///
+    /// The converter runs during parsing on the value given for this key, and
+    /// its result is available from `ParseResult::typed_value_of`. A value the
+    /// converter rejects fails the parse with `Error::InvalidValue`. The
+    /// converted type must be `Send + Sync`, since it is stored in the parse
+    /// result, so a converter returning `Rc` or `RefCell` is not accepted.
+    ///
     /// # Arguments
     ///
     /// * `converter` - The function to use for type conversion
@@ -470,19 +1003,27 @@ impl KeyValue {
     ///
     /// # Examples
     ///
-    /// ```ignore
-    /// use pam_args::{KeyValue, from_str};
+    /// ```
+    /// use pam_args::{converter, ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("WIDTH", "Width in pixels")
+    ///         .type_converter(converter::from_str::<i32>()));
     ///
-    /// let kv = KeyValue::new("WIDTH", "Width in pixels")
-    ///     .type_converter(converter::from_str::<i32>());
+    /// let result = parser.parse(vec!["WIDTH=80".to_string()]).unwrap();
+    /// assert_eq!(result.typed_value_of::<i32>("WIDTH"), Some(&80));
     /// ```
-    pub fn type_converter<T, E>(mut self, _converter: fn(&str) -> std::result::Result<T, E>) -> Self
+    pub fn type_converter<T, E>(mut self, converter: fn(&str) -> std::result::Result<T, E>) -> Self
     where
-        T: 'static + std::any::Any,
-        E: std::fmt::Display,
+        T: Any + Send + Sync,
+        E: std::fmt::Display + 'static,
     {
-        // Set the flag to indicate that a type converter was set
-        self.has_type_converter = true;
+        let name = self.name.clone();
+        self.type_converter = Some(Arc::new(move |value: &str| {
+            converter(value)
+                .map(|converted| Arc::new(converted) as TypedValue)
+                .map_err(|e| Error::InvalidValue(name.clone(), format!("{} ({})", value, e)))
+        }));
         self
     }
     
@@ -565,13 +1106,45 @@ impl KeyValue {
         }
     }
     
+    /// Returns the default value of this key-value pair
+    ///
+    /// # Returns
+    ///
+    /// The default value, or None if no default was set
+    pub fn get_default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+    
     /// Returns whether this key-value pair has a type converter
     ///
     /// # Returns
     ///
     /// true if the key-value pair has a type converter, false otherwise
     pub fn has_type_converter(&self) -> bool {
-        self.has_type_converter
+        self.type_converter.is_some()
+    }
+    
+    /// Runs the type converter on a value, if one was registered
+    pub(crate) fn convert_typed(&self, value: &str) -> Option<Result<TypedValue>> {
+        self.type_converter.as_ref().map(|converter| converter(value))
+    }
+    
+    /// Returns the alternative names of this key-value pair
+    ///
+    /// # Returns
+    ///
+    /// A slice of the aliases in the order they were added
+    pub fn get_aliases(&self) -> &[String] {
+        &self.aliases
+    }
+    
+    /// Returns the usage examples of this key-value pair
+    ///
+    /// # Returns
+    ///
+    /// A slice of the examples
+    pub fn examples(&self) -> &[String] {
+        &self.examples
     }
     
     /// Returns whether this key-value pair has a binding
@@ -600,6 +1173,23 @@ mod tests {
         assert!(!flag.has_binding());
     }
 
+    #[test]
+    fn test_flag_binding() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let target = Arc::new(AtomicBool::new(false));
+        let setter = target.clone();
+        let flag = Flag::new("DEBUG", "Enable debug mode")
+            .bind(move |present| setter.store(present, Ordering::Relaxed));
+        assert!(flag.has_binding());
+        assert!(format!("{:?}", flag).contains("has_binding: true"));
+
+        flag.apply_binding(true);
+        assert!(target.load(Ordering::Relaxed));
+        flag.clone().apply_binding(false);
+        assert!(!target.load(Ordering::Relaxed));
+    }
+
     #[test]
     fn test_flag_dependencies() {
         let flag = Flag::new("VERBOSE", "Verbose output")
@@ -618,6 +1208,39 @@ mod tests {
         assert_eq!(flag.exclusions()[0], "QUIET");
     }
 
+    #[test]
+    fn test_flag_conflicts_with_all() {
+        let flag = Flag::new("DEBUG", "Debug mode")
+            .excludes("QUIET")
+            .conflicts_with_all(&["SILENT", "PRODUCTION", "TRACE"]);
+        
+        assert_eq!(flag.exclusions(), &["QUIET", "SILENT", "PRODUCTION", "TRACE"]);
+    }
+
+    #[test]
+    fn test_flag_implications() {
+        let flag = Flag::new("PARANOID", "Hardening");
+        assert!(flag.implications().is_empty());
+        
+        let flag = flag.implies("STRICT", "true").implies("TIMEOUT", "5");
+        assert_eq!(
+            flag.implications(),
+            &[
+                ("STRICT".to_string(), "true".to_string()),
+                ("TIMEOUT".to_string(), "5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flag_examples() {
+        let flag = Flag::new("DEBUG", "Debug mode");
+        assert!(flag.examples().is_empty());
+        
+        let flag = flag.example("DEBUG").example("[DEBUG]");
+        assert_eq!(flag.examples(), &["DEBUG", "[DEBUG]"]);
+    }
+
     #[test]
     fn test_keyvalue_creation() {
         let kv = KeyValue::new("USER", "Username for authentication");
@@ -631,6 +1254,46 @@ mod tests {
         assert!(kv.get_allowed_values().is_none());
         assert!(!kv.has_type_converter());
         assert!(!kv.has_binding());
+        assert_eq!(kv.get_default_value(), None);
+    }
+
+    #[test]
+    fn test_aliases() {
+        let flag = Flag::new("DEBUG", "Enable debug mode").alias("D").aliases(&["DBG"]);
+        assert_eq!(flag.get_aliases(), ["D", "DBG"]);
+        assert!(Flag::new("QUIET", "Suppress output").get_aliases().is_empty());
+
+        let kv = KeyValue::new("USER", "Username").aliases(&["U".to_string()]).alias("LOGIN");
+        assert_eq!(kv.clone().get_aliases(), ["U", "LOGIN"]);
+    }
+
+    #[test]
+    fn test_keyvalue_default_value() {
+        let kv = KeyValue::new("WIDTH", "Output width").default_value("80");
+        assert_eq!(kv.get_default_value(), Some("80"));
+        assert_eq!(kv.clone().get_default_value(), Some("80"));
+        assert!(format!("{:?}", kv).contains("default_value: Some(\"80\")"));
+    }
+
+    #[test]
+    fn test_keyvalue_type_converter() {
+        let kv = KeyValue::new("WIDTH", "Width in pixels")
+            .type_converter(crate::conversion::converter::from_str::<i32>());
+        assert!(kv.has_type_converter());
+        assert!(kv.clone().has_type_converter());
+
+        let converted = kv.convert_typed("80").unwrap().unwrap();
+        assert_eq!(converted.downcast_ref::<i32>(), Some(&80));
+
+        match kv.convert_typed("wide") {
+            Some(Err(Error::InvalidValue(key, value))) => {
+                assert_eq!(key, "WIDTH");
+                assert_eq!(value, "wide (invalid digit found in string)");
+            },
+            other => panic!("Expected InvalidValue error, got {:?}", other.map(|r| r.map(|_| ()))),
+        }
+
+        assert!(KeyValue::new("USER", "Username").convert_typed("admin").is_none());
     }
 
     #[test]
@@ -659,6 +1322,24 @@ mod tests {
         assert_eq!(kv.exclusions()[0], "LOCAL");
     }
 
+    #[test]
+    fn test_keyvalue_conflicts_with_all() {
+        let kv = KeyValue::new("HOST", "Host name")
+            .conflicts_with_all(&["LOCAL", "SOCKET", "PIPE"]);
+        
+        assert_eq!(kv.exclusions(), &["LOCAL", "SOCKET", "PIPE"]);
+    }
+
+    #[test]
+    fn test_keyvalue_examples() {
+        let kv = KeyValue::new("USER", "Username")
+            .example("USER=admin")
+            .example("USER='service account'");
+        
+        assert_eq!(kv.examples(), &["USER=admin", "USER='service account'"]);
+        assert_eq!(kv.clone().examples().len(), 2);
+    }
+
     #[test]
     fn test_keyvalue_allowed_formats() {
         let kv = KeyValue::new("DEBUG", "Debug mode")
@@ -672,6 +1353,23 @@ mod tests {
         assert_eq!(kv.get_allowed_formats()[1], AllowedKeyValueFormats::KeyValue);
     }
 
+    #[test]
+    fn test_format_presets() {
+        assert_eq!(
+            FormatPreset::ValueOnly.formats(),
+            vec![AllowedKeyValueFormats::KeyValue]
+        );
+        assert_eq!(
+            FormatPreset::FlagOrValue.formats(),
+            vec![AllowedKeyValueFormats::KeyOnly, AllowedKeyValueFormats::KeyValue]
+        );
+        assert_eq!(FormatPreset::Any.formats(), AllowedKeyValueFormats::all());
+        
+        let kv = KeyValue::new("DEBUG", "Debug mode")
+            .format_preset(FormatPreset::FlagOrValue);
+        assert_eq!(kv.get_allowed_formats(), FormatPreset::FlagOrValue.formats().as_slice());
+    }
+
     #[test]
     fn test_keyvalue_allowed_values() {
         let kv = KeyValue::new("ALIGN", "Text alignment")
@@ -688,4 +1386,23 @@ mod tests {
         assert!(kv.is_value_allowed("RIGHT"));
         assert!(!kv.is_value_allowed("BOTTOM"));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_keyvalue_allowed_values_iter() {
+        let groups: Vec<String> = ["wheel", "staff", "dev"].iter().map(|g| g.to_string()).collect();
+        let kv = KeyValue::new("GROUP", "Required group")
+            .allowed_values_iter(groups);
+        
+        assert_eq!(kv.get_allowed_values().unwrap(), ["wheel", "staff", "dev"]);
+        assert!(kv.is_value_allowed("staff"));
+        assert!(!kv.is_value_allowed("root"));
+    }
+    
+    #[test]
+    fn test_format_as_str() {
+        assert_eq!(AllowedKeyValueFormats::KeyValue.as_str(), "key_value");
+        assert_eq!(AllowedKeyValueFormats::KeyOnly.as_str(), "key_only");
+        assert_eq!(AllowedKeyValueFormats::KeyEquals.as_str(), "key_equals");
+        assert_eq!(AllowedKeyValueFormats::KeyAll.as_str(), "key_all");
+    }
+}
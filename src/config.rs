@@ -5,7 +5,30 @@
 //! The configuration is immutable after creation, ensuring thread safety
 //! and consistent behavior during parsing.
 
+use std::sync::Arc;
+
 use crate::args::AllowedKeyValueFormats;
+use crate::conversion::ConverterConfig;
+use crate::tokenizer::TrailingDelimiterPolicy;
+use crate::utils::case::{self, Normalizer};
+
+/// Function mapping an argument name to its canonical form
+///
+/// See `ParserConfigBuilder::key_normalizer`.
+pub type KeyNormalizer = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Handling of a key repeated within one bracketed group, e.g. `[A=1,A=2]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketDuplicatePolicy {
+    /// The last value wins: `[A=1,A=2]` gives `A=2`
+    Overwrite,
+    
+    /// The first value wins: `[A=1,A=2]` gives `A=1`
+    KeepFirst,
+    
+    /// Reject the group with `Error::DuplicateArgName`
+    Error,
+}
 
 /// Configuration for the argument parser
 #[derive(Debug, Clone)]
@@ -50,8 +73,77 @@ pub struct ParserConfig {
     /// Custom delimiters can be useful when comma is commonly used in values
     delimiter: char,
     
-    /// Whether to trim whitespace from values
+    /// Whether to trim whitespace from values and from elements of bracketed groups
     trim_values: bool,
+    
+    /// Conversion settings shared by all typed value lookups
+    conversion: ConverterConfig,
+    
+    /// How a delimiter at the end of bracketed content is handled (default: keep an empty token)
+    /// Trailing delimiters are a common copy-paste artifact in PAM configuration files
+    trailing_delimiter_policy: TrailingDelimiterPolicy,
+    
+    /// Whether comment lines read before the argument line are kept in the parse result
+    retain_comments: bool,
+    
+    /// Whether a missing required argument's dependencies are checked before it is
+    /// reported missing, so that `DependencyNotMet` takes precedence over `RequiredArgMissing`
+    required_implies_dependency_check: bool,
+    
+    /// Whether `parse_line` treats the leading token as the module name
+    capture_module_name: bool,
+    
+    /// Whether `${KEY}` references in values are expanded once parsing completes
+    enable_value_templating: bool,
+    
+    /// Whether bracketed groups are rejected unless multi key-value pairs are enabled
+    require_multi_key_value_for_brackets: bool,
+    
+    /// Whether contradictory argument definitions are errors rather than warnings
+    strict_definitions: bool,
+    
+    /// Maximum line width of the text generated by `ArgumentParser::help`
+    help_width: usize,
+    
+    /// Whether brackets may nest inside bracketed arguments
+    allow_nested_brackets: bool,
+    
+    /// Whether errors returned by `ArgumentParser::parse` carry the arguments being parsed
+    error_context: bool,
+    
+    /// Separator used to join adjacent non-argument text into one entry, if any
+    join_adjacent_non_arg: Option<char>,
+    
+    /// Function canonicalizing argument names in place of case normalization, if any
+    key_normalizer: Option<Normalizer>,
+    
+    /// Whether several multi key-value pairs in one argument must be bracketed
+    require_brackets_for_multi: bool,
+    
+    /// Whether the key of a key-value pair may be quoted
+    allow_quoted_keys: bool,
+    
+    /// Whether type converter failures are collected instead of failing the parse
+    collect_conversion_errors: bool,
+    
+    /// Whether unregistered keys given with a value must be valid key names
+    validate_key_names: bool,
+    
+    /// Maximum length in bytes of a single argument, if limited
+    max_arg_length: Option<usize>,
+    
+    /// Whether invisible formatting characters are removed from arguments before parsing
+    strip_invisible_chars: bool,
+    
+    /// Whether values are lowercased when they are stored
+    lowercase_values: bool,
+    
+    /// How a key repeated within one bracketed group is handled (default: the last value wins)
+    bracket_duplicate_policy: BracketDuplicatePolicy,
+    
+    /// Character starting a comment line (default: '#')
+    /// Lines starting with it are skipped by `parse_reader` and `lint_line`
+    comment_prefix: char,
 }
 
 /// Builder for creating parser configurations
@@ -247,285 +339,1236 @@ impl ParserConfig {
         self.trim_values
     }
     
-    /// Returns a builder for creating a new configuration
+    /// Returns the conversion settings used for typed value lookups
     ///
     /// # Examples
     ///
     /// ```
     /// use pam_args::ParserConfig;
     ///
-    /// let config = ParserConfig::builder()
-    ///     .case_sensitive(false)
-    ///     .collect_non_argument_text(true)
-    ///     .build();
+    /// let config = ParserConfig::new();
+    /// assert!(config.conversion().trim_whitespace);
     /// ```
-    pub fn builder() -> ParserConfigBuilder {
-        ParserConfigBuilder::new()
-    }
-}
-
-impl Default for ParserConfig {
-    fn default() -> Self {
-        Self {
-            case_sensitive: true,
-            case_sensitive_values: true,
-            collect_non_argument_text: false,
-            enable_multi_key_value: false,
-            multi_key_value_formats: vec![AllowedKeyValueFormats::KeyValue],
-            escape_char: '\\',
-            single_quote: '\'',
-            double_quote: '"',
-            open_bracket: '[',
-            close_bracket: ']',
-            delimiter: ',',
-            trim_values: true,
-        }
+    pub fn conversion(&self) -> &ConverterConfig {
+        &self.conversion
     }
-}
-
-impl ParserConfigBuilder {
-    /// Creates a new parser configuration builder with default settings
+    
+    /// Returns how a trailing delimiter in bracketed content is handled
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::{ParserConfig, TrailingDelimiterPolicy};
     ///
-    /// let builder = ParserConfigBuilder::new();
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.trailing_delimiter_policy(), TrailingDelimiterPolicy::KeepEmpty);
     /// ```
-    pub fn new() -> Self {
-        Self {
-            config: ParserConfig::default(),
-        }
+    pub fn trailing_delimiter_policy(&self) -> TrailingDelimiterPolicy {
+        self.trailing_delimiter_policy
     }
     
-    /// Sets whether argument names are case-sensitive
-    ///
-    /// # Arguments
-    ///
-    /// * `case_sensitive` - Whether argument names are case-sensitive
+    /// Returns whether comment lines are retained when parsing a reader
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .case_sensitive(false);
+    /// let config = ParserConfig::new();
+    /// assert!(!config.retain_comments());
     /// ```
-    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
-        self.config.case_sensitive = case_sensitive;
-        self
+    pub fn retain_comments(&self) -> bool {
+        self.retain_comments
     }
     
-    /// Sets whether argument values are case-sensitive when validating against allowed values
+    /// Returns whether dependency errors take precedence over missing required arguments
     ///
-    /// This is separate from `case_sensitive()` which controls argument name matching.
-    /// When set to false, values like "LEFT" would match "left" during allowed values validation.
+    /// # Examples
     ///
-    /// # Arguments
+    /// ```
+    /// use pam_args::ParserConfig;
     ///
-    /// * `case_sensitive_values` - Whether argument values are case-sensitive
+    /// let config = ParserConfig::new();
+    /// assert!(!config.required_implies_dependency_check());
+    /// ```
+    pub fn required_implies_dependency_check(&self) -> bool {
+        self.required_implies_dependency_check
+    }
+    
+    /// Returns whether the leading token of a parsed line is captured as the module name
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .case_sensitive_values(false);
+    /// let config = ParserConfig::new();
+    /// assert!(!config.capture_module_name());
     /// ```
-    pub fn case_sensitive_values(mut self, case_sensitive_values: bool) -> Self {
-        self.config.case_sensitive_values = case_sensitive_values;
-        self
+    pub fn capture_module_name(&self) -> bool {
+        self.capture_module_name
     }
     
-    /// Sets whether non-argument text is collected
-    ///
-    /// # Arguments
-    ///
-    /// * `collect_non_argument_text` - Whether non-argument text is collected
+    /// Returns whether `${KEY}` references in values are expanded
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .collect_non_argument_text(true);
+    /// let config = ParserConfig::new();
+    /// assert!(!config.enable_value_templating());
     /// ```
-    pub fn collect_non_argument_text(mut self, collect_non_argument_text: bool) -> Self {
-        self.config.collect_non_argument_text = collect_non_argument_text;
-        self
+    pub fn enable_value_templating(&self) -> bool {
+        self.enable_value_templating
     }
     
-    /// Sets whether multi key-value pairs are enabled
-    ///
-    /// # Arguments
-    ///
-    /// * `enable_multi_key_value` - Whether multi key-value pairs are enabled
+    /// Returns whether bracketed groups require multi key-value pairs to be enabled
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .enable_multi_key_value(true);
+    /// let config = ParserConfig::new();
+    /// assert!(!config.require_multi_key_value_for_brackets());
     /// ```
-    pub fn enable_multi_key_value(mut self, enable_multi_key_value: bool) -> Self {
-        self.config.enable_multi_key_value = enable_multi_key_value;
-        self
+    pub fn require_multi_key_value_for_brackets(&self) -> bool {
+        self.require_multi_key_value_for_brackets
     }
     
-    /// Sets the allowed formats for multi key-value pairs
+    /// Returns whether contradictory argument definitions are errors
     ///
-    /// Multiple formats can be combined to support different key-value representations.
-    /// The formats will be checked in the order they are specified.
+    /// # Examples
     ///
-    /// # Arguments
+    /// ```
+    /// use pam_args::ParserConfig;
     ///
-    /// * `formats` - Slice of allowed formats
+    /// let config = ParserConfig::new();
+    /// assert!(!config.strict_definitions());
+    /// ```
+    pub fn strict_definitions(&self) -> bool {
+        self.strict_definitions
+    }
+    
+    /// Returns the maximum line width of generated help text
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::{ParserConfigBuilder, AllowedKeyValueFormats};
+    /// use pam_args::ParserConfig;
     ///
-    /// // Allow three different formats:
-    /// // - KEY=VALUE (e.g., "USER=admin")
-    /// // - KEY (e.g., "DEBUG" with no value)
-    /// // - KEY= (e.g., "EMPTY=" with empty value)
-    /// let builder = ParserConfigBuilder::new()
-    ///     .multi_key_value_formats(&[
-    ///         AllowedKeyValueFormats::KeyValue,
-    ///         AllowedKeyValueFormats::KeyOnly,
-    ///         AllowedKeyValueFormats::KeyEquals,
-    ///     ]);
-    ///     
-    /// // Alternatively, use KeyAll as a shorthand for all formats:
-    /// let builder = ParserConfigBuilder::new()
-    ///     .multi_key_value_formats(&[AllowedKeyValueFormats::KeyAll]);
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.help_width(), 80);
     /// ```
-    pub fn multi_key_value_formats(mut self, formats: &[AllowedKeyValueFormats]) -> Self {
-        self.config.multi_key_value_formats = formats.to_vec();
-        self
+    pub fn help_width(&self) -> usize {
+        self.help_width
     }
     
-    /// Sets the escape character
-    ///
-    /// # Arguments
-    ///
-    /// * `escape_char` - The escape character
+    /// Returns whether brackets may nest inside bracketed arguments
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .escape_char('$');
+    /// let config = ParserConfig::new();
+    /// assert!(!config.allow_nested_brackets());
     /// ```
-    pub fn escape_char(mut self, escape_char: char) -> Self {
-        self.config.escape_char = escape_char;
-        self
+    pub fn allow_nested_brackets(&self) -> bool {
+        self.allow_nested_brackets
     }
     
-    /// Sets the quote characters
-    ///
-    /// # Arguments
-    ///
-    /// * `single_quote` - The single quote character
-    /// * `double_quote` - The double quote character
+    /// Returns whether parse errors carry the arguments being parsed
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .quote_chars('\'', '"');
+    /// let config = ParserConfig::new();
+    /// assert!(!config.error_context());
     /// ```
-    pub fn quote_chars(mut self, single_quote: char, double_quote: char) -> Self {
-        self.config.single_quote = single_quote;
-        self.config.double_quote = double_quote;
-        self
+    pub fn error_context(&self) -> bool {
+        self.error_context
     }
     
-    /// Sets the bracket characters
-    ///
-    /// # Arguments
+    /// Returns the separator used to join adjacent non-argument text
     ///
-    /// * `open_bracket` - The opening bracket character
-    /// * `close_bracket` - The closing bracket character
+    /// None means every non-argument token is stored as its own entry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .bracket_chars('<', '>');
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.join_adjacent_non_arg(), None);
     /// ```
-    pub fn bracket_chars(mut self, open_bracket: char, close_bracket: char) -> Self {
-        self.config.open_bracket = open_bracket;
-        self.config.close_bracket = close_bracket;
-        self
+    pub fn join_adjacent_non_arg(&self) -> Option<char> {
+        self.join_adjacent_non_arg
     }
     
-    /// Sets the delimiter character
-    ///
-    /// # Arguments
-    ///
-    /// * `delimiter` - The delimiter character
+    /// Returns the function canonicalizing argument names, if any
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .delimiter(';');
+    /// let config = ParserConfig::new();
+    /// assert!(config.key_normalizer().is_none());
     /// ```
-    pub fn delimiter(mut self, delimiter: char) -> Self {
-        self.config.delimiter = delimiter;
-        self
+    pub fn key_normalizer(&self) -> Option<&KeyNormalizer> {
+        self.key_normalizer.as_ref().map(|normalizer| &normalizer.0)
     }
     
-    /// Sets whether values are trimmed
+    /// Normalizes an argument name for matching and storage
+    ///
+    /// Uses the key normalizer when one is set, and the configured case
+    /// sensitivity otherwise.
+    pub(crate) fn normalize_key(&self, name: &str) -> String {
+        match &self.key_normalizer {
+            Some(normalizer) => (normalizer.0)(name),
+            None => case::normalize(name, self.case_sensitive),
+        }
+    }
+    
+    /// Checks whether two argument names refer to the same argument
+    pub(crate) fn keys_match(&self, a: &str, b: &str) -> bool {
+        match &self.key_normalizer {
+            Some(normalizer) => (normalizer.0)(a) == (normalizer.0)(b),
+            None => case::compare(a, b, self.case_sensitive),
+        }
+    }
+    
+    /// Returns whether several multi key-value pairs in one argument must be bracketed
     ///
-    /// # Arguments
+    /// # Examples
     ///
-    /// * `trim_values` - Whether values are trimmed
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert!(!config.require_brackets_for_multi());
+    /// ```
+    pub fn require_brackets_for_multi(&self) -> bool {
+        self.require_brackets_for_multi
+    }
+    
+    /// Returns whether the key of a key-value pair may be quoted
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let builder = ParserConfigBuilder::new()
-    ///     .trim_values(false);
+    /// let config = ParserConfig::new();
+    /// assert!(!config.allow_quoted_keys());
     /// ```
-    pub fn trim_values(mut self, trim_values: bool) -> Self {
-        self.config.trim_values = trim_values;
-        self
+    pub fn allow_quoted_keys(&self) -> bool {
+        self.allow_quoted_keys
     }
     
-    /// Builds the configuration
+    /// Returns whether type converter failures are collected instead of failing the parse
     ///
-    /// # Returns
+    /// # Examples
     ///
-    /// The built configuration
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert!(!config.collect_conversion_errors());
+    /// ```
+    pub fn collect_conversion_errors(&self) -> bool {
+        self.collect_conversion_errors
+    }
+    
+    /// Returns whether unregistered keys given with a value must be valid key names
     ///
     /// # Examples
     ///
     /// ```
-    /// use pam_args::ParserConfigBuilder;
+    /// use pam_args::ParserConfig;
     ///
-    /// let config = ParserConfigBuilder::new()
-    ///     .case_sensitive(false)
-    ///     .collect_non_argument_text(true)
-    ///     .build();
+    /// let config = ParserConfig::new();
+    /// assert!(!config.validate_key_names());
+    /// ```
+    pub fn validate_key_names(&self) -> bool {
+        self.validate_key_names
+    }
+    
+    /// Returns the maximum length in bytes of a single argument, if limited
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.max_arg_length(), None);
+    /// ```
+    pub fn max_arg_length(&self) -> Option<usize> {
+        self.max_arg_length
+    }
+    
+    /// Returns whether invisible formatting characters are removed from arguments before parsing
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert!(!config.strip_invisible_chars());
+    /// ```
+    pub fn strip_invisible_chars(&self) -> bool {
+        self.strip_invisible_chars
+    }
+    
+    /// Creates a configuration for parsing untrusted input
+    ///
+    /// Starts from the defaults and enables:
+    ///
+    /// * `validate_key_names`: keys given with a value must be valid names
+    /// * `max_arg_length`: arguments are limited to 1024 bytes
+    /// * `strip_invisible_chars`: invisible formatting characters are removed
+    /// * `collect_non_argument_text` and `enable_multi_key_value` off, so
+    ///   every unrecognized argument is an `UnrecognizedArg` error
+    /// * `allow_nested_brackets` off
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::strict_secure();
+    /// assert!(config.validate_key_names());
+    /// assert_eq!(config.max_arg_length(), Some(1024));
+    /// ```
+    pub fn strict_secure() -> Self {
+        ParserConfigBuilder::new()
+            .validate_key_names(true)
+            .max_arg_length(Some(1024))
+            .strip_invisible_chars(true)
+            .collect_non_argument_text(false)
+            .enable_multi_key_value(false)
+            .allow_nested_brackets(false)
+            .build()
+    }
+    
+    /// Returns whether values are lowercased when they are stored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert!(!config.lowercase_values());
+    /// ```
+    pub fn lowercase_values(&self) -> bool {
+        self.lowercase_values
+    }
+    
+    /// Returns how a key repeated within one bracketed group is handled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{BracketDuplicatePolicy, ParserConfig};
+    ///
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.bracket_duplicate_policy(), BracketDuplicatePolicy::Overwrite);
+    /// ```
+    pub fn bracket_duplicate_policy(&self) -> BracketDuplicatePolicy {
+        self.bracket_duplicate_policy
+    }
+    
+    /// Returns the character starting a comment line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::new();
+    /// assert_eq!(config.comment_prefix(), '#');
+    /// ```
+    pub fn comment_prefix(&self) -> char {
+        self.comment_prefix
+    }
+    
+    /// Returns a builder for creating a new configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfig;
+    ///
+    /// let config = ParserConfig::builder()
+    ///     .case_sensitive(false)
+    ///     .collect_non_argument_text(true)
+    ///     .build();
+    /// ```
+    pub fn builder() -> ParserConfigBuilder {
+        ParserConfigBuilder::new()
+    }
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            case_sensitive_values: true,
+            collect_non_argument_text: false,
+            enable_multi_key_value: false,
+            multi_key_value_formats: vec![AllowedKeyValueFormats::KeyValue],
+            escape_char: '\\',
+            single_quote: '\'',
+            double_quote: '"',
+            open_bracket: '[',
+            close_bracket: ']',
+            delimiter: ',',
+            trim_values: true,
+            conversion: ConverterConfig::default(),
+            trailing_delimiter_policy: TrailingDelimiterPolicy::KeepEmpty,
+            retain_comments: false,
+            required_implies_dependency_check: false,
+            capture_module_name: false,
+            enable_value_templating: false,
+            require_multi_key_value_for_brackets: false,
+            strict_definitions: false,
+            help_width: 80,
+            allow_nested_brackets: false,
+            error_context: false,
+            join_adjacent_non_arg: None,
+            key_normalizer: None,
+            require_brackets_for_multi: false,
+            allow_quoted_keys: false,
+            collect_conversion_errors: false,
+            validate_key_names: false,
+            max_arg_length: None,
+            strip_invisible_chars: false,
+            lowercase_values: false,
+            bracket_duplicate_policy: BracketDuplicatePolicy::Overwrite,
+            comment_prefix: '#',
+        }
+    }
+}
+
+impl ParserConfigBuilder {
+    /// Creates a new parser configuration builder with default settings
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new();
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            config: ParserConfig::default(),
+        }
+    }
+    
+    /// Sets whether argument names are case-sensitive
+    ///
+    /// # Arguments
+    ///
+    /// * `case_sensitive` - Whether argument names are case-sensitive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .case_sensitive(false);
+    /// ```
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.config.case_sensitive = case_sensitive;
+        self
+    }
+    
+    /// Sets whether argument values are case-sensitive when validating against allowed values
+    ///
+    /// This is separate from `case_sensitive()` which controls argument name matching.
+    /// When set to false, values like "LEFT" would match "left" during allowed values validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `case_sensitive_values` - Whether argument values are case-sensitive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .case_sensitive_values(false);
+    /// ```
+    pub fn case_sensitive_values(mut self, case_sensitive_values: bool) -> Self {
+        self.config.case_sensitive_values = case_sensitive_values;
+        self
+    }
+    
+    /// Sets whether non-argument text is collected
+    ///
+    /// # Arguments
+    ///
+    /// * `collect_non_argument_text` - Whether non-argument text is collected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .collect_non_argument_text(true);
+    /// ```
+    pub fn collect_non_argument_text(mut self, collect_non_argument_text: bool) -> Self {
+        self.config.collect_non_argument_text = collect_non_argument_text;
+        self
+    }
+    
+    /// Sets whether multi key-value pairs are enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `enable_multi_key_value` - Whether multi key-value pairs are enabled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .enable_multi_key_value(true);
+    /// ```
+    pub fn enable_multi_key_value(mut self, enable_multi_key_value: bool) -> Self {
+        self.config.enable_multi_key_value = enable_multi_key_value;
+        self
+    }
+    
+    /// Sets the allowed formats for multi key-value pairs
+    ///
+    /// Multiple formats can be combined to support different key-value representations.
+    /// The formats will be checked in the order they are specified.
+    ///
+    /// # Arguments
+    ///
+    /// * `formats` - Slice of allowed formats
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ParserConfigBuilder, AllowedKeyValueFormats};
+    ///
+    /// // Allow three different formats:
+    /// // - KEY=VALUE (e.g., "USER=admin")
+    /// // - KEY (e.g., "DEBUG" with no value)
+    /// // - KEY= (e.g., "EMPTY=" with empty value)
+    /// let builder = ParserConfigBuilder::new()
+    ///     .multi_key_value_formats(&[
+    ///         AllowedKeyValueFormats::KeyValue,
+    ///         AllowedKeyValueFormats::KeyOnly,
+    ///         AllowedKeyValueFormats::KeyEquals,
+    ///     ]);
+    ///     
+    /// // Alternatively, use KeyAll as a shorthand for all formats:
+    /// let builder = ParserConfigBuilder::new()
+    ///     .multi_key_value_formats(&[AllowedKeyValueFormats::KeyAll]);
+    /// ```
+    pub fn multi_key_value_formats(mut self, formats: &[AllowedKeyValueFormats]) -> Self {
+        self.config.multi_key_value_formats = formats.to_vec();
+        self
+    }
+    
+    /// Sets the escape character
+    ///
+    /// The escape character is also used when values are converted, for
+    /// example by `String` with `interpret_escapes`, so it is copied into
+    /// the conversion configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `escape_char` - The escape character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .escape_char('$');
+    /// ```
+    pub fn escape_char(mut self, escape_char: char) -> Self {
+        self.config.escape_char = escape_char;
+        self.config.conversion.escape_char = escape_char;
+        self
+    }
+    
+    /// Sets the quote characters
+    ///
+    /// The quotes are also recognized in list elements during conversion, so
+    /// they are copied into the conversion configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `single_quote` - The single quote character
+    /// * `double_quote` - The double quote character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .quote_chars('\'', '"');
+    /// ```
+    pub fn quote_chars(mut self, single_quote: char, double_quote: char) -> Self {
+        self.config.single_quote = single_quote;
+        self.config.double_quote = double_quote;
+        self.config.conversion.single_quote = single_quote;
+        self.config.conversion.double_quote = double_quote;
+        self
+    }
+    
+    /// Sets the bracket characters
+    ///
+    /// # Arguments
+    ///
+    /// * `open_bracket` - The opening bracket character
+    /// * `close_bracket` - The closing bracket character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .bracket_chars('<', '>');
+    /// ```
+    pub fn bracket_chars(mut self, open_bracket: char, close_bracket: char) -> Self {
+        self.config.open_bracket = open_bracket;
+        self.config.close_bracket = close_bracket;
+        self
+    }
+    
+    /// Sets the delimiter character
+    ///
+    /// The delimiter also separates elements when a value is converted to a
+    /// list, so it is copied into the conversion configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The delimiter character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .delimiter(';');
+    /// ```
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.config.delimiter = delimiter;
+        self.config.conversion.delimiter = delimiter;
+        self
+    }
+    
+    /// Sets whether values are trimmed
+    ///
+    /// This also trims each element of a bracketed group, so `[ DEBUG , USER=admin ]`
+    /// is read as `DEBUG` and `USER=admin`. Whitespace inside quotes is kept.
+    /// When disabled, whitespace around an element stays part of its value,
+    /// so `[USER=admin ]` stores `admin ` for `USER`.
+    ///
+    /// # Arguments
+    ///
+    /// * `trim_values` - Whether values are trimmed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .trim_values(false);
+    /// ```
+    pub fn trim_values(mut self, trim_values: bool) -> Self {
+        self.config.trim_values = trim_values;
+        self
+    }
+    
+    /// Sets the conversion settings used for typed value lookups
+    ///
+    /// The delimiter, escape and quote characters set afterwards with their
+    /// own builder methods are copied into these settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `conversion` - The converter configuration to share across lookups
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ConverterConfig, ParserConfigBuilder};
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .conversion(ConverterConfig {
+    ///         recognize_none_values: false,
+    ///         ..ConverterConfig::default()
+    ///     });
+    /// ```
+    pub fn conversion(mut self, conversion: ConverterConfig) -> Self {
+        self.config.conversion = conversion;
+        self
+    }
+    
+    /// Sets the values accepted as `true` and `false` for booleans
+    ///
+    /// The lists replace the defaults (`true`, `yes`, `1`, `on` and `false`,
+    /// `no`, `0`, `off`) and are compared without regard to case.
+    ///
+    /// # Arguments
+    ///
+    /// * `true_values` - Values read as `true`
+    /// * `false_values` - Values read as `false`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .bool_values(&["oui", "1"], &["non", "0"]);
+    /// ```
+    pub fn bool_values<S: AsRef<str>>(mut self, true_values: &[S], false_values: &[S]) -> Self {
+        self.config.conversion.true_values = true_values.iter().map(|v| v.as_ref().to_string()).collect();
+        self.config.conversion.false_values = false_values.iter().map(|v| v.as_ref().to_string()).collect();
+        self
+    }
+    
+    /// Sets how a trailing delimiter in bracketed content is handled
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Whether to keep an empty token, drop it, or reject the argument
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ParserConfigBuilder, TrailingDelimiterPolicy};
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .trailing_delimiter_policy(TrailingDelimiterPolicy::Drop);
+    /// ```
+    pub fn trailing_delimiter_policy(mut self, policy: TrailingDelimiterPolicy) -> Self {
+        self.config.trailing_delimiter_policy = policy;
+        self
+    }
+    
+    /// Sets whether comment lines are retained when parsing a reader
+    ///
+    /// # Arguments
+    ///
+    /// * `retain_comments` - Whether comment lines are kept in the parse result
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .retain_comments(true);
+    /// ```
+    pub fn retain_comments(mut self, retain_comments: bool) -> Self {
+        self.config.retain_comments = retain_comments;
+        self
+    }
+    
+    /// Sets whether dependency errors take precedence over missing required arguments
+    ///
+    /// When a required argument is missing and so is one of its dependencies,
+    /// the parser reports `RequiredArgMissing` by default. With this set, the
+    /// required argument's dependencies are checked first and
+    /// `DependencyNotMet` is reported instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether dependencies of required arguments are checked first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .required_implies_dependency_check(true);
+    /// ```
+    pub fn required_implies_dependency_check(mut self, enabled: bool) -> Self {
+        self.config.required_implies_dependency_check = enabled;
+        self
+    }
+    
+    /// Sets whether the leading token of a parsed line is captured as the module name
+    ///
+    /// This suits full module lines such as `pam_example.so DEBUG USER=admin`,
+    /// where the first token names the module rather than an argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `capture_module_name` - Whether `parse_line` captures the leading token
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .capture_module_name(true);
+    /// ```
+    pub fn capture_module_name(mut self, capture_module_name: bool) -> Self {
+        self.config.capture_module_name = capture_module_name;
+        self
+    }
+    
+    /// Sets whether `${KEY}` references in values are expanded
+    ///
+    /// Expansion runs once all arguments are parsed, so a value may refer to
+    /// keys given after it, e.g. `URL=https://${HOST}:${PORT}`. A reference
+    /// to a key that was not given with a value is an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable_value_templating` - Whether to expand references in values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .enable_value_templating(true);
+    /// ```
+    pub fn enable_value_templating(mut self, enable_value_templating: bool) -> Self {
+        self.config.enable_value_templating = enable_value_templating;
+        self
+    }
+    
+    /// Sets whether bracketed groups require multi key-value pairs to be enabled
+    ///
+    /// By default a bracketed argument such as `[DEBUG,USER=admin]` is always
+    /// expanded into its parts. When this is set and `enable_multi_key_value`
+    /// is not, bracketed content is rejected instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `require_multi_key_value_for_brackets` - Whether to reject bracketed groups
+    ///   while multi key-value pairs are disabled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .require_multi_key_value_for_brackets(true);
+    /// ```
+    pub fn require_multi_key_value_for_brackets(mut self, require_multi_key_value_for_brackets: bool) -> Self {
+        self.config.require_multi_key_value_for_brackets = require_multi_key_value_for_brackets;
+        self
+    }
+    
+    /// Sets whether contradictory argument definitions are errors
+    ///
+    /// Some definitions are accepted but cannot behave as written, such as a
+    /// required key-value pair with a default value, which can never be
+    /// missing. They are logged as warnings unless this is set, in which case
+    /// parsing fails with `Error::InvalidInput`.
+    ///
+    /// # Arguments
+    ///
+    /// * `strict_definitions` - Whether contradictory definitions are errors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .strict_definitions(true);
+    /// ```
+    pub fn strict_definitions(mut self, strict_definitions: bool) -> Self {
+        self.config.strict_definitions = strict_definitions;
+        self
+    }
+    
+    /// Sets the maximum line width of generated help text
+    ///
+    /// Descriptions are wrapped to fit. Lines may still exceed the width when
+    /// an argument label or a single word is too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The maximum line width in characters
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .help_width(100);
+    /// ```
+    pub fn help_width(mut self, width: usize) -> Self {
+        self.config.help_width = width;
+        self
+    }
+    
+    /// Sets whether brackets may nest inside bracketed arguments
+    ///
+    /// When set, `[OUTER=[a,b],X=1]` yields the tokens `OUTER=[a,b]` and
+    /// `X=1`: only the outermost brackets are split, and inner bracketed
+    /// content is kept verbatim. Otherwise nested brackets are rejected with
+    /// `Error::NestedBrackets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow_nested_brackets` - Whether brackets may nest
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .allow_nested_brackets(true);
+    /// ```
+    pub fn allow_nested_brackets(mut self, allow_nested_brackets: bool) -> Self {
+        self.config.allow_nested_brackets = allow_nested_brackets;
+        self
+    }
+    
+    /// Sets whether parse errors carry the arguments being parsed
+    ///
+    /// When set, every error returned by `ArgumentParser::parse` is wrapped with
+    /// `Error::with_context`, so it displays the full argument vector alongside
+    /// the cause. Use `Error::cause` to match on the original error.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_context` - Whether parse errors carry the arguments
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .error_context(true);
+    /// ```
+    pub fn error_context(mut self, error_context: bool) -> Self {
+        self.config.error_context = error_context;
+        self
+    }
+    
+    /// Sets the separator used to join adjacent non-argument text
+    ///
+    /// When set, consecutive non-argument tokens are joined with the separator
+    /// into a single entry, so `Hello world` becomes `"Hello world"` instead
+    /// of two entries. Text separated by a recognized argument stays in
+    /// separate entries. Only applies when non-argument text is collected.
+    ///
+    /// # Arguments
+    ///
+    /// * `join_adjacent_non_arg` - The separator, or None to keep tokens separate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .join_adjacent_non_arg(Some(' '));
+    /// ```
+    pub fn join_adjacent_non_arg(mut self, join_adjacent_non_arg: Option<char>) -> Self {
+        self.config.join_adjacent_non_arg = join_adjacent_non_arg;
+        self
+    }
+    
+    /// Sets a function canonicalizing argument names
+    ///
+    /// The function is applied to registered names, aliases and keys in the
+    /// input before they are compared or stored, and replaces the case
+    /// normalization of `case_sensitive`. Use it to strip site prefixes or
+    /// map legacy names onto current ones. It must give the same result for
+    /// names that should refer to the same argument; duplicate detection
+    /// at registration uses it too.
+    ///
+    /// Lookups on the parse result are normalized the same way, so both the
+    /// legacy and the current name find the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `normalizer` - Function mapping a name to its canonical form
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let builder = ParserConfigBuilder::new()
+    ///     .key_normalizer(|name| name.trim_start_matches("legacy_").to_uppercase());
+    /// ```
+    pub fn key_normalizer<F>(mut self, normalizer: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.config.key_normalizer = Some(Normalizer(Arc::new(normalizer)));
+        self
+    }
+    
+    /// Sets whether several multi key-value pairs in one argument must be bracketed
+    ///
+    /// When set, `[A=1,B=2]` is accepted but a bare `A=1,B=2` is rejected with
+    /// `Error::InvalidKeyValue` instead of storing `1,B=2` as the value of `A`.
+    /// A single bare pair such as `A=1` is still accepted. Only unregistered
+    /// keys are checked, since a registered key's value may contain the
+    /// delimiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `require_brackets_for_multi` - Whether grouped pairs must be bracketed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .require_brackets_for_multi(true);
+    /// ```
+    pub fn require_brackets_for_multi(mut self, require_brackets_for_multi: bool) -> Self {
+        self.config.require_brackets_for_multi = require_brackets_for_multi;
+        self
+    }
+    
+    /// Sets whether the key of a key-value pair may be quoted
+    ///
+    /// When set, a key enclosed in the configured quote characters, as in
+    /// `"my key"=value`, is unquoted before it is matched or stored, so it
+    /// may contain spaces and `=`. Quoted keys of multi key-value pairs skip
+    /// the key name check.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow_quoted_keys` - Whether keys may be quoted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .allow_quoted_keys(true);
+    /// ```
+    pub fn allow_quoted_keys(mut self, allow_quoted_keys: bool) -> Self {
+        self.config.allow_quoted_keys = allow_quoted_keys;
+        self
+    }
+    
+    /// Sets whether type converter failures are collected instead of failing the parse
+    ///
+    /// By default a value rejected by its key's `KeyValue::type_converter`
+    /// fails the parse. When set, the parse succeeds, the key has no typed
+    /// value, and the failure is reported by `ParseResult::conversion_errors`.
+    ///
+    /// # Arguments
+    ///
+    /// * `collect_conversion_errors` - Whether converter failures are collected
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .collect_conversion_errors(true);
+    /// ```
+    pub fn collect_conversion_errors(mut self, collect_conversion_errors: bool) -> Self {
+        self.config.collect_conversion_errors = collect_conversion_errors;
+        self
+    }
+    
+    /// Sets whether unregistered keys given with a value must be valid key names
+    ///
+    /// When set, a `KEY=VALUE` or `KEY=` token whose key is not registered and
+    /// is not a valid key name (a letter or underscore followed by letters,
+    /// digits or underscores) is rejected with `Error::InvalidKeyValue`. This
+    /// includes an empty key, as in `=value` or `==`.
+    ///
+    /// When not set, a token with an empty key is stored under the empty key
+    /// `""`, so `=value` gives the value `value` and `==` the value `=`;
+    /// other unregistered keys are handled as before.
+    ///
+    /// # Arguments
+    ///
+    /// * `validate_key_names` - Whether key names are validated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .validate_key_names(true);
+    /// ```
+    pub fn validate_key_names(mut self, validate_key_names: bool) -> Self {
+        self.config.validate_key_names = validate_key_names;
+        self
+    }
+    
+    /// Sets the maximum length in bytes of a single argument
+    ///
+    /// Longer arguments are rejected with `Error::InvalidInput` before they are
+    /// tokenized. The error does not include the argument, which may hold a
+    /// secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_arg_length` - The maximum length, or None for no limit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .max_arg_length(Some(1024));
+    /// ```
+    pub fn max_arg_length(mut self, max_arg_length: Option<usize>) -> Self {
+        self.config.max_arg_length = max_arg_length;
+        self
+    }
+    
+    /// Sets whether invisible formatting characters are removed from arguments before parsing
+    ///
+    /// Zero-width characters, the byte order mark, soft hyphens and
+    /// bidirectional controls render as nothing, so `DEBUG` with a zero-width
+    /// space inside looks like the flag but does not match it. When set, these
+    /// characters are removed from each argument before it is tokenized.
+    ///
+    /// # Arguments
+    ///
+    /// * `strip_invisible_chars` - Whether invisible characters are removed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .strip_invisible_chars(true);
+    /// ```
+    pub fn strip_invisible_chars(mut self, strip_invisible_chars: bool) -> Self {
+        self.config.strip_invisible_chars = strip_invisible_chars;
+        self
+    }
+    
+    /// Sets whether values are lowercased when they are stored
+    ///
+    /// When set, every value and default value in the parse result is
+    /// lowercased, while keys keep their case; useful for values such as
+    /// hostnames. Allowed values are checked before lowercasing.
+    ///
+    /// This differs from `case_sensitive_values`, which only changes how a
+    /// value is compared against its allowed values and never changes the
+    /// stored value, and from `case_sensitive`, which applies to key names.
+    ///
+    /// # Arguments
+    ///
+    /// * `lowercase_values` - Whether values are lowercased
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .lowercase_values(true);
+    /// ```
+    pub fn lowercase_values(mut self, lowercase_values: bool) -> Self {
+        self.config.lowercase_values = lowercase_values;
+        self
+    }
+    
+    /// Sets how a key repeated within one bracketed group is handled
+    ///
+    /// This applies only to keys repeated inside the same group, such as
+    /// `[A=1,A=2]`, which are usually copy-paste mistakes. A key repeated across
+    /// arguments or groups, such as `A=1 [A=2]`, always overwrites. Keys are
+    /// compared after normalization, so a name and its alias are the same key.
+    ///
+    /// # Arguments
+    ///
+    /// * `bracket_duplicate_policy` - The policy for repeated keys in a group
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{BracketDuplicatePolicy, ParserConfigBuilder};
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .bracket_duplicate_policy(BracketDuplicatePolicy::Error);
+    /// ```
+    pub fn bracket_duplicate_policy(mut self, bracket_duplicate_policy: BracketDuplicatePolicy) -> Self {
+        self.config.bracket_duplicate_policy = bracket_duplicate_policy;
+        self
+    }
+    
+    /// Sets the character starting a comment line
+    ///
+    /// Lines whose first non-blank character is the prefix are skipped by
+    /// `ArgumentParser::parse_reader` and `lint_line`, and kept with
+    /// `retain_comments`.
+    ///
+    /// # Arguments
+    ///
+    /// * `comment_prefix` - The comment prefix
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    /// 
+    /// let builder = ParserConfigBuilder::new()
+    ///     .comment_prefix(';');
+    /// ```
+    pub fn comment_prefix(mut self, comment_prefix: char) -> Self {
+        self.config.comment_prefix = comment_prefix;
+        self
+    }
+    
+    /// Builds the configuration
+    ///
+    /// # Returns
+    ///
+    /// The built configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ParserConfigBuilder;
+    ///
+    /// let config = ParserConfigBuilder::new()
+    ///     .case_sensitive(false)
+    ///     .collect_non_argument_text(true)
+    ///     .build();
     /// ```
     pub fn build(self) -> ParserConfig {
         self.config
@@ -558,6 +1601,29 @@ mod tests {
         assert_eq!(config.close_bracket(), ']');
         assert_eq!(config.delimiter(), ',');
         assert!(config.trim_values());
+        assert!(config.conversion().trim_whitespace);
+        assert!(config.conversion().allow_radix_prefixes);
+        assert_eq!(config.trailing_delimiter_policy(), TrailingDelimiterPolicy::KeepEmpty);
+        assert!(!config.retain_comments());
+        assert!(!config.required_implies_dependency_check());
+        assert!(!config.capture_module_name());
+        assert!(!config.enable_value_templating());
+        assert!(!config.require_multi_key_value_for_brackets());
+        assert!(!config.strict_definitions());
+        assert_eq!(config.help_width(), 80);
+        assert!(!config.allow_nested_brackets());
+        assert!(!config.error_context());
+        assert_eq!(config.join_adjacent_non_arg(), None);
+        assert!(config.key_normalizer().is_none());
+        assert!(!config.require_brackets_for_multi());
+        assert!(!config.allow_quoted_keys());
+        assert!(!config.collect_conversion_errors());
+        assert!(!config.validate_key_names());
+        assert_eq!(config.max_arg_length(), None);
+        assert!(!config.strip_invisible_chars());
+        assert!(!config.lowercase_values());
+        assert_eq!(config.bracket_duplicate_policy(), BracketDuplicatePolicy::Overwrite);
+        assert_eq!(config.comment_prefix(), '#');
     }
 
     #[test]
@@ -646,6 +1712,7 @@ mod tests {
             .delimiter(';')
             .build();
         assert_eq!(config.delimiter(), ';');
+        assert_eq!(config.conversion().delimiter, ';');
     }
 
     #[test]
@@ -656,6 +1723,52 @@ mod tests {
         assert!(!config.trim_values());
     }
 
+    #[test]
+    fn test_conversion_settings() {
+        let config = ParserConfigBuilder::new()
+            .conversion(ConverterConfig {
+                allow_radix_prefixes: false,
+                ..ConverterConfig::default()
+            })
+            .build();
+        assert!(!config.conversion().allow_radix_prefixes);
+        
+        let value = crate::conversion::converter::convert::<i32>("0x10", Some(config.conversion()));
+        assert!(value.is_err());
+    }
+
+    #[test]
+    fn test_bool_values() {
+        let config = ParserConfigBuilder::new()
+            .bool_values(&["ja"], &["nein"])
+            .build();
+        assert_eq!(config.conversion().true_values, ["ja"]);
+        assert_eq!(config.conversion().false_values, ["nein"]);
+        
+        let value = crate::conversion::converter::convert::<bool>("JA", Some(config.conversion()));
+        assert_eq!(value, Ok(true));
+        
+        let config = ParserConfigBuilder::new().build();
+        assert!(config.conversion().true_values.contains(&"yes".to_string()));
+    }
+
+    #[test]
+    fn test_strict_secure() {
+        let config = ParserConfig::strict_secure();
+        assert!(config.validate_key_names());
+        assert_eq!(config.max_arg_length(), Some(1024));
+        assert!(config.strip_invisible_chars());
+        assert!(!config.collect_non_argument_text());
+        assert!(!config.enable_multi_key_value());
+        assert!(!config.allow_nested_brackets());
+        
+        // Everything else keeps its default
+        let default = ParserConfig::default();
+        assert_eq!(config.is_case_sensitive(), default.is_case_sensitive());
+        assert_eq!(config.delimiter(), default.delimiter());
+        assert_eq!(config.trim_values(), default.trim_values());
+    }
+    
     #[test]
     fn test_method_chaining() {
         let config = ParserConfigBuilder::new()
@@ -669,6 +1782,27 @@ mod tests {
             .bracket_chars('<', '>')
             .delimiter(';')
             .trim_values(false)
+            .trailing_delimiter_policy(TrailingDelimiterPolicy::Error)
+            .retain_comments(true)
+            .required_implies_dependency_check(true)
+            .capture_module_name(true)
+            .enable_value_templating(true)
+            .require_multi_key_value_for_brackets(true)
+            .strict_definitions(true)
+            .help_width(100)
+            .allow_nested_brackets(true)
+            .error_context(true)
+            .join_adjacent_non_arg(Some(' '))
+            .key_normalizer(|name| name.to_uppercase())
+            .require_brackets_for_multi(true)
+            .allow_quoted_keys(true)
+            .collect_conversion_errors(true)
+            .validate_key_names(true)
+            .max_arg_length(Some(1024))
+            .strip_invisible_chars(true)
+            .lowercase_values(true)
+            .bracket_duplicate_policy(BracketDuplicatePolicy::Error)
+            .comment_prefix(';')
             .build();
         
         assert!(!config.is_case_sensitive());
@@ -679,10 +1813,33 @@ mod tests {
         assert_eq!(config.escape_char(), '$');
         assert_eq!(config.single_quote(), '`');
         assert_eq!(config.double_quote(), '"');
+        assert_eq!(config.conversion().escape_char, '$');
+        assert_eq!(config.conversion().single_quote, '`');
         assert_eq!(config.open_bracket(), '<');
         assert_eq!(config.close_bracket(), '>');
         assert_eq!(config.delimiter(), ';');
         assert!(!config.trim_values());
+        assert_eq!(config.trailing_delimiter_policy(), TrailingDelimiterPolicy::Error);
+        assert!(config.retain_comments());
+        assert!(config.required_implies_dependency_check());
+        assert!(config.capture_module_name());
+        assert!(config.enable_value_templating());
+        assert!(config.require_multi_key_value_for_brackets());
+        assert!(config.strict_definitions());
+        assert_eq!(config.help_width(), 100);
+        assert!(config.allow_nested_brackets());
+        assert!(config.error_context());
+        assert_eq!(config.join_adjacent_non_arg(), Some(' '));
+        assert_eq!(config.key_normalizer().map(|normalize| normalize("user")), Some("USER".to_string()));
+        assert!(config.require_brackets_for_multi());
+        assert!(config.allow_quoted_keys());
+        assert!(config.collect_conversion_errors());
+        assert!(config.validate_key_names());
+        assert_eq!(config.max_arg_length(), Some(1024));
+        assert!(config.strip_invisible_chars());
+        assert!(config.lowercase_values());
+        assert_eq!(config.bracket_duplicate_policy(), BracketDuplicatePolicy::Error);
+        assert_eq!(config.comment_prefix(), ';');
     }
 
     #[test]
@@ -6,10 +6,15 @@
 //! sensible defaults, and extension points for advanced use cases.
 
 use std::any::Any;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::path::PathBuf;
+use std::time::Duration;
 use std::str::FromStr;
 use std::fmt;
 use crate::args::AllowedKeyValueFormats;
 use crate::error::{Error, Result};
+use crate::utils::{escaping, strings, TextProcessingConfig};
 
 /// Result of key-value format detection
 #[derive(Debug, Clone, PartialEq)]
@@ -38,6 +43,9 @@ pub(crate) struct ConversionError {
 }
 
 /// Trait for types that can be parsed from a string
+///
+/// Implementors provide `from_arg_value_with_config`; `from_arg_value` is a
+/// convenience that converts with the default configuration.
 pub trait FromArgValue: Sized {
     /// Converts a string to this type
     ///
@@ -49,7 +57,51 @@ pub trait FromArgValue: Sized {
     /// # Returns
     ///
     /// The converted value or an error
-    fn from_arg_value(value: &str, config: Option<&ConverterConfig>) -> Result<Self>;
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self>;
+    
+    /// Converts a string to this type using the default configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string value to convert
+    ///
+    /// # Returns
+    ///
+    /// The converted value or an error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::FromArgValue;
+    ///
+    /// assert_eq!(i32::from_arg_value("80").unwrap(), 80);
+    /// assert!(bool::from_arg_value("yes").unwrap());
+    /// ```
+    fn from_arg_value(value: &str) -> Result<Self> {
+        Self::from_arg_value_with_config(value, None)
+    }
+    
+    /// Converts a string to this type, knowing which key it belongs to
+    ///
+    /// The default delegates to `from_arg_value_with_config`. Implementors can
+    /// override it to produce errors that name the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string value to convert
+    /// * `key` - The name of the key the value was given for
+    /// * `config` - Optional configuration for the conversion
+    ///
+    /// # Returns
+    ///
+    /// The converted value or an error
+    fn from_arg_value_for_key(
+        value: &str,
+        _key: &str,
+        config: Option<&ConverterConfig>,
+    ) -> Result<Self> {
+        Self::from_arg_value_with_config(value, config)
+    }
 }
 
 /// Configuration for type converters
@@ -61,8 +113,51 @@ pub struct ConverterConfig {
     /// Whether to handle empty strings specially
     pub handle_empty: bool,
     
-    /// Whether to treat "none" and "null" as None for Option types
+    /// Whether to treat the `none_values` such as "none" and "null" as None for Option types
     pub recognize_none_values: bool,
+    
+    /// Delimiter separating elements of collection types such as `HashSet<T>`
+    pub delimiter: char,
+    
+    /// Whether integers may use `0x`, `0o` or `0b` radix prefixes (default: true)
+    pub allow_radix_prefixes: bool,
+    
+    /// Character used as the decimal point when parsing floats
+    pub decimal_separator: char,
+    
+    /// Separator between the two halves of a pair such as `name:value`
+    pub pair_separator: char,
+    
+    /// Whether `PathBuf` values must name a path that exists on disk
+    pub require_existing_path: bool,
+    
+    /// Whether a `Duration` given as a bare number is read as seconds
+    pub bare_duration_as_seconds: bool,
+    
+    /// Whether enum values declared with `arg_enum!` match regardless of case
+    pub case_insensitive_enums: bool,
+    
+    /// Whether `String` values interpret escapes such as `\n`, `\t` and `\r`
+    /// instead of keeping them as written
+    pub interpret_escapes: bool,
+    
+    /// Character that starts an escape sequence (default: '\\')
+    pub escape_char: char,
+    
+    /// Single quote character recognized in collection elements (default: '\'')
+    pub single_quote: char,
+    
+    /// Double quote character recognized in collection elements (default: '"')
+    pub double_quote: char,
+    
+    /// Values accepted as `true` for booleans, compared without case
+    pub true_values: Vec<String>,
+    
+    /// Values accepted as `false` for booleans, compared without case
+    pub false_values: Vec<String>,
+    
+    /// Values read as `None` for `Option` types, compared without case
+    pub none_values: Vec<String>,
 }
 
 /// Static configuration for the conversion system
@@ -84,10 +179,78 @@ pub(crate) struct ConversionConfig {
 /// Default implementation of the converter configuration
 impl Default for ConverterConfig {
     fn default() -> Self {
+        let defaults = ConversionConfig::default();
+        let owned = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
         Self {
             trim_whitespace: true,
             handle_empty: true,
             recognize_none_values: true,
+            delimiter: ',',
+            allow_radix_prefixes: true,
+            decimal_separator: '.',
+            pair_separator: ':',
+            require_existing_path: false,
+            bare_duration_as_seconds: false,
+            case_insensitive_enums: false,
+            interpret_escapes: false,
+            escape_char: '\\',
+            single_quote: '\'',
+            double_quote: '"',
+            true_values: owned(&defaults.true_values),
+            false_values: owned(&defaults.false_values),
+            none_values: owned(&defaults.none_values),
+        }
+    }
+}
+
+impl ConverterConfig {
+    /// Checks that the configuration is internally consistent
+    ///
+    /// The collection delimiter and the decimal separator must differ,
+    /// otherwise a list of floats such as `1,5,2,5` would be ambiguous.
+    /// Likewise the delimiter and the pair separator must differ so that a
+    /// list of pairs such as `a:1,b:2` can be split.
+    ///
+    /// # Returns
+    ///
+    /// Ok if the configuration is consistent, or an error describing the clash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ConverterConfig;
+    ///
+    /// let config = ConverterConfig {
+    ///     decimal_separator: ',',
+    ///     ..ConverterConfig::default()
+    /// };
+    /// assert!(config.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        if self.delimiter == self.decimal_separator {
+            return Err(Error::InvalidInput(format!(
+                "The delimiter and the decimal separator must differ, both are '{}'",
+                self.delimiter
+            )));
+        }
+        if self.delimiter == self.pair_separator {
+            return Err(Error::InvalidInput(format!(
+                "The delimiter and the pair separator must differ, both are '{}'",
+                self.delimiter
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Returns the text processing settings matching this configuration
+    ///
+    /// Brackets play no part in conversion, so they keep their defaults.
+    pub(crate) fn text_config(&self) -> TextProcessingConfig {
+        TextProcessingConfig {
+            escape_char: self.escape_char,
+            single_quote: self.single_quote,
+            double_quote: self.double_quote,
+            ..TextProcessingConfig::default()
         }
     }
 }
@@ -109,8 +272,11 @@ impl fmt::Display for ConverterConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "ConverterConfig:\n  trim_whitespace: {}\n  handle_empty: {}\n  recognize_none_values: {}",
-            self.trim_whitespace, self.handle_empty, self.recognize_none_values
+            "ConverterConfig:\n  trim_whitespace: {}\n  handle_empty: {}\n  recognize_none_values: {}\n  delimiter: {:?}\n  allow_radix_prefixes: {}\n  decimal_separator: {:?}\n  pair_separator: {:?}\n  require_existing_path: {}\n  bare_duration_as_seconds: {}\n  case_insensitive_enums: {}\n  interpret_escapes: {}\n  escape_char: {:?}\n  single_quote: {:?}\n  double_quote: {:?}\n  true_values: {:?}\n  false_values: {:?}\n  none_values: {:?}",
+            self.trim_whitespace, self.handle_empty, self.recognize_none_values, self.delimiter,
+            self.allow_radix_prefixes, self.decimal_separator, self.pair_separator,
+            self.require_existing_path, self.bare_duration_as_seconds, self.case_insensitive_enums,
+            self.interpret_escapes, self.escape_char, self.single_quote, self.double_quote, self.true_values, self.false_values, self.none_values
         )
     }
 }
@@ -207,48 +373,102 @@ pub(crate) mod format {
         if detected.format.is_compatible_with_any(allowed_formats) {
             Ok(())
         } else {
+            let allowed: Vec<String> = allowed_formats.iter().map(|f| f.to_string()).collect();
             Err(Error::InvalidKeyValue(format!(
-                "Invalid format for key '{}': expected one of {:?}, got {:?}",
-                detected.key, allowed_formats, detected.format
+                "Invalid format for key '{}': given as {}, but only {} is allowed",
+                detected.key, detected.format, allowed.join(", ")
             )))
         }
     }
 }
 
 /// Implementation of FromArgValue for common types
+///
+/// Strings are returned as written unless `interpret_escapes` is set, in which
+/// case escape sequences are replaced by the characters they stand for and an
+/// unknown escape is reported as `Error::InvalidInput`. Escapes start with
+/// the configured `escape_char`.
 impl FromArgValue for String {
-    fn from_arg_value(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
-        Ok(value.to_string())
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        match config {
+            Some(config) if config.interpret_escapes => escaping::unescape(value, &config.text_config()),
+            _ => Ok(value.to_string()),
+        }
     }
 }
 
-impl FromArgValue for i32 {
-    fn from_arg_value(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
-        value.parse::<i32>().map_err(|_| {
-            Error::InvalidIntValue(value.to_string())
-        })
+/// Implementation of FromArgValue for integer types
+///
+/// Out-of-range values, including negative values for unsigned types, are
+/// reported as `Error::InvalidIntValue` like any other invalid integer.
+/// Unless `allow_radix_prefixes` is turned off, `0x`, `0o` and `0b` prefixes
+/// select hexadecimal, octal and binary; a plain leading zero as in `010`
+/// is still decimal.
+macro_rules! impl_from_arg_value_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl FromArgValue for $t {
+                fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+                    let parsed = if config.map_or(true, |c| c.allow_radix_prefixes) {
+                        parse_radix::<$t>(value)
+                    } else {
+                        value.parse::<$t>().ok()
+                    };
+                    
+                    parsed.ok_or_else(|| Error::InvalidIntValue(value.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg_value_for_int!(i32, u16, u32, i64, u64, usize, isize);
+
+/// Parses an integer that may carry a `0x`, `0o` or `0b` prefix
+///
+/// An optional leading sign is accepted before the prefix. Without a
+/// prefix the value is parsed as base 10.
+fn parse_radix<T: TryFrom<i128>>(value: &str) -> Option<T> {
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    
+    let lower = unsigned.get(..2).map(|p| p.to_ascii_lowercase());
+    let (radix, digits) = match lower.as_deref() {
+        Some("0x") => (16, &unsigned[2..]),
+        Some("0o") => (8, &unsigned[2..]),
+        Some("0b") => (2, &unsigned[2..]),
+        _ => (10, unsigned),
+    };
+    
+    // Reject a second sign hidden after the prefix, e.g. "0x-1", and any
+    // minus sign on unsigned types, including "-0"
+    if digits.starts_with(['+', '-']) || (negative && T::try_from(-1).is_err()) {
+        return None;
     }
+    
+    // Parse as i128 so that every supported type's range, including the
+    // minimum of signed types, survives the sign being applied afterwards
+    let magnitude = i128::from_str_radix(digits, radix).ok()?;
+    let signed = if negative { -magnitude } else { magnitude };
+    T::try_from(signed).ok()
 }
 
 impl FromArgValue for bool {
-    fn from_arg_value(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
-        // Use the default conversion configuration
-        let conversion_config = ConversionConfig::default();
-        
-        // If case-insensitive, convert to lowercase for comparison
-        let compare_value = if conversion_config.case_insensitive_booleans {
-            value.to_lowercase()
-        } else {
-            value.to_string()
-        };
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        // Unwrap the configuration or use the default
+        let default_config = ConverterConfig::default();
+        let config = config.unwrap_or(&default_config);
+        let case_insensitive = ConversionConfig::default().case_insensitive_booleans;
         
         // Check if the value matches any true value
-        if conversion_config.true_values.iter().any(|v| *v == compare_value) {
+        if matches_any(value, &config.true_values, case_insensitive) {
             return Ok(true);
         }
         
         // Check if the value matches any false value
-        if conversion_config.false_values.iter().any(|v| *v == compare_value) {
+        if matches_any(value, &config.false_values, case_insensitive) {
             return Ok(false);
         }
         
@@ -257,8 +477,57 @@ impl FromArgValue for bool {
     }
 }
 
+/// Checks whether a value equals one of the given tokens
+fn matches_any(value: &str, tokens: &[String], case_insensitive: bool) -> bool {
+    if case_insensitive {
+        let value = value.to_lowercase();
+        tokens.iter().any(|token| token.to_lowercase() == value)
+    } else {
+        tokens.iter().any(|token| token == value)
+    }
+}
+
+/// Implementation of FromArgValue for floating-point types
+///
+/// Scientific notation (`1.5e3`) and infinities (`inf`, `-inf`, `infinity`)
+/// are accepted. `nan` is rejected: a NaN threshold or ratio compares false
+/// against everything, which silently disables whatever it configures.
+/// Empty strings are rejected.
+///
+/// The decimal point is the configured `decimal_separator`. With a custom
+/// separator a '.' is not a decimal point, so it is rejected rather than
+/// silently accepting e.g. thousands separators, and with the default
+/// separator a locale-style comma (`1,5`) is rejected.
+macro_rules! impl_from_arg_value_for_float {
+    ($($t:ty),*) => {
+        $(
+            impl FromArgValue for $t {
+                fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+                    let separator = config.map_or('.', |c| c.decimal_separator);
+                    let invalid = || Error::InvalidFloatValue(value.to_string());
+                    
+                    let normalized = if separator == '.' {
+                        value.to_string()
+                    } else if value.contains('.') {
+                        return Err(invalid());
+                    } else {
+                        value.replace(separator, ".")
+                    };
+                    
+                    match normalized.parse::<$t>() {
+                        Ok(parsed) if !parsed.is_nan() => Ok(parsed),
+                        _ => Err(invalid()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_arg_value_for_float!(f32, f64);
+
 impl FromArgValue for char {
-    fn from_arg_value(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
+    fn from_arg_value_with_config(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
         // A character must be exactly one character long
         let chars: Vec<char> = value.chars().collect();
         
@@ -276,7 +545,7 @@ impl FromArgValue for char {
 
 /// Implementation of FromArgValue for Option types
 impl<T: FromArgValue> FromArgValue for Option<T> {
-    fn from_arg_value(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
         // Unwrap the configuration or use the default
         let default_config = ConverterConfig::default();
         let config = config.unwrap_or(&default_config);
@@ -287,15 +556,12 @@ impl<T: FromArgValue> FromArgValue for Option<T> {
         }
         
         // Check for "none" and "null" values if configured to recognize them
-        if config.recognize_none_values {
-            let config = ConversionConfig::default();
-            if config.none_values.contains(&value.to_lowercase().as_str()) {
-                return Ok(None);
-            }
+        if config.recognize_none_values && matches_any(value, &config.none_values, true) {
+            return Ok(None);
         }
         
         // If the value is not None, convert it to the target type
-        match T::from_arg_value(value, Some(config)) {
+        match T::from_arg_value_with_config(value, Some(config)) {
             Ok(converted) => {
                 Ok(Some(converted))
             },
@@ -304,10 +570,177 @@ impl<T: FromArgValue> FromArgValue for Option<T> {
     }
 }
 
+/// Implementation of FromArgValue for sets
+///
+/// Elements are separated by the configured delimiter or by whitespace, and
+/// are split, trimmed and unquoted like the elements of `Vec<T>`, so a
+/// quoted element such as `'read only'` stays whole. Empty elements are
+/// skipped, duplicates collapse, and each element is converted individually
+/// so the first invalid element fails the whole set.
+impl<T: FromArgValue + Eq + Hash> FromArgValue for HashSet<T> {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        // Unwrap the configuration or use the default
+        let default_config = ConverterConfig::default();
+        let config = config.unwrap_or(&default_config);
+        
+        let text_config = config.text_config();
+        let mut set = HashSet::new();
+        for item in split_elements(value, config, &text_config)? {
+            for word in strings::split_whitespace(&item, &text_config)? {
+                let word = strip_enclosing_quotes(&word, &text_config);
+                if !word.is_empty() {
+                    set.insert(T::from_arg_value_with_config(word, Some(config))?);
+                }
+            }
+        }
+        Ok(set)
+    }
+}
+
+/// Implementation of FromArgValue for lists
+///
+/// Elements are separated by the configured delimiter, which follows
+/// `ParserConfig::delimiter` when the parser is built with
+/// `ParserConfigBuilder::delimiter`. Delimiters inside quotes or preceded by
+/// the escape character do not split, and quotes enclosing a whole element
+/// are removed. Empty input yields an empty vector, while a trailing
+/// delimiter produces a trailing empty element, so `"a,b,"` has three
+/// elements. The first element that fails to convert fails the whole list
+/// with that element's error.
+impl<T: FromArgValue> FromArgValue for Vec<T> {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        // Unwrap the configuration or use the default
+        let default_config = ConverterConfig::default();
+        let config = config.unwrap_or(&default_config);
+        
+        if value.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let text_config = config.text_config();
+        split_elements(value, config, &text_config)?
+            .iter()
+            .map(|item| {
+                let item = strip_enclosing_quotes(item, &text_config);
+                T::from_arg_value_with_config(item, Some(config))
+            })
+            .collect()
+    }
+}
+
+/// Implementation of FromArgValue for pairs
+///
+/// The value is split at the first occurrence of the configured pair
+/// separator, so `"uid:1000"` becomes `("uid", "1000")` and any later
+/// separators stay in the second half. Combined with `Vec<T>` this parses
+/// map-like values such as `MAP=a:1,b:2`.
+impl FromArgValue for (String, String) {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        // Unwrap the configuration or use the default
+        let default_config = ConverterConfig::default();
+        let config = config.unwrap_or(&default_config);
+        
+        match value.split_once(config.pair_separator) {
+            Some((first, second)) => Ok((first.to_string(), second.to_string())),
+            None => Err(Error::InvalidInput(format!(
+                "Expected a pair separated by '{}', got '{}'",
+                config.pair_separator, value
+            ))),
+        }
+    }
+}
+
+/// Implementation of FromArgValue for filesystem paths
+///
+/// Any non-empty value is accepted as-is. When `require_existing_path` is
+/// set the path must also exist on disk; this is the only conversion that
+/// touches the filesystem, so the check is off by default.
+impl FromArgValue for PathBuf {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        Self::from_arg_value_for_key(value, "path", config)
+    }
+    
+    fn from_arg_value_for_key(
+        value: &str,
+        key: &str,
+        config: Option<&ConverterConfig>,
+    ) -> Result<Self> {
+        if value.is_empty() {
+            return Err(Error::InvalidValue(key.to_string(), value.to_string()));
+        }
+        
+        let path = PathBuf::from(value);
+        if config.is_some_and(|c| c.require_existing_path) && !path.exists() {
+            return Err(Error::InvalidValue(key.to_string(), value.to_string()));
+        }
+        Ok(path)
+    }
+}
+
+/// Implementation of FromArgValue for durations
+///
+/// A duration is a number followed by one of the units `ms`, `s`, `m`, `h`
+/// or `d`, such as `500ms`, `2.5s` or `90m`. The number may be fractional and
+/// follows the configured decimal separator. A bare number is only accepted
+/// when `bare_duration_as_seconds` is set.
+impl FromArgValue for Duration {
+    fn from_arg_value_with_config(value: &str, config: Option<&ConverterConfig>) -> Result<Self> {
+        Self::from_arg_value_for_key(value, "duration", config)
+    }
+    
+    fn from_arg_value_for_key(
+        value: &str,
+        key: &str,
+        config: Option<&ConverterConfig>,
+    ) -> Result<Self> {
+        let invalid = || Error::InvalidValue(key.to_string(), value.to_string());
+        
+        let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let seconds_per_unit = match &value[number.len()..] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            "" if config.is_some_and(|c| c.bare_duration_as_seconds) => 1.0,
+            _ => return Err(invalid()),
+        };
+        
+        let amount = f64::from_arg_value_with_config(number, config).map_err(|_| invalid())?;
+        Duration::try_from_secs_f64(amount * seconds_per_unit).map_err(|_| invalid())
+    }
+}
+
+/// Splits a collection value on the configured delimiter
+///
+/// Delimiters inside quotes do not split, and each element is trimmed
+/// outside its quotes when `trim_whitespace` is set. Quotes are kept.
+fn split_elements(value: &str, config: &ConverterConfig, text_config: &TextProcessingConfig) -> Result<Vec<String>> {
+    let items = strings::smart_split(value, config.delimiter, text_config)?;
+    if !config.trim_whitespace {
+        return Ok(items);
+    }
+    Ok(items.iter().map(|item| strings::smart_trim(item, text_config)).collect())
+}
+
+/// Removes a matching pair of quotes surrounding the whole value
+fn strip_enclosing_quotes<'a>(value: &'a str, config: &TextProcessingConfig) -> &'a str {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next_back()) {
+        (Some(first), Some(last))
+            if first == last && (first == config.single_quote || first == config.double_quote) =>
+        {
+            chars.as_str()
+        }
+        _ => value,
+    }
+}
+
 /// Main type conversion functions
 pub mod converter {
     use super::*;
     use crate::error::{Error, Result};
+    use crate::logging::{LogComponent, LogOperation};
     use std::any::Any;
     use std::str::FromStr;
     
@@ -347,10 +780,14 @@ pub mod converter {
         // Use the provided config or the default
         let default_config = ConverterConfig::default();
         let config = config.unwrap_or(&default_config);
+        config.validate()?;
         
-        // Print the configuration details
-        println!("#DEBUG: default_config: {}", default_config.to_string());
-        println!("#DEBUG: config: {}", config.to_string());
+        crate::log_debug!(
+            LogComponent::General,
+            LogOperation::Config,
+            "Converting value with configuration",
+            config
+        );
 
         // Pre-process the value if needed
         let processed_value = if config.trim_whitespace {
@@ -360,7 +797,39 @@ pub mod converter {
         };
         
         // Perform the conversion
-        T::from_arg_value(processed_value, Some(config))
+        T::from_arg_value_with_config(processed_value, Some(config))
+    }
+    
+    /// Converts a string value given for a key to the specified type
+    ///
+    /// Identical to `convert`, but calls `FromArgValue::from_arg_value_for_key`
+    /// so that converters can report which key failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string value to convert
+    /// * `key` - The name of the key the value was given for
+    /// * `config` - Optional configuration for the conversion
+    ///
+    /// # Returns
+    ///
+    /// The converted value or an error
+    pub fn convert_for_key<T: FromArgValue>(
+        value: &str,
+        key: &str,
+        config: Option<&ConverterConfig>,
+    ) -> Result<T> {
+        let default_config = ConverterConfig::default();
+        let config = config.unwrap_or(&default_config);
+        config.validate()?;
+        
+        let processed_value = if config.trim_whitespace {
+            value.trim()
+        } else {
+            value
+        };
+        
+        T::from_arg_value_for_key(processed_value, key, Some(config))
     }
     
     /// Converts a string value to a boxed Any trait object
@@ -417,6 +886,74 @@ pub mod converter {
     pub fn from_str<T: FromStr + 'static>() -> fn(&str) -> std::result::Result<T, T::Err> {
         T::from_str
     }
+    
+    /// Largest number of values a single range may expand to
+    const MAX_RANGE_LEN: u64 = 65_536;
+    
+    /// Helper function to create a converter for comma-separated integer ranges
+    ///
+    /// Each element is either a single value or an inclusive range `start-end`,
+    /// so `"1000-1003,2000"` expands to `[1000, 1001, 1002, 1003, 2000]`.
+    /// Values keep their order and duplicates are kept. Empty input yields an
+    /// empty list.
+    ///
+    /// # Returns
+    ///
+    /// A function that expands a range expression into a list of integers
+    ///
+    /// # Errors
+    ///
+    /// The returned function fails with `Error::InvalidInput` for empty or
+    /// malformed elements, reversed ranges, and ranges of more than 65536 values
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::converter;
+    ///
+    /// let ports = converter::int_ranges()("1000-1003,2000").unwrap();
+    /// assert_eq!(ports, vec![1000, 1001, 1002, 1003, 2000]);
+    /// ```
+    pub fn int_ranges() -> fn(&str) -> Result<Vec<u32>> {
+        parse_int_ranges
+    }
+    
+    /// Expands a comma-separated list of integers and inclusive ranges
+    fn parse_int_ranges(value: &str) -> Result<Vec<u32>> {
+        let mut values = Vec::new();
+        if value.trim().is_empty() {
+            return Ok(values);
+        }
+        
+        let parse = |element: &str, part: &str| {
+            part.trim().parse::<u32>().map_err(|_| {
+                Error::InvalidInput(format!("Invalid range element '{}': '{}' is not an integer", element, part))
+            })
+        };
+        
+        for element in value.split(',') {
+            let element = element.trim();
+            match element.split_once('-') {
+                Some((start, end)) => {
+                    let (start, end) = (parse(element, start)?, parse(element, end)?);
+                    if start > end {
+                        return Err(Error::InvalidInput(format!(
+                            "Invalid range '{}': start is greater than end", element
+                        )));
+                    }
+                    if u64::from(end - start) >= MAX_RANGE_LEN {
+                        return Err(Error::InvalidInput(format!(
+                            "Invalid range '{}': ranges may hold at most {} values", element, MAX_RANGE_LEN
+                        )));
+                    }
+                    values.extend(start..=end);
+                },
+                None => values.push(parse(element, element)?),
+            }
+        }
+        
+        Ok(values)
+    }
 }
 
 #[cfg(test)]
@@ -464,63 +1001,459 @@ mod tests {
         assert!(format::validate(&result, &[AllowedKeyValueFormats::KeyAll]).is_ok());
     }
     
+    #[test]
+    fn test_format_validation_message() {
+        let result = format::detect("USER");
+        let allowed = [AllowedKeyValueFormats::KeyValue, AllowedKeyValueFormats::KeyEquals];
+        let message = match format::validate(&result, &allowed) {
+            Err(Error::InvalidKeyValue(message)) => message,
+            other => panic!("Expected InvalidKeyValue, got {:?}", other),
+        };
+        
+        assert!(message.contains("'USER'"));
+        assert!(message.contains("given as KEY,"));
+        assert!(message.contains("KEY=VALUE, KEY="));
+        assert!(!message.contains("KeyValue"));
+        assert!(!message.contains("KeyOnly"));
+        assert!(!message.contains("KeyEquals"));
+    }
+    
     #[test]
     fn test_string_conversion() {
-        assert_eq!(String::from_arg_value("hello", None).unwrap(), "hello");
-        assert_eq!(String::from_arg_value("", None).unwrap(), "");
-        assert_eq!(String::from_arg_value("  spaced  ", None).unwrap(), "  spaced  ");
+        assert_eq!(String::from_arg_value("hello").unwrap(), "hello");
+        assert_eq!(String::from_arg_value("").unwrap(), "");
+        assert_eq!(String::from_arg_value("  spaced  ").unwrap(), "  spaced  ");
+    }
+    
+    #[test]
+    fn test_string_escape_interpretation() {
+        assert_eq!(String::from_arg_value("Line1\\nLine2").unwrap(), "Line1\\nLine2");
+        
+        let config = ConverterConfig {
+            interpret_escapes: true,
+            ..ConverterConfig::default()
+        };
+        let convert = |value| String::from_arg_value_with_config(value, Some(&config));
+        assert_eq!(convert("Line1\\nLine2").unwrap(), "Line1\nLine2");
+        assert_eq!(convert("a\\tb\\rc").unwrap(), "a\tb\rc");
+        assert_eq!(convert("C:\\\\temp").unwrap(), "C:\\temp");
+        assert!(matches!(convert("bad\\q"), Err(Error::InvalidInput(_))));
     }
     
     #[test]
     fn test_integer_conversion() {
-        assert_eq!(i32::from_arg_value("123", None).unwrap(), 123);
-        assert_eq!(i32::from_arg_value("-123", None).unwrap(), -123);
-        assert_eq!(i32::from_arg_value("0", None).unwrap(), 0);
-        assert!(i32::from_arg_value("abc", None).is_err());
-        assert!(i32::from_arg_value("123.45", None).is_err());
+        assert_eq!(i32::from_arg_value("123").unwrap(), 123);
+        assert_eq!(i32::from_arg_value("-123").unwrap(), -123);
+        assert_eq!(i32::from_arg_value("0").unwrap(), 0);
+        assert!(i32::from_arg_value("abc").is_err());
+        assert!(i32::from_arg_value("123.45").is_err());
+    }
+    
+    #[test]
+    fn test_integer_family_conversion() {
+        assert_eq!(u16::from_arg_value("0").unwrap(), 0);
+        assert_eq!(u16::from_arg_value("65535").unwrap(), u16::MAX);
+        assert_eq!(u32::from_arg_value("4294967295").unwrap(), u32::MAX);
+        assert_eq!(i64::from_arg_value("-9223372036854775808").unwrap(), i64::MIN);
+        assert_eq!(i64::from_arg_value("9223372036854775807").unwrap(), i64::MAX);
+        assert_eq!(u64::from_arg_value("18446744073709551615").unwrap(), u64::MAX);
+        assert_eq!(usize::from_arg_value("4096").unwrap(), 4096);
+        assert_eq!(isize::from_arg_value("-4096").unwrap(), -4096);
+        
+        // Overflow and negatives for unsigned types are errors, not panics
+        let invalid = |result: Result<u64>, value: &str| {
+            assert_eq!(result, Err(Error::InvalidIntValue(value.to_string())));
+        };
+        invalid(u16::from_arg_value("70000").map(u64::from), "70000");
+        invalid(u16::from_arg_value("-1").map(u64::from), "-1");
+        invalid(u32::from_arg_value("4294967296").map(u64::from), "4294967296");
+        invalid(u64::from_arg_value("18446744073709551616"), "18446744073709551616");
+        invalid(u64::from_arg_value("-0"), "-0");
+        invalid(usize::from_arg_value("-5").map(|v| v as u64), "-5");
+        assert_eq!(
+            i64::from_arg_value("9223372036854775808"),
+            Err(Error::InvalidIntValue("9223372036854775808".to_string()))
+        );
+        assert_eq!(isize::from_arg_value("12abc"), Err(Error::InvalidIntValue("12abc".to_string())));
+        
+        // The converter trims whitespace before parsing
+        assert_eq!(converter::convert::<u16>(" 22 ", None).unwrap(), 22);
     }
     
     #[test]
     fn test_boolean_conversion() {
-        assert_eq!(bool::from_arg_value("true", None).unwrap(), true);
-        assert_eq!(bool::from_arg_value("yes", None).unwrap(), true);
-        assert_eq!(bool::from_arg_value("1", None).unwrap(), true);
-        assert_eq!(bool::from_arg_value("on", None).unwrap(), true);
+        assert_eq!(bool::from_arg_value("true").unwrap(), true);
+        assert_eq!(bool::from_arg_value("yes").unwrap(), true);
+        assert_eq!(bool::from_arg_value("1").unwrap(), true);
+        assert_eq!(bool::from_arg_value("on").unwrap(), true);
+        
+        assert_eq!(bool::from_arg_value("false").unwrap(), false);
+        assert_eq!(bool::from_arg_value("no").unwrap(), false);
+        assert_eq!(bool::from_arg_value("0").unwrap(), false);
+        assert_eq!(bool::from_arg_value("off").unwrap(), false);
+        
+        assert_eq!(bool::from_arg_value("TRUE").unwrap(), true);
+        assert_eq!(bool::from_arg_value("YES").unwrap(), true);
         
-        assert_eq!(bool::from_arg_value("false", None).unwrap(), false);
-        assert_eq!(bool::from_arg_value("no", None).unwrap(), false);
-        assert_eq!(bool::from_arg_value("0", None).unwrap(), false);
-        assert_eq!(bool::from_arg_value("off", None).unwrap(), false);
+        assert!(bool::from_arg_value("invalid").is_err());
+        assert!(bool::from_arg_value("").is_err());
+    }
+    
+    #[test]
+    fn test_custom_token_lists() {
+        let config = ConverterConfig {
+            true_values: vec!["oui".to_string(), "Ja".to_string()],
+            false_values: vec!["non".to_string(), "nein".to_string()],
+            none_values: vec!["aucun".to_string()],
+            ..ConverterConfig::default()
+        };
+        
+        assert!(bool::from_arg_value_with_config("oui", Some(&config)).unwrap());
+        assert!(bool::from_arg_value_with_config("ja", Some(&config)).unwrap());
+        assert!(!bool::from_arg_value_with_config("NEIN", Some(&config)).unwrap());
+        assert!(bool::from_arg_value_with_config("yes", Some(&config)).is_err());
         
-        assert_eq!(bool::from_arg_value("TRUE", None).unwrap(), true);
-        assert_eq!(bool::from_arg_value("YES", None).unwrap(), true);
+        assert_eq!(Option::<i32>::from_arg_value_with_config("Aucun", Some(&config)).unwrap(), None);
+        assert!(Option::<i32>::from_arg_value_with_config("none", Some(&config)).is_err());
         
-        assert!(bool::from_arg_value("invalid", None).is_err());
-        assert!(bool::from_arg_value("", None).is_err());
+        // Defaults still apply when the lists are not overridden
+        assert!(bool::from_arg_value_with_config("on", Some(&ConverterConfig::default())).unwrap());
+        assert!(bool::from_arg_value("oui").is_err());
     }
     
     #[test]
     fn test_character_conversion() {
-        assert_eq!(char::from_arg_value("a", None).unwrap(), 'a');
-        assert_eq!(char::from_arg_value("A", None).unwrap(), 'A');
-        assert_eq!(char::from_arg_value("1", None).unwrap(), '1');
-        assert_eq!(char::from_arg_value(" ", None).unwrap(), ' ');
+        assert_eq!(char::from_arg_value("a").unwrap(), 'a');
+        assert_eq!(char::from_arg_value("A").unwrap(), 'A');
+        assert_eq!(char::from_arg_value("1").unwrap(), '1');
+        assert_eq!(char::from_arg_value(" ").unwrap(), ' ');
         
-        assert!(char::from_arg_value("", None).is_err());
-        assert!(char::from_arg_value("ab", None).is_err());
+        assert!(char::from_arg_value("").is_err());
+        assert!(char::from_arg_value("ab").is_err());
     }
     
     #[test]
     fn test_option_conversion() {
-        assert_eq!(Option::<String>::from_arg_value("hello", None).unwrap(), Some("hello".to_string()));
-        assert_eq!(Option::<String>::from_arg_value("", None).unwrap(), None);
-        assert_eq!(Option::<String>::from_arg_value("none", None).unwrap(), None);
-        assert_eq!(Option::<String>::from_arg_value("null", None).unwrap(), None);
-        
-        assert_eq!(Option::<i32>::from_arg_value("123", None).unwrap(), Some(123));
-        assert_eq!(Option::<i32>::from_arg_value("", None).unwrap(), None);
-        assert_eq!(Option::<i32>::from_arg_value("none", None).unwrap(), None);
-        assert!(Option::<i32>::from_arg_value("invalid", None).is_err());
+        assert_eq!(Option::<String>::from_arg_value("hello").unwrap(), Some("hello".to_string()));
+        assert_eq!(Option::<String>::from_arg_value("").unwrap(), None);
+        assert_eq!(Option::<String>::from_arg_value("none").unwrap(), None);
+        assert_eq!(Option::<String>::from_arg_value("null").unwrap(), None);
+        
+        assert_eq!(Option::<i32>::from_arg_value("123").unwrap(), Some(123));
+        assert_eq!(Option::<i32>::from_arg_value("").unwrap(), None);
+        assert_eq!(Option::<i32>::from_arg_value("none").unwrap(), None);
+        assert!(Option::<i32>::from_arg_value("invalid").is_err());
+    }
+    
+    #[derive(Debug, PartialEq)]
+    struct Port(u16);
+    
+    impl FromArgValue for Port {
+        fn from_arg_value_with_config(value: &str, _config: Option<&ConverterConfig>) -> Result<Self> {
+            value.parse().map(Port).map_err(|_| Error::InvalidIntValue(value.to_string()))
+        }
+        
+        fn from_arg_value_for_key(
+            value: &str,
+            key: &str,
+            config: Option<&ConverterConfig>,
+        ) -> Result<Self> {
+            Self::from_arg_value_with_config(value, config)
+                .map_err(|_| Error::InvalidValue(key.to_string(), value.to_string()))
+        }
+    }
+    
+    #[test]
+    fn test_key_aware_conversion() {
+        assert_eq!(converter::convert_for_key::<Port>(" 22 ", "PORT", None).unwrap(), Port(22));
+        assert_eq!(
+            converter::convert_for_key::<Port>("http", "PORT", None),
+            Err(Error::InvalidValue("PORT".to_string(), "http".to_string()))
+        );
+        
+        // Types without an override fall back to from_arg_value
+        assert_eq!(
+            converter::convert_for_key::<i32>("x", "WIDTH", None),
+            Err(Error::InvalidIntValue("x".to_string()))
+        );
+    }
+    
+    #[test]
+    fn test_float_conversion() {
+        assert_eq!(f64::from_arg_value("1.5").unwrap(), 1.5);
+        assert_eq!(f64::from_arg_value("-2").unwrap(), -2.0);
+        assert!(f64::from_arg_value("1,5").is_err());
+        
+        let config = ConverterConfig {
+            decimal_separator: ',',
+            delimiter: ';',
+            ..ConverterConfig::default()
+        };
+        assert_eq!(f64::from_arg_value_with_config("1,5", Some(&config)).unwrap(), 1.5);
+        assert!(f64::from_arg_value_with_config("1.5", Some(&config)).is_err());
+        assert!(f64::from_arg_value_with_config("1,5,0", Some(&config)).is_err());
+        
+        let set = HashSet::<String>::from_arg_value_with_config("1,5;2,5", Some(&config)).unwrap();
+        assert_eq!(set, HashSet::from(["1,5".to_string(), "2,5".to_string()]));
+    }
+    
+    #[test]
+    fn test_float_edge_cases() {
+        assert_eq!(f64::from_arg_value("1.5e3").unwrap(), 1500.0);
+        assert_eq!(f64::from_arg_value("2E-2").unwrap(), 0.02);
+        assert_eq!(f32::from_arg_value("0.25").unwrap(), 0.25f32);
+        assert_eq!(f32::from_arg_value("-1.5e3").unwrap(), -1500.0f32);
+        
+        assert_eq!(f64::from_arg_value("inf").unwrap(), f64::INFINITY);
+        assert_eq!(f64::from_arg_value("-inf").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(f32::from_arg_value("Infinity").unwrap(), f32::INFINITY);
+        
+        for value in ["nan", "NaN", "-nan", "", "1,5", "1.5.0", "abc", "1.5x"] {
+            assert_eq!(f64::from_arg_value(value), Err(Error::InvalidFloatValue(value.to_string())));
+            assert_eq!(f32::from_arg_value(value), Err(Error::InvalidFloatValue(value.to_string())));
+        }
+        
+        // Values too large for f32 become infinite rather than failing
+        assert_eq!(f32::from_arg_value("1e39").unwrap(), f32::INFINITY);
+    }
+    
+    #[test]
+    fn test_decimal_separator_validation() {
+        assert!(ConverterConfig::default().validate().is_ok());
+        
+        let clashing = ConverterConfig {
+            decimal_separator: ',',
+            ..ConverterConfig::default()
+        };
+        assert!(matches!(clashing.validate(), Err(Error::InvalidInput(_))));
+        assert!(converter::convert::<f64>("1,5", Some(&clashing)).is_err());
+        
+        let fixed = ConverterConfig {
+            delimiter: ';',
+            ..clashing
+        };
+        assert_eq!(converter::convert::<f64>("1,5", Some(&fixed)).unwrap(), 1.5);
+        
+        let clashing_pairs = ConverterConfig {
+            pair_separator: ',',
+            ..ConverterConfig::default()
+        };
+        assert!(matches!(clashing_pairs.validate(), Err(Error::InvalidInput(_))));
+    }
+    
+    #[test]
+    fn test_radix_prefix_conversion() {
+        let config = ConverterConfig {
+            allow_radix_prefixes: true,
+            ..ConverterConfig::default()
+        };
+        
+        assert_eq!(i32::from_arg_value_with_config("0xFF", Some(&config)).unwrap(), 255);
+        assert_eq!(i32::from_arg_value_with_config("0Xff", Some(&config)).unwrap(), 255);
+        assert_eq!(i32::from_arg_value_with_config("0o755", Some(&config)).unwrap(), 493);
+        assert_eq!(i32::from_arg_value_with_config("0b1010", Some(&config)).unwrap(), 10);
+        assert_eq!(i32::from_arg_value_with_config("42", Some(&config)).unwrap(), 42);
+        assert_eq!(i32::from_arg_value_with_config("-0x10", Some(&config)).unwrap(), -16);
+        assert_eq!(i32::from_arg_value_with_config("-0x80000000", Some(&config)).unwrap(), i32::MIN);
+        
+        // Invalid digits and out-of-range values use the usual int error
+        for bad in ["0xZZ", "0b102", "0o8", "0x", "0x-1", "0x100000000"] {
+            match i32::from_arg_value_with_config(bad, Some(&config)) {
+                Err(Error::InvalidIntValue(val)) => assert_eq!(val, bad),
+                other => panic!("Expected InvalidIntValue for {}, got {:?}", bad, other),
+            }
+        }
+        
+        // Prefixes are enabled by default, and a leading zero is still decimal
+        assert_eq!(i32::from_arg_value("0xFF").unwrap(), 255);
+        assert_eq!(i32::from_arg_value("010").unwrap(), 10);
+        assert_eq!(i32::from_arg_value_with_config("010", Some(&config)).unwrap(), 10);
+        
+        // Every integer type accepts prefixes
+        assert_eq!(u16::from_arg_value("0o022").unwrap(), 0o22);
+        assert_eq!(u32::from_arg_value("0xFF").unwrap(), 255);
+        assert_eq!(u64::from_arg_value("0xFFFFFFFFFFFFFFFF").unwrap(), u64::MAX);
+        assert_eq!(i64::from_arg_value("-0x8000000000000000").unwrap(), i64::MIN);
+        assert_eq!(usize::from_arg_value("0b11").unwrap(), 3);
+        assert_eq!(isize::from_arg_value("-0b11").unwrap(), -3);
+        assert!(u16::from_arg_value("0x10000").is_err());
+        assert!(u32::from_arg_value("-0x1").is_err());
+        
+        // Prefixes are rejected when disabled
+        let decimal_only = ConverterConfig {
+            allow_radix_prefixes: false,
+            ..ConverterConfig::default()
+        };
+        assert!(i32::from_arg_value_with_config("0xFF", Some(&decimal_only)).is_err());
+        assert!(u64::from_arg_value_with_config("0o755", Some(&decimal_only)).is_err());
+        assert_eq!(i32::from_arg_value_with_config("010", Some(&decimal_only)).unwrap(), 10);
+    }
+    
+    #[test]
+    fn test_hashset_conversion() {
+        let set = HashSet::<i32>::from_arg_value("1,2, 3 2,1").unwrap();
+        assert_eq!(set, HashSet::from([1, 2, 3]));
+        
+        let set = HashSet::<String>::from_arg_value("").unwrap();
+        assert!(set.is_empty());
+        
+        let config = ConverterConfig {
+            delimiter: ';',
+            ..ConverterConfig::default()
+        };
+        let set = HashSet::<String>::from_arg_value_with_config("read;write;read", Some(&config)).unwrap();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("read") && set.contains("write"));
+        
+        match HashSet::<i32>::from_arg_value("1,two,3") {
+            Err(Error::InvalidIntValue(val)) => assert_eq!(val, "two"),
+            other => panic!("Expected InvalidIntValue error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_hashset_quoted_elements() {
+        let set = HashSet::<String>::from_arg_value("'read only',write \"a,b\" write").unwrap();
+        assert_eq!(set, HashSet::from(["read only".to_string(), "write".to_string(), "a,b".to_string()]));
+        
+        assert!(HashSet::<String>::from_arg_value("'unclosed,write").is_err());
+    }
+    
+    #[test]
+    fn test_vec_conversion() {
+        let groups = Vec::<String>::from_arg_value("admin,staff,dev").unwrap();
+        assert_eq!(groups, vec!["admin", "staff", "dev"]);
+        
+        let ports = Vec::<u16>::from_arg_value("22, 80 ,443").unwrap();
+        assert_eq!(ports, vec![22, 80, 443]);
+        
+        assert!(Vec::<String>::from_arg_value("").unwrap().is_empty());
+        
+        // Quoted and escaped delimiters stay inside their element
+        let items = Vec::<String>::from_arg_value(r"'a,b',c\,d").unwrap();
+        assert_eq!(items, vec!["a,b", r"c\,d"]);
+        
+        // A trailing delimiter produces a trailing empty element
+        let items = Vec::<String>::from_arg_value("a,b,").unwrap();
+        assert_eq!(items, vec!["a", "b", ""]);
+        
+        let config = ConverterConfig {
+            delimiter: ';',
+            ..ConverterConfig::default()
+        };
+        let items = Vec::<String>::from_arg_value_with_config("1,5;2,5", Some(&config)).unwrap();
+        assert_eq!(items, vec!["1,5", "2,5"]);
+        
+        let err = Vec::<i32>::from_arg_value("1,two,3").unwrap_err();
+        assert_eq!(err, Error::InvalidIntValue("two".to_string()));
+    }
+    
+    #[test]
+    fn test_pair_conversion() {
+        let pair = <(String, String)>::from_arg_value("uid:1000").unwrap();
+        assert_eq!(pair, ("uid".to_string(), "1000".to_string()));
+        
+        let pair = <(String, String)>::from_arg_value("url:http://host").unwrap();
+        assert_eq!(pair, ("url".to_string(), "http://host".to_string()));
+        
+        let config = ConverterConfig {
+            pair_separator: '=',
+            ..ConverterConfig::default()
+        };
+        let pair = <(String, String)>::from_arg_value_with_config("a=1", Some(&config)).unwrap();
+        assert_eq!(pair, ("a".to_string(), "1".to_string()));
+        
+        let map = Vec::<(String, String)>::from_arg_value("a:1,b:2").unwrap();
+        assert_eq!(map, vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]);
+        
+        match <(String, String)>::from_arg_value("missing") {
+            Err(Error::InvalidInput(msg)) => assert!(msg.contains("':'") && msg.contains("missing")),
+            other => panic!("Expected InvalidInput error, got {:?}", other),
+        }
+        assert!(Vec::<(String, String)>::from_arg_value("a:1,b").is_err());
+    }
+    
+    #[test]
+    fn test_path_conversion() {
+        let path = PathBuf::from_arg_value("/nonexistent/pam-args.conf").unwrap();
+        assert_eq!(path, PathBuf::from("/nonexistent/pam-args.conf"));
+        
+        match PathBuf::from_arg_value_for_key("", "CONFIG", None) {
+            Err(Error::InvalidValue(key, value)) => {
+                assert_eq!(key, "CONFIG");
+                assert_eq!(value, "");
+            },
+            other => panic!("Expected InvalidValue error, got {:?}", other),
+        }
+        
+        let config = ConverterConfig {
+            require_existing_path: true,
+            ..ConverterConfig::default()
+        };
+        let existing = env!("CARGO_MANIFEST_DIR");
+        let path = PathBuf::from_arg_value_with_config(existing, Some(&config)).unwrap();
+        assert_eq!(path, PathBuf::from(existing));
+        
+        match PathBuf::from_arg_value_for_key("/nonexistent/pam-args.conf", "CONFIG", Some(&config)) {
+            Err(Error::InvalidValue(key, value)) => {
+                assert_eq!(key, "CONFIG");
+                assert_eq!(value, "/nonexistent/pam-args.conf");
+            },
+            other => panic!("Expected InvalidValue error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_duration_conversion() {
+        assert_eq!(Duration::from_arg_value("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(Duration::from_arg_value("2.5s").unwrap(), Duration::from_millis(2500));
+        assert_eq!(Duration::from_arg_value("90m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(Duration::from_arg_value("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(Duration::from_arg_value("2d").unwrap(), Duration::from_secs(172800));
+        
+        for bad in ["s", "10x", "", "-5s", "infs", "1.5.5s", "30"] {
+            match Duration::from_arg_value_for_key(bad, "TIMEOUT", None) {
+                Err(Error::InvalidValue(key, value)) => {
+                    assert_eq!(key, "TIMEOUT");
+                    assert_eq!(value, bad);
+                },
+                other => panic!("Expected InvalidValue for {:?}, got {:?}", bad, other),
+            }
+        }
+        
+        let config = ConverterConfig {
+            bare_duration_as_seconds: true,
+            decimal_separator: ',',
+            delimiter: ';',
+            ..ConverterConfig::default()
+        };
+        assert_eq!(Duration::from_arg_value_with_config("30", Some(&config)).unwrap(), Duration::from_secs(30));
+        assert_eq!(Duration::from_arg_value_with_config("1,5m", Some(&config)).unwrap(), Duration::from_secs(90));
+    }
+    
+    #[test]
+    fn test_int_ranges() {
+        let ranges = converter::int_ranges();
+        
+        assert_eq!(ranges("2000").unwrap(), vec![2000]);
+        assert_eq!(ranges("1000-1003").unwrap(), vec![1000, 1001, 1002, 1003]);
+        assert_eq!(ranges("1000-1003,2000").unwrap(), vec![1000, 1001, 1002, 1003, 2000]);
+        assert_eq!(ranges(" 5 , 1 - 2 ,7-7").unwrap(), vec![5, 1, 2, 7]);
+        assert!(ranges("").unwrap().is_empty());
+        
+        for bad in ["1003-1000", "1-", "-5", "a-b", "1,,2", "1-2-3", "0-4294967295", "x"] {
+            match ranges(bad) {
+                Err(Error::InvalidInput(msg)) => assert!(msg.contains("Invalid range"), "{}", msg),
+                other => panic!("Expected InvalidInput for {:?}, got {:?}", bad, other),
+            }
+        }
+        match ranges("1003-1000") {
+            Err(Error::InvalidInput(msg)) => assert!(msg.contains("start is greater than end")),
+            other => panic!("Expected InvalidInput error, got {:?}", other),
+        }
     }
     
     #[test]
@@ -44,6 +44,14 @@ fn test_string_conversion() {
         trim_whitespace: false,
         handle_empty: true,
         recognize_none_values: true,
+        delimiter: ',',
+        allow_radix_prefixes: false,
+        decimal_separator: '.',
+        pair_separator: ':',
+        require_existing_path: false,
+        bare_duration_as_seconds: false,
+        case_insensitive_enums: false,
+        ..ConverterConfig::default()
     };
     let s: String = converter::convert("  hello  ", Some(&config)).unwrap();
     assert_eq!(s, "  hello  ");
@@ -115,6 +123,14 @@ fn test_character_conversion() {
         trim_whitespace: false,
         handle_empty: true,
         recognize_none_values: true,
+        delimiter: ',',
+        allow_radix_prefixes: false,
+        decimal_separator: '.',
+        pair_separator: ':',
+        require_existing_path: false,
+        bare_duration_as_seconds: false,
+        case_insensitive_enums: false,
+        ..ConverterConfig::default()
     };
     let c: char = converter::convert(" ", Some(&config)).unwrap();
     assert_eq!(c, ' ');
@@ -184,6 +200,14 @@ fn test_converter_config() {
         trim_whitespace: false,
         handle_empty: true,
         recognize_none_values: false,
+        delimiter: ',',
+        allow_radix_prefixes: false,
+        decimal_separator: '.',
+        pair_separator: ':',
+        require_existing_path: false,
+        bare_duration_as_seconds: false,
+        case_insensitive_enums: false,
+        ..ConverterConfig::default()
     };
 
     // Test that whitespace is not trimmed with custom config
@@ -206,9 +230,17 @@ fn test_converter_config_none_values() {
         trim_whitespace: false,
         handle_empty: true,
         recognize_none_values: false,
+        delimiter: ',',
+        allow_radix_prefixes: false,
+        decimal_separator: '.',
+        pair_separator: ':',
+        require_existing_path: false,
+        bare_duration_as_seconds: false,
+        case_insensitive_enums: false,
+        ..ConverterConfig::default()
     };
 
     // Test that "none" is NOT treated as None when recognize_none_values is false
     let none_result: Option<String> = converter::convert("none", Some(&custom_config)).unwrap();
     assert_eq!(none_result, Some("none".to_string()));
-}
\ No newline at end of file
+}
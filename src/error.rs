@@ -31,6 +31,10 @@ pub enum Error {
     /// Contains the invalid value string
     InvalidBoolValue(String),
     
+    /// Failed to parse an argument value as a floating-point number
+    /// Contains the invalid value string
+    InvalidFloatValue(String),
+    
     /// An argument dependency was not satisfied
     /// Contains the argument name and its dependency
     DependencyNotMet(String, String),
@@ -58,6 +62,18 @@ pub enum Error {
     /// An unexpected error occurred
     /// Contains a description of the error
     UnexpectedError(String),
+    
+    /// The help flag was given; not a failure
+    /// Contains the rendered help text for the caller to print
+    HelpRequested(String),
+    
+    /// The version flag was given; not a failure
+    /// Contains the version text for the caller to print
+    VersionRequested(String),
+    
+    /// An error annotated with the input that caused it
+    /// Contains the original error and the full argument vector
+    WithContext(Box<Error>, Vec<String>),
 }
 
 /// Type alias for Result with the library's Error type
@@ -85,6 +101,7 @@ impl Error {
             Error::UnrecognizedArg(_) => "UNRECOGNIZED_ARG",
             Error::InvalidIntValue(_) => "INVALID_INT_VALUE",
             Error::InvalidBoolValue(_) => "INVALID_BOOL_VALUE",
+            Error::InvalidFloatValue(_) => "INVALID_FLOAT_VALUE",
             Error::DependencyNotMet(_, _) => "DEPENDENCY_NOT_MET",
             Error::InvalidValue(_, _) => "INVALID_VALUE",
             Error::DuplicateArgName(_) => "DUPLICATE_ARG_NAME",
@@ -92,6 +109,56 @@ impl Error {
             Error::NestedBrackets(_) => "NESTED_BRACKETS",
             Error::InvalidInput(_) => "INVALID_INPUT",
             Error::UnexpectedError(_) => "UNEXPECTED_ERROR",
+            Error::HelpRequested(_) => "HELP_REQUESTED",
+            Error::VersionRequested(_) => "VERSION_REQUESTED",
+            Error::WithContext(_, _) => "WITH_CONTEXT",
+        }
+    }
+    
+    /// Attaches the full original argument vector to this error
+    ///
+    /// The resulting error keeps the original cause and records the input
+    /// that produced it, which makes syslog diagnostics self-contained.
+    /// Attaching context to an error that already has context replaces the
+    /// previous input rather than nesting.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The argument vector that was being parsed
+    ///
+    /// # Returns
+    ///
+    /// An `Error::WithContext` wrapping this error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Error;
+    ///
+    /// let args = vec!["DEBUG".to_string(), "USER=".to_string()];
+    /// let err = Error::InvalidKeyValue("USER=".to_string()).with_context(&args);
+    ///
+    /// assert_eq!(err.code(), "WITH_CONTEXT");
+    /// assert_eq!(err.cause(), &Error::InvalidKeyValue("USER=".to_string()));
+    /// assert!(err.to_string().contains("DEBUG USER="));
+    /// ```
+    pub fn with_context(self, args: &[String]) -> Error {
+        let cause = match self {
+            Error::WithContext(cause, _) => cause,
+            other => Box::new(other),
+        };
+        Error::WithContext(cause, args.to_vec())
+    }
+    
+    /// Returns the underlying error, looking through any attached context
+    ///
+    /// # Returns
+    ///
+    /// The original error for `Error::WithContext`, or `self` otherwise
+    pub fn cause(&self) -> &Error {
+        match self {
+            Error::WithContext(cause, _) => cause,
+            other => other,
         }
     }
     
@@ -153,6 +220,13 @@ impl Error {
                     val
                 )
             },
+            Error::InvalidFloatValue(val) => {
+                format!(
+                    "The value '{}' could not be parsed as a number. \
+                     Please provide a decimal value such as '0.5', '1.5e3' or 'inf'.",
+                    val
+                )
+            },
             Error::DependencyNotMet(arg, dep) => {
                 format!(
                     "The argument '{}' requires '{}' to also be provided. \
@@ -202,6 +276,27 @@ impl Error {
                     info
                 )
             },
+            Error::HelpRequested(text) => {
+                format!(
+                    "Help was requested. This is not a failure; \
+                     print the following text and exit:\n{}",
+                    text
+                )
+            },
+            Error::VersionRequested(text) => {
+                format!(
+                    "The version was requested. This is not a failure; \
+                     print the following text and exit:\n{}",
+                    text
+                )
+            },
+            Error::WithContext(cause, args) => {
+                format!(
+                    "{} The full input was: {}",
+                    cause.details(),
+                    args.join(" ")
+                )
+            },
         }
     }
 }
@@ -221,6 +316,8 @@ impl std::fmt::Display for Error {
                 write!(f, "Invalid integer value: {}", val),
             Error::InvalidBoolValue(val) => 
                 write!(f, "Invalid boolean value: {}", val),
+            Error::InvalidFloatValue(val) => 
+                write!(f, "Invalid floating-point value: {}", val),
             Error::DependencyNotMet(arg, dep) => 
                 write!(f, "Dependency not met: {} requires {}", arg, dep),
             Error::InvalidValue(arg, val) => 
@@ -235,6 +332,12 @@ impl std::fmt::Display for Error {
                 write!(f, "Invalid input: {}", info),
             Error::UnexpectedError(info) => 
                 write!(f, "Unexpected error: {}", info),
+            Error::HelpRequested(text) => 
+                write!(f, "{}", text),
+            Error::VersionRequested(text) => 
+                write!(f, "{}", text),
+            Error::WithContext(cause, args) => 
+                write!(f, "{} (input: {})", cause, args.join(" ")),
         }
     }
 }
@@ -278,6 +381,9 @@ impl serde::Serialize for Error {
             Error::InvalidBoolValue(val) => {
                 state.serialize_field("value", val)?;
             },
+            Error::InvalidFloatValue(val) => {
+                state.serialize_field("value", val)?;
+            },
             Error::DependencyNotMet(arg, dep) => {
                 state.serialize_field("argument", arg)?;
                 state.serialize_field("dependency", dep)?;
@@ -301,6 +407,16 @@ impl serde::Serialize for Error {
             Error::UnexpectedError(info) => {
                 state.serialize_field("info", info)?;
             },
+            Error::HelpRequested(text) => {
+                state.serialize_field("info", text)?;
+            },
+            Error::VersionRequested(text) => {
+                state.serialize_field("info", text)?;
+            },
+            Error::WithContext(cause, args) => {
+                state.serialize_field("cause", cause)?;
+                state.serialize_field("input", args)?;
+            },
         }
         
         state.end()
@@ -338,6 +454,8 @@ impl<'de> serde::Deserialize<'de> for Error {
                 let mut value: Option<String> = None;
                 let mut key_value: Option<String> = None;
                 let mut info: Option<String> = None;
+                let mut cause: Option<Error> = None;
+                let mut input: Option<Vec<String>> = None;
                 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -368,6 +486,12 @@ impl<'de> serde::Deserialize<'de> for Error {
                         "info" => {
                             info = Some(map.next_value()?);
                         }
+                        "cause" => {
+                            cause = Some(map.next_value()?);
+                        }
+                        "input" => {
+                            input = Some(map.next_value()?);
+                        }
                         _ => {
                             // Skip unknown fields
                             let _: de::IgnoredAny = map.next_value()?;
@@ -403,6 +527,10 @@ impl<'de> serde::Deserialize<'de> for Error {
                         let val = value.ok_or_else(|| de::Error::missing_field("value"))?;
                         Ok(Error::InvalidBoolValue(val))
                     }
+                    "INVALID_FLOAT_VALUE" => {
+                        let val = value.ok_or_else(|| de::Error::missing_field("value"))?;
+                        Ok(Error::InvalidFloatValue(val))
+                    }
                     "DEPENDENCY_NOT_MET" => {
                         let arg = argument.ok_or_else(|| de::Error::missing_field("argument"))?;
                         let dep = dependency.ok_or_else(|| de::Error::missing_field("dependency"))?;
@@ -433,6 +561,19 @@ impl<'de> serde::Deserialize<'de> for Error {
                         let i = info.ok_or_else(|| de::Error::missing_field("info"))?;
                         Ok(Error::UnexpectedError(i))
                     }
+                    "HELP_REQUESTED" => {
+                        let i = info.ok_or_else(|| de::Error::missing_field("info"))?;
+                        Ok(Error::HelpRequested(i))
+                    }
+                    "VERSION_REQUESTED" => {
+                        let i = info.ok_or_else(|| de::Error::missing_field("info"))?;
+                        Ok(Error::VersionRequested(i))
+                    }
+                    "WITH_CONTEXT" => {
+                        let c = cause.ok_or_else(|| de::Error::missing_field("cause"))?;
+                        let i = input.ok_or_else(|| de::Error::missing_field("input"))?;
+                        Ok(Error::WithContext(Box::new(c), i))
+                    }
                     _ => {
                         // Default to UnexpectedError if code is unknown
                         let msg = message.unwrap_or_else(|| "Unknown error code".to_string());
@@ -484,6 +625,10 @@ mod tests {
         
         let err = Error::InvalidBoolValue("MAYBE".to_string());
         assert!(err.details().contains("could not be parsed as a boolean"));
+        
+        let err = Error::InvalidFloatValue("1,5".to_string());
+        assert_eq!(err.code(), "INVALID_FLOAT_VALUE");
+        assert!(err.details().contains("could not be parsed as a number"));
     }
     
     #[test]
@@ -526,6 +671,47 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_with_context() {
+        let args = vec!["DEBUG".to_string(), "USER='admin".to_string()];
+        let err = Error::UnclosedDelimiter("single quote".to_string()).with_context(&args);
+        
+        assert_eq!(err.code(), "WITH_CONTEXT");
+        assert_eq!(err.cause(), &Error::UnclosedDelimiter("single quote".to_string()));
+        assert_eq!(
+            err.to_string(),
+            "Unclosed delimiter: single quote (input: DEBUG USER='admin)"
+        );
+        assert!(err.details().contains("unclosed delimiter was found"));
+        assert!(err.details().contains("DEBUG USER='admin"));
+        
+        // Re-attaching context replaces the input instead of nesting
+        let err = err.with_context(&["OTHER".to_string()]);
+        match &err {
+            Error::WithContext(cause, input) => {
+                assert!(matches!(**cause, Error::UnclosedDelimiter(_)));
+                assert_eq!(input, &vec!["OTHER".to_string()]);
+            },
+            _ => panic!("Expected WithContext variant"),
+        }
+        
+        // Errors without context are their own cause
+        let plain = Error::InvalidInput("x".to_string());
+        assert_eq!(plain.cause(), &plain);
+    }
+    
+    #[test]
+    fn test_help_and_version_requested() {
+        let err = Error::HelpRequested("Usage: DEBUG USER=<name>".to_string());
+        assert_eq!(err.code(), "HELP_REQUESTED");
+        assert_eq!(err.to_string(), "Usage: DEBUG USER=<name>");
+        assert!(err.details().contains("not a failure"));
+        
+        let err = Error::VersionRequested("pam_example 1.2.0".to_string());
+        assert_eq!(err.code(), "VERSION_REQUESTED");
+        assert_eq!(err.to_string(), "pam_example 1.2.0");
+    }
+    
     #[test]
     fn test_clone() {
         let err = Error::DuplicateArgName("FLAG".to_string());
@@ -0,0 +1,218 @@
+//! Help text generation for the pam-args library.
+//!
+//! This module renders the registered flags and key-value pairs as an aligned
+//! usage block, suitable for printing from a PAM module's `--help`-style
+//! diagnostics or for inclusion in documentation. Flags are listed before
+//! key-value pairs, each group in registration order.
+
+use crate::args::{AllowedKeyValueFormats, Flag, KeyValue};
+use log::trace;
+
+/// Indentation before each argument label
+const INDENT: usize = 2;
+
+/// Space between the label column and the description column
+const GAP: usize = 2;
+
+/// Narrowest description column used, even if the line width is smaller
+const MIN_DESCRIPTION_WIDTH: usize = 20;
+
+/// A single argument prepared for rendering
+struct Entry {
+    /// The label shown in the first column, e.g. `USER=<value>`
+    label: String,
+
+    /// The description and notes shown in the second column, one per paragraph
+    paragraphs: Vec<String>,
+}
+
+/// Renders the help text for the given arguments
+///
+/// Each argument is shown with its description, a required marker, and
+/// notes on aliases, allowed values, defaults, non-default formats, dependencies, exclusions and
+/// examples. Descriptions are wrapped so lines fit within `width` where the
+/// label column leaves enough room.
+///
+/// # Arguments
+///
+/// * `flags` - The registered flags
+/// * `key_values` - The registered key-value pairs
+/// * `width` - The maximum line width
+///
+/// # Returns
+///
+/// The help text, or an empty string if no arguments are registered
+pub(crate) fn render(flags: &[Flag], key_values: &[KeyValue], width: usize) -> String {
+    let flag_entries: Vec<Entry> = flags.iter().map(flag_entry).collect();
+    let key_value_entries: Vec<Entry> = key_values.iter().map(key_value_entry).collect();
+
+    let label_width = flag_entries
+        .iter()
+        .chain(&key_value_entries)
+        .map(|entry| entry.label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let description_column = INDENT + label_width + GAP;
+    let description_width = width.saturating_sub(description_column).max(MIN_DESCRIPTION_WIDTH);
+    trace!("Rendering help with labels {} wide and descriptions {} wide", label_width, description_width);
+
+    let mut sections = Vec::new();
+    for (title, entries) in [("Flags:", flag_entries), ("Key-value arguments:", key_value_entries)] {
+        if entries.is_empty() {
+            continue;
+        }
+        let mut section = vec![title.to_string()];
+        for entry in &entries {
+            let lines = entry.paragraphs.iter().flat_map(|paragraph| wrap(paragraph, description_width));
+            for (index, line) in lines.enumerate() {
+                let label = if index == 0 { entry.label.as_str() } else { "" };
+                let row = format!("{:indent$}{:label_width$}{:gap$}{}", "", label, "", line,
+                    indent = INDENT, label_width = label_width, gap = GAP);
+                section.push(row.trim_end().to_string());
+            }
+        }
+        sections.push(section.join("\n"));
+    }
+
+    sections.join("\n\n")
+}
+
+/// Prepares a flag for rendering
+fn flag_entry(flag: &Flag) -> Entry {
+    let mut paragraphs = vec![flag.description().to_string()];
+    push_notes(&mut paragraphs, flag.get_aliases(), flag.dependencies(), flag.exclusions());
+    if !flag.implications().is_empty() {
+        let implied: Vec<String> = flag.implications().iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        paragraphs.push(format!("Implies: {}", implied.join(", ")));
+    }
+    push_examples(&mut paragraphs, flag.examples());
+
+    Entry { label: flag.name().to_string(), paragraphs }
+}
+
+/// Prepares a key-value pair for rendering
+fn key_value_entry(key_value: &KeyValue) -> Entry {
+    let mut description = key_value.description().to_string();
+    if key_value.is_required() {
+        description.push_str(" (required)");
+    }
+
+    let mut paragraphs = vec![description];
+    if let Some(allowed) = key_value.get_allowed_values() {
+        paragraphs.push(format!("Allowed values: {}", allowed.join(", ")));
+    }
+    if let Some(default) = key_value.get_default_value() {
+        paragraphs.push(format!("Default: {}", default));
+    }
+    // Only list the formats when they differ from the default `KEY=VALUE`
+    let formats = key_value.get_allowed_formats();
+    if formats != [AllowedKeyValueFormats::KeyValue] {
+        let names: Vec<&str> = formats.iter().map(AllowedKeyValueFormats::as_str).collect();
+        paragraphs.push(format!("Formats: {}", names.join(", ")));
+    }
+    push_notes(&mut paragraphs, key_value.get_aliases(), key_value.dependencies(), key_value.exclusions());
+    push_examples(&mut paragraphs, key_value.examples());
+
+    Entry { label: format!("{}=<value>", key_value.name()), paragraphs }
+}
+
+/// Adds the alias, dependency and exclusion notes shared by all arguments
+fn push_notes(paragraphs: &mut Vec<String>, aliases: &[String], dependencies: &[String], exclusions: &[String]) {
+    if !aliases.is_empty() {
+        paragraphs.push(format!("Aliases: {}", aliases.join(", ")));
+    }
+    if !dependencies.is_empty() {
+        paragraphs.push(format!("Requires: {}", dependencies.join(", ")));
+    }
+    if !exclusions.is_empty() {
+        paragraphs.push(format!("Conflicts with: {}", exclusions.join(", ")));
+    }
+}
+
+/// Adds one paragraph per usage example
+fn push_examples(paragraphs: &mut Vec<String>, examples: &[String]) {
+    paragraphs.extend(examples.iter().map(|example| format!("Example: {}", example)));
+}
+
+/// Wraps text at word boundaries into lines of at most `width` characters
+///
+/// A single word longer than `width` is kept whole on its own line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three four", 9), vec!["one two", "three", "four"]);
+        assert_eq!(wrap("averyveryverylongword x", 5), vec!["averyveryverylongword", "x"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn test_render_layout() {
+        let flags = vec![Flag::new("DEBUG", "Enable debug mode").alias("D").excludes("QUIET")];
+        let key_values = vec![
+            KeyValue::new("USER", "Username for authentication").required(),
+            KeyValue::new("MODE", "Operating mode").allowed_values(&["fast", "safe"]).default_value("safe"),
+        ];
+
+        let expected = [
+            "Flags:",
+            "  DEBUG         Enable debug mode",
+            "                Aliases: D",
+            "                Conflicts with: QUIET",
+            "",
+            "Key-value arguments:",
+            "  USER=<value>  Username for authentication (required)",
+            "  MODE=<value>  Operating mode",
+            "                Allowed values: fast, safe",
+            "                Default: safe",
+        ]
+        .join("\n");
+        assert_eq!(render(&flags, &key_values, 80), expected);
+    }
+
+    #[test]
+    fn test_render_lists_non_default_formats() {
+        let key_values = vec![
+            KeyValue::new("USER", "Username"),
+            KeyValue::new("DEBUG", "Debug level")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyOnly, AllowedKeyValueFormats::KeyValue]),
+        ];
+
+        let help = render(&[], &key_values, 80);
+        assert!(help.contains("Formats: key_only, key_value"), "{}", help);
+        assert_eq!(help.matches("Formats:").count(), 1, "{}", help);
+    }
+
+    #[test]
+    fn test_render_wraps_to_width() {
+        let flags = vec![Flag::new("DEBUG", "Write detailed diagnostics for every argument to the system log")];
+        let help = render(&flags, &[], 40);
+        assert!(help.lines().count() > 2);
+        assert!(help.lines().all(|line| line.chars().count() <= 40), "{}", help);
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render(&[], &[], 80), "");
+    }
+}
@@ -12,25 +12,43 @@ mod config;
 pub(crate) mod conversion;
 mod storage;
 mod tokenizer;
-#[cfg(test)]
-mod testing;
+mod lint;
+mod help;
+mod parser;
+mod arg_enum;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 #[cfg(test)]
 mod conversion_tests;
 #[cfg(test)]
 mod storage_tests;
+#[cfg(test)]
+mod tokenizer_proptests;
 
 // Re-export Error and Result types
 pub use error::{Error, Result};
 
 // Re-export logging module public API
-pub use logging::{LogComponent, LogOperation, LogDestination, LogOptions, LogConfig};
+pub use logging::{LogComponent, LogOperation, LogDestination, LogOptions, LogConfig, LogPrefixStyle};
 pub use logging::init;
 
 // Re-export core argument types
-pub use args::{Flag, KeyValue, AllowedKeyValueFormats};
+pub use args::{Flag, KeyValue, AllowedKeyValueFormats, FormatPreset};
 
 // Re-export configuration types
-pub use config::{ParserConfig, ParserConfigBuilder};
+pub use config::{BracketDuplicatePolicy, KeyNormalizer, ParserConfig, ParserConfigBuilder};
+pub use conversion::{converter, ConverterConfig, FromArgValue};
+pub use tokenizer::{Tokenizer, TokenizerConfig, TokenizationResult, Token, TrailingDelimiterPolicy};
 
 // Re-export storage module public API
-pub use storage::{KeyValueStore, DefaultKeyValueStore, NonArgTextStore, KeyValueStoreExt, FromArgValue};
+pub use storage::{KeyValueStore, DefaultKeyValueStore, NonArgTextStore, KeyValueStoreExt};
+
+// Re-export the parser
+pub use parser::{ArgCounts, ArgumentParser, ParseResult, ValueSource};
+
+// Re-export structural validation
+pub use lint::lint_line;
+
+// Re-export the helpers for table-driven parser tests
+#[cfg(any(test, feature = "test-utils"))]
+pub use testing::{assert_parses_to, ParseResultBuilder};
@@ -0,0 +1,255 @@
+//! Structural validation for the pam-args library.
+//!
+//! This module checks an argument line for structural problems (unbalanced
+//! quotes or brackets, nested brackets and malformed key-value syntax) without
+//! needing any argument definitions. It is intended for configuration linters
+//! and editors that want to report every problem in a line at once rather than
+//! stopping at the first one.
+
+use crate::config::ParserConfig;
+use crate::conversion::format;
+use crate::error::Error;
+use crate::tokenizer::{Tokenizer, TokenizerConfig};
+use crate::utils::{strings, TextProcessingConfig};
+use log::{debug, trace};
+
+/// Checks a raw argument line for structural problems
+///
+/// Only the shape of the line is validated: quotes and brackets must be
+/// balanced, brackets must not be nested unless `allow_nested_brackets` is
+/// enabled, and every `KEY=VALUE` token must
+/// have a valid key name. No argument definitions are consulted, so unknown
+/// flags or keys are not reported.
+///
+/// Unbalanced quotes or brackets prevent the line from being split into
+/// arguments, so in that case only that single problem is returned. A line
+/// starting with `ParserConfig::comment_prefix` is a comment and has no
+/// problems.
+///
+/// # Arguments
+///
+/// * `line` - The raw argument line, as it would appear in a PAM configuration file
+/// * `config` - Parser configuration providing the quote, bracket, escape and comment
+///   characters and whether nested brackets are allowed
+///
+/// # Returns
+///
+/// All structural problems found, or an empty vector if the line is well-formed
+///
+/// # Examples
+///
+/// ```
+/// use pam_args::{lint_line, Error, ParserConfig};
+///
+/// let config = ParserConfig::default();
+///
+/// assert!(lint_line("DEBUG USER=admin [HOST=localhost,PORT=22]", &config).is_empty());
+///
+/// let problems = lint_line("=admin [A,[B]]", &config);
+/// assert_eq!(problems.len(), 2);
+/// assert!(matches!(problems[0], Error::InvalidKeyValue(_)));
+/// assert!(matches!(problems[1], Error::NestedBrackets(_)));
+/// ```
+pub fn lint_line(line: &str, config: &ParserConfig) -> Vec<Error> {
+    trace!("Linting line: '{}'", line);
+    if line.trim_start().starts_with(config.comment_prefix()) {
+        trace!("Skipping comment line");
+        return Vec::new();
+    }
+
+    let text_config = TextProcessingConfig::from(config);
+    let words = match strings::split_whitespace(line, &text_config) {
+        Ok(words) => words,
+        Err(e) => return vec![e],
+    };
+
+    let tokenizer = Tokenizer::with_config(TokenizerConfig::from(config));
+    let mut problems = Vec::new();
+
+    for word in &words {
+        if let Err(e) = check_brackets(word, &text_config, config.allow_nested_brackets()) {
+            problems.push(e);
+            continue;
+        }
+
+        check_tokens(&tokenizer, word, &text_config, &mut problems);
+    }
+
+    debug!("Found {} structural problem(s) in line", problems.len());
+    problems
+}
+
+/// Tokenizes an argument and checks the syntax of each token
+///
+/// Nested bracket groups, which the tokenizer keeps whole when nesting is
+/// allowed, are checked recursively.
+fn check_tokens(tokenizer: &Tokenizer, word: &str, config: &TextProcessingConfig, problems: &mut Vec<Error>) {
+    match tokenizer.tokenize_arg(word) {
+        Ok(result) => {
+            for token in &result.tokens {
+                if tokenizer.config().allow_nested_brackets && token.starts_with(config.open_bracket) {
+                    check_tokens(tokenizer, token, config, problems);
+                } else if let Some(e) = check_key_value(token, config) {
+                    problems.push(e);
+                }
+            }
+        }
+        Err(e) => problems.push(e),
+    }
+}
+
+/// Checks that brackets in a single argument are matched, and not nested
+/// unless `allow_nested` is set
+fn check_brackets(word: &str, config: &TextProcessingConfig, allow_nested: bool) -> Result<(), Error> {
+    let mut depth = 0usize;
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let mut in_escape = false;
+
+    for c in word.chars() {
+        if in_escape {
+            in_escape = false;
+        } else if c == config.escape_char {
+            in_escape = true;
+        } else if c == config.single_quote && !in_double_quote {
+            in_single_quote = !in_single_quote;
+        } else if c == config.double_quote && !in_single_quote {
+            in_double_quote = !in_double_quote;
+        } else if in_single_quote || in_double_quote {
+            continue;
+        } else if c == config.open_bracket {
+            if depth > 0 && !allow_nested {
+                return Err(Error::NestedBrackets(format!(
+                    "Nested brackets are not supported: {}", word
+                )));
+            }
+            depth += 1;
+        } else if c == config.close_bracket {
+            if depth == 0 {
+                return Err(Error::InvalidInput(format!(
+                    "Unmatched closing bracket in: {}", word
+                )));
+            }
+            depth -= 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that a `KEY=VALUE` token has a valid key name
+///
+/// Tokens without an equals sign and quoted tokens are left alone, since
+/// they may be flags or free text.
+fn check_key_value(token: &str, config: &TextProcessingConfig) -> Option<Error> {
+    let token = token.trim();
+    if token.is_empty()
+        || token.starts_with(config.single_quote)
+        || token.starts_with(config.double_quote)
+        || !token.contains('=')
+    {
+        return None;
+    }
+
+    let detected = format::detect(token);
+    if strings::is_valid_key_name(detected.key.trim()) {
+        None
+    } else {
+        Some(Error::InvalidKeyValue(token.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(line: &str) -> Vec<Error> {
+        lint_line(line, &ParserConfig::default())
+    }
+
+    #[test]
+    fn test_clean_line() {
+        assert!(lint("").is_empty());
+        assert!(lint("DEBUG USER=admin EMPTY= MSG='hello world'").is_empty());
+        assert!(lint("[HOST=localhost, PORT=22] KEY=\\[escaped\\]").is_empty());
+        assert!(lint("free text here").is_empty());
+    }
+
+    #[test]
+    fn test_unclosed_quote() {
+        let problems = lint("USER='admin DEBUG");
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0], Error::UnclosedDelimiter(_)));
+    }
+
+    #[test]
+    fn test_unclosed_bracket() {
+        let problems = lint("DEBUG [HOST=localhost,PORT=22");
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0], Error::UnclosedDelimiter(_)));
+    }
+
+    #[test]
+    fn test_unmatched_closing_bracket() {
+        let problems = lint("HOST=localhost]");
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0], Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_nested_brackets() {
+        let problems = lint("[A=1,[B=2]]");
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0], Error::NestedBrackets(_)));
+    }
+
+    #[test]
+    fn test_nested_brackets_when_allowed() {
+        let config = ParserConfig::builder()
+            .allow_nested_brackets(true)
+            .build();
+        assert!(lint_line("[A=1,[B=2]]", &config).is_empty());
+        assert!(lint_line("[A=1,[B=2,C]]", &config).is_empty());
+
+        let problems = lint_line("[A=1,[=2]] [A,[B]]]", &config);
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(problems[0], Error::InvalidKeyValue(_)));
+        assert!(matches!(problems[1], Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_invalid_key_value_syntax() {
+        let problems = lint("=value 1KEY=x BAD-KEY=y [GOOD=1,=2]");
+        assert_eq!(problems.len(), 4);
+        assert!(problems.iter().all(|e| matches!(e, Error::InvalidKeyValue(_))));
+    }
+
+    #[test]
+    fn test_reports_all_problems() {
+        let problems = lint("=admin DEBUG [A,[B]] HOST=x] USER=ok");
+        assert_eq!(problems.len(), 3);
+        assert!(matches!(problems[0], Error::InvalidKeyValue(_)));
+        assert!(matches!(problems[1], Error::NestedBrackets(_)));
+        assert!(matches!(problems[2], Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_uses_configured_delimiters() {
+        let config = ParserConfig::builder()
+            .bracket_chars('{', '}')
+            .build();
+        assert!(lint_line("{A=1,B=2} [literal]", &config).is_empty());
+        assert_eq!(lint_line("{A=1,{B=2}}", &config).len(), 1);
+    }
+
+    #[test]
+    fn test_comment_lines() {
+        assert!(lint("# [unclosed 'quote").is_empty());
+        assert!(lint("   # =admin").is_empty());
+        assert_eq!(lint("DEBUG # =admin").len(), 1);
+
+        let config = ParserConfig::builder().comment_prefix(';').build();
+        assert!(lint_line("; [unclosed", &config).is_empty());
+        assert_eq!(lint_line("# [unclosed", &config).len(), 1);
+    }
+}
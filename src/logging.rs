@@ -70,6 +70,8 @@
 
 use crate::error::{Error, Result};
 use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Represents a component within the library for logging purposes
 #[derive(Debug, Clone, Copy)]
@@ -161,6 +163,16 @@ pub struct LogConfig {
     
     /// Whether to use JSON format for logs
     pub json_format: bool,
+    
+    /// How the component and operation are decorated in text logs
+    pub prefix_style: LogPrefixStyle,
+    
+    /// Text placed between the component and the operation in text logs
+    pub component_operation_separator: String,
+    
+    /// Keys whose values are replaced by `***` when arguments are logged,
+    /// compared without case
+    pub redact_keys: Vec<String>,
 }
 
 impl Default for LogConfig {
@@ -170,12 +182,28 @@ impl Default for LogConfig {
             include_component: true,
             include_operation: true,
             json_format: false,
+            prefix_style: LogPrefixStyle::Brackets,
+            component_operation_separator: String::new(),
+            redact_keys: vec!["PASSWORD".to_string(), "SECRET".to_string(), "TOKEN".to_string()],
         }
     }
 }
 
+/// Decoration applied to the component and operation in text logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogPrefixStyle {
+    /// Wrap each part in brackets, e.g. `[PARSER][PARSE]`
+    Brackets,
+    
+    /// Follow each part with a colon, e.g. `PARSER:PARSE:`
+    Colon,
+    
+    /// Use the bare names, joined only by the separator
+    None,
+}
+
 /// Available logging destinations
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub enum LogDestination {
     /// Log to the system's syslog facility (default for PAM modules)
     Syslog,
@@ -186,10 +214,34 @@ pub enum LogDestination {
     /// Log to both syslog and terminal
     Both,
     
+    /// Append to the given file
+    File(PathBuf),
+    
+    /// Log to both syslog and the given file
+    SyslogAndFile(PathBuf),
+    
+    /// Route messages through a logger supplied by the caller
+    Custom(Arc<dyn log::Log>),
+    
     /// Do not initialize any logger (use existing or none)
     None,
 }
 
+/// Debug output for LogDestination; custom loggers are shown by name only
+impl Debug for LogDestination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogDestination::Syslog => write!(f, "Syslog"),
+            LogDestination::Terminal => write!(f, "Terminal"),
+            LogDestination::Both => write!(f, "Both"),
+            LogDestination::File(path) => f.debug_tuple("File").field(path).finish(),
+            LogDestination::SyslogAndFile(path) => f.debug_tuple("SyslogAndFile").field(path).finish(),
+            LogDestination::Custom(_) => write!(f, "Custom"),
+            LogDestination::None => write!(f, "None"),
+        }
+    }
+}
+
 /// Logging initialization options
 #[derive(Debug, Clone)]
 pub struct LogOptions {
@@ -249,6 +301,254 @@ pub mod logger {
         LOG_CONFIG.get_or_init(LogConfig::default)
     }
     
+    /// Builds the component/operation prefix for a text log message
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The logging configuration to apply
+    /// * `component` - The component generating the log
+    /// * `operation` - The operation being performed
+    ///
+    /// # Returns
+    ///
+    /// The rendered prefix, or an empty string if both parts are disabled
+    pub fn format_prefix(
+        config: &LogConfig,
+        component: LogComponent,
+        operation: LogOperation,
+    ) -> String {
+        let decorate = |name: &str| match config.prefix_style {
+            LogPrefixStyle::Brackets => format!("[{}]", name),
+            LogPrefixStyle::Colon => format!("{}:", name),
+            LogPrefixStyle::None => name.to_string(),
+        };
+        
+        let mut parts = Vec::with_capacity(2);
+        if config.include_component {
+            parts.push(decorate(component.as_str()));
+        }
+        if config.include_operation {
+            parts.push(decorate(operation.as_str()));
+        }
+        
+        parts.join(&config.component_operation_separator)
+    }
+    
+    /// Renders a string as a quoted JSON string literal
+    ///
+    /// Quotes, backslashes and control characters are escaped so that any
+    /// message or data rendering produces valid JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string to render
+    ///
+    /// # Returns
+    ///
+    /// The escaped string, including the surrounding quotes
+    pub fn json_string(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len() + 2);
+        escaped.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if u32::from(c) < 0x20 => escaped.push_str(&format!("\\u{:04x}", u32::from(c))),
+                c => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+    
+    /// Builds a JSON log line
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level name
+    /// * `component` - The component generating the log
+    /// * `operation` - The operation being performed
+    /// * `message` - The log message, escaped as a JSON string
+    /// * `field` - The name of the trailing field, such as `data` or `error`
+    /// * `value` - The trailing field's value, which must already be valid JSON
+    ///
+    /// # Returns
+    ///
+    /// A single-line JSON object
+    pub fn json_event(
+        level: &str,
+        component: LogComponent,
+        operation: LogOperation,
+        message: &str,
+        field: &str,
+        value: &str,
+    ) -> String {
+        format!(
+            "{{\"level\":{},\"component\":{},\"operation\":{},\"message\":{},{}:{}}}",
+            json_string(level),
+            json_string(component.as_str()),
+            json_string(operation.as_str()),
+            json_string(message),
+            json_string(field),
+            value
+        )
+    }
+    
+    /// Renders a log message with optional data in the configured format
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The logging configuration to apply
+    /// * `level` - The level name used in JSON output
+    /// * `component` - The component generating the log
+    /// * `operation` - The operation being performed
+    /// * `message` - The log message
+    /// * `args` - Optional data to include in the log
+    ///
+    /// # Returns
+    ///
+    /// The rendered log line, without timestamp or level for text output
+    pub fn format_event<D: Debug>(
+        config: &LogConfig,
+        level: &str,
+        component: LogComponent,
+        operation: LogOperation,
+        message: &str,
+        args: Option<D>,
+    ) -> String {
+        if config.json_format {
+            let data = args.map_or_else(|| "null".to_string(), |data| json_string(&format!("{:?}", data)));
+            json_event(level, component, operation, message, "data", &data)
+        } else {
+            let prefix = format_prefix(config, component, operation);
+            
+            match args {
+                Some(data) => format!("{} {} - {:?}", prefix, message, data),
+                None => format!("{} {}", prefix, message),
+            }
+        }
+    }
+    
+    /// Masks the values of sensitive `KEY=VALUE` arguments
+    ///
+    /// Arguments whose key is listed in `redact_keys` are rendered as
+    /// `KEY=***`; everything else is returned unchanged. Each element of a
+    /// bracketed argument is checked on its own, so `[USER=a,PASSWORD=x]`
+    /// becomes `[USER=a,PASSWORD=***]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The logging configuration holding the redaction list
+    /// * `args` - The arguments to redact
+    ///
+    /// # Returns
+    ///
+    /// The arguments with sensitive values masked
+    pub fn redact_args(config: &LogConfig, args: &[String]) -> Vec<String> {
+        args.iter().map(|arg| redact_arg(config, arg)).collect()
+    }
+    
+    /// Masks sensitive values in text using the global configuration
+    ///
+    /// The text may be a single argument, a bracketed group or a whole
+    /// argument line; every `KEY=VALUE` element in it is checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to redact
+    ///
+    /// # Returns
+    ///
+    /// The text with sensitive values masked
+    pub fn redact(text: &str) -> String {
+        redact_arg(config(), text)
+    }
+    
+    /// Masks sensitive values in text, splitting elements at custom characters
+    ///
+    /// Like `redact`, but elements are separated by whitespace and the given
+    /// characters, for parsers with custom bracket or delimiter characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to redact
+    /// * `separators` - Characters that end an element outside quotes
+    ///
+    /// # Returns
+    ///
+    /// The text with sensitive values masked
+    pub fn redact_with(text: &str, separators: &[char]) -> String {
+        redact_elements(config(), text, separators)
+    }
+    
+    /// Masks a value if its key is sensitive, using the global configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key the value belongs to
+    /// * `value` - The value to mask
+    ///
+    /// # Returns
+    ///
+    /// `***` if the key is listed in `redact_keys`, otherwise the value
+    pub fn redact_value<'a>(key: &str, value: &'a str) -> &'a str {
+        if is_sensitive(config(), key) {
+            "***"
+        } else {
+            value
+        }
+    }
+    
+    fn is_sensitive(config: &LogConfig, key: &str) -> bool {
+        config.redact_keys.iter().any(|k| k.eq_ignore_ascii_case(key.trim()))
+    }
+    
+    /// Redacts each element of an argument, line or bracketed group
+    ///
+    /// Elements end at whitespace, brackets and commas outside quotes, so a
+    /// quoted secret containing any of them is masked whole.
+    fn redact_arg(config: &LogConfig, arg: &str) -> String {
+        redact_elements(config, arg, &['[', ']', ','])
+    }
+    
+    fn redact_elements(config: &LogConfig, text: &str, separators: &[char]) -> String {
+        let mut redacted = String::with_capacity(text.len());
+        let mut element = String::new();
+        let mut quote = None;
+        for c in text.chars() {
+            match quote {
+                Some(q) => {
+                    if c == q {
+                        quote = None;
+                    }
+                    element.push(c);
+                }
+                None if c == '\'' || c == '"' => {
+                    quote = Some(c);
+                    element.push(c);
+                }
+                None if separators.contains(&c) || c.is_whitespace() => {
+                    redacted.push_str(&redact_element(config, &element));
+                    element.clear();
+                    redacted.push(c);
+                }
+                None => element.push(c),
+            }
+        }
+        redacted.push_str(&redact_element(config, &element));
+        redacted
+    }
+    
+    fn redact_element(config: &LogConfig, element: &str) -> String {
+        match element.split_once('=') {
+            Some((key, _)) if is_sensitive(config, key) => format!("{}=***", key),
+            _ => element.to_string(),
+        }
+    }
+    
     /// Log a message at TRACE level
     ///
     /// # Arguments
@@ -267,34 +567,7 @@ pub mod logger {
             return;
         }
         
-        let config = config();
-        
-        if config.json_format {
-            trace!(
-                "{{\"level\":\"TRACE\",\"component\":\"{}\",\"operation\":\"{}\",\"message\":\"{}\",\"data\":{}}}",
-                component.as_str(),
-                operation.as_str(),
-                message,
-                format!("{:?}", args)
-            );
-        } else {
-            let comp_str = if config.include_component {
-                format!("[{}]", component.as_str())
-            } else {
-                String::new()
-            };
-            
-            let op_str = if config.include_operation {
-                format!("[{}]", operation.as_str())
-            } else {
-                String::new()
-            };
-            
-            match args {
-                Some(data) => trace!("{}{} {} - {:?}", comp_str, op_str, message, data),
-                None => trace!("{}{} {}", comp_str, op_str, message),
-            }
-        }
+        trace!("{}", format_event(config(), "TRACE", component, operation, message, args));
     }
     
     /// Log a message at DEBUG level
@@ -315,34 +588,7 @@ pub mod logger {
             return;
         }
         
-        let config = config();
-        
-        if config.json_format {
-            debug!(
-                "{{\"level\":\"DEBUG\",\"component\":\"{}\",\"operation\":\"{}\",\"message\":\"{}\",\"data\":{}}}",
-                component.as_str(),
-                operation.as_str(),
-                message,
-                format!("{:?}", args)
-            );
-        } else {
-            let comp_str = if config.include_component {
-                format!("[{}]", component.as_str())
-            } else {
-                String::new()
-            };
-            
-            let op_str = if config.include_operation {
-                format!("[{}]", operation.as_str())
-            } else {
-                String::new()
-            };
-            
-            match args {
-                Some(data) => debug!("{}{} {} - {:?}", comp_str, op_str, message, data),
-                None => debug!("{}{} {}", comp_str, op_str, message),
-            }
-        }
+        debug!("{}", format_event(config(), "DEBUG", component, operation, message, args));
     }
     
     /// Log a message at INFO level
@@ -363,34 +609,7 @@ pub mod logger {
             return;
         }
         
-        let config = config();
-        
-        if config.json_format {
-            info!(
-                "{{\"level\":\"INFO\",\"component\":\"{}\",\"operation\":\"{}\",\"message\":\"{}\",\"data\":{}}}",
-                component.as_str(),
-                operation.as_str(),
-                message,
-                format!("{:?}", args)
-            );
-        } else {
-            let comp_str = if config.include_component {
-                format!("[{}]", component.as_str())
-            } else {
-                String::new()
-            };
-            
-            let op_str = if config.include_operation {
-                format!("[{}]", operation.as_str())
-            } else {
-                String::new()
-            };
-            
-            match args {
-                Some(data) => info!("{}{} {} - {:?}", comp_str, op_str, message, data),
-                None => info!("{}{} {}", comp_str, op_str, message),
-            }
-        }
+        info!("{}", format_event(config(), "INFO", component, operation, message, args));
     }
     
     /// Log a message at WARN level
@@ -411,34 +630,7 @@ pub mod logger {
             return;
         }
         
-        let config = config();
-        
-        if config.json_format {
-            warn!(
-                "{{\"level\":\"WARN\",\"component\":\"{}\",\"operation\":\"{}\",\"message\":\"{}\",\"data\":{}}}",
-                component.as_str(),
-                operation.as_str(),
-                message,
-                format!("{:?}", args)
-            );
-        } else {
-            let comp_str = if config.include_component {
-                format!("[{}]", component.as_str())
-            } else {
-                String::new()
-            };
-            
-            let op_str = if config.include_operation {
-                format!("[{}]", operation.as_str())
-            } else {
-                String::new()
-            };
-            
-            match args {
-                Some(data) => warn!("{}{} {} - {:?}", comp_str, op_str, message, data),
-                None => warn!("{}{} {}", comp_str, op_str, message),
-            }
-        }
+        warn!("{}", format_event(config(), "WARN", component, operation, message, args));
     }
     
     /// Log a message at ERROR level
@@ -463,33 +655,21 @@ pub mod logger {
         
         if config.json_format {
             let error_data = match error {
-                Some(e) => format!("{{\"code\":\"{}\",\"message\":\"{}\"}}", e.code(), e),
+                Some(e) => format!(
+                    "{{\"code\":{},\"message\":{}}}",
+                    json_string(e.code()),
+                    json_string(&e.to_string())
+                ),
                 None => "null".to_string(),
             };
             
-            error!(
-                "{{\"level\":\"ERROR\",\"component\":\"{}\",\"operation\":\"{}\",\"message\":\"{}\",\"error\":{}}}",
-                component.as_str(),
-                operation.as_str(),
-                message,
-                error_data
-            );
+            error!("{}", json_event("ERROR", component, operation, message, "error", &error_data));
         } else {
-            let comp_str = if config.include_component {
-                format!("[{}]", component.as_str())
-            } else {
-                String::new()
-            };
-            
-            let op_str = if config.include_operation {
-                format!("[{}]", operation.as_str())
-            } else {
-                String::new()
-            };
+            let prefix = format_prefix(config, component, operation);
             
             match error {
-                Some(e) => error!("{}{} {} - Error: {} ({})", comp_str, op_str, message, e, e.code()),
-                None => error!("{}{} {}", comp_str, op_str, message),
+                Some(e) => error!("{} {} - Error: {} ({})", prefix, message, e, e.code()),
+                None => error!("{} {}", prefix, message),
             }
         }
     }
@@ -505,7 +685,7 @@ pub mod logger {
             LogComponent::Parser,
             LogOperation::Parse,
             message,
-            Some(args),
+            Some(redact_args(config(), args)),
         );
     }
     
@@ -517,12 +697,13 @@ pub mod logger {
     /// * `input` - Input being tokenized
     /// * `tokens` - Resulting tokens
     pub fn tokenizer_event(message: &str, input: &str, tokens: Option<&[String]>) {
+        let input = redact_arg(config(), input);
         if let Some(t) = tokens {
             trace_log(
                 LogComponent::Tokenizer,
                 LogOperation::Tokenize,
                 message,
-                Some((input, t)),
+                Some((input, redact_args(config(), t))),
             );
         } else {
             trace_log(
@@ -572,6 +753,8 @@ pub mod init {
     use super::*;
     use crate::error::{Error, Result};
     use log::LevelFilter;
+    use std::io::Write;
+    use std::path::Path;
     
     /// Initialize logging with the specified options
     ///
@@ -605,7 +788,7 @@ pub mod init {
         // Initialize the logger configuration
         super::logger::init(options.config.clone());
         
-        match options.destination {
+        match &options.destination {
             LogDestination::Syslog => {
                 init_syslog(
                     options.level,
@@ -623,6 +806,21 @@ pub mod init {
                     options.syslog_identifier.clone().unwrap_or_else(|| "pam_args".to_string()),
                 )?;
             }
+            LogDestination::File(path) => {
+                init_file(options.level, path, &options.config)?;
+            }
+            LogDestination::SyslogAndFile(path) => {
+                init_syslog_and_file(
+                    options.level,
+                    options.syslog_facility.unwrap_or(syslog::Facility::LOG_AUTH),
+                    options.syslog_identifier.clone().unwrap_or_else(|| "pam_args".to_string()),
+                    path,
+                    &options.config,
+                )?;
+            }
+            LogDestination::Custom(logger) => {
+                init_custom(options.level, logger.clone())?;
+            }
             LogDestination::None => {
                 // Do nothing, use existing logger or none
             }
@@ -735,9 +933,193 @@ pub mod init {
         with_options(&options)
     }
     
+    /// Initialize logging to a file
+    ///
+    /// Log lines are appended to the file, which is created if needed, and
+    /// each record is flushed as soon as it is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to append log lines to
+    /// * `level` - The maximum log level to enable
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnexpectedError` if the file cannot be opened or a
+    /// logger is already installed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pam_args::logging::init;
+    /// use log::LevelFilter;
+    ///
+    /// init::for_file("/var/log/pam_mymodule.log", LevelFilter::Info)?;
+    /// # Ok::<(), pam_args::Error>(())
+    /// ```
+    pub fn for_file<P: AsRef<Path>>(path: P, level: LevelFilter) -> Result<()> {
+        let options = LogOptions {
+            destination: LogDestination::File(path.as_ref().to_path_buf()),
+            level,
+            ..Default::default()
+        };
+        
+        with_options(&options)
+    }
+    
+    /// Initialize logging through a caller-supplied logger
+    ///
+    /// Use this when the application already has its own `log::Log`
+    /// implementation, such as a bridge to another logging framework.
+    /// Messages are formatted according to the active `LogConfig` before
+    /// reaching the logger, so component and operation tags are kept. Use
+    /// `with_options` and `LogDestination::Custom` to also set the formatting.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to route messages through
+    /// * `level` - The maximum log level to enable
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnexpectedError` if a logger is already installed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pam_args::logging::init;
+    /// use log::LevelFilter;
+    ///
+    /// struct StderrLogger;
+    ///
+    /// impl log::Log for StderrLogger {
+    ///     fn enabled(&self, _metadata: &log::Metadata) -> bool {
+    ///         true
+    ///     }
+    ///
+    ///     fn log(&self, record: &log::Record) {
+    ///         eprintln!("{}", record.args());
+    ///     }
+    ///
+    ///     fn flush(&self) {}
+    /// }
+    ///
+    /// init::with_logger(Box::new(StderrLogger), LevelFilter::Info)?;
+    /// # Ok::<(), pam_args::Error>(())
+    /// ```
+    pub fn with_logger(logger: Box<dyn log::Log>, level: LevelFilter) -> Result<()> {
+        init_custom(level, Arc::from(logger))
+    }
+    
+    /// Logger that appends each record to a file
+    ///
+    /// Lines are prefixed with the level, and with a Unix timestamp when
+    /// `include_timestamps` is set. JSON log lines are written unchanged so
+    /// the file stays one JSON object per line.
+    pub(crate) struct FileLogger {
+        level: LevelFilter,
+        config: LogConfig,
+        file: std::sync::Mutex<std::fs::File>,
+    }
+    
+    impl FileLogger {
+        /// Opens the file for appending, creating it if needed
+        pub(crate) fn open(path: &Path, level: LevelFilter, config: LogConfig) -> Result<Self> {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| Error::UnexpectedError(format!(
+                    "Failed to open log file {}: {}", path.display(), e
+                )))?;
+            
+            Ok(Self { level, config, file: std::sync::Mutex::new(file) })
+        }
+    }
+    
+    impl log::Log for FileLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= self.level
+        }
+        
+        fn log(&self, record: &log::Record) {
+            if !self.enabled(record.metadata()) {
+                return;
+            }
+            
+            let line = if self.config.json_format {
+                record.args().to_string()
+            } else if self.config.include_timestamps {
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs());
+                format!("{} {} {}", timestamp, record.level(), record.args())
+            } else {
+                format!("{} {}", record.level(), record.args())
+            };
+            
+            // Logging must never panic, so write failures are ignored
+            if let Ok(mut file) = self.file.lock() {
+                let _ = writeln!(file, "{}", line).and_then(|_| file.flush());
+            }
+        }
+        
+        fn flush(&self) {
+            if let Ok(mut file) = self.file.lock() {
+                let _ = file.flush();
+            }
+        }
+    }
+    
+    /// Logger that forwards each record to several loggers
+    ///
+    /// Used for `LogDestination::Both` and custom loggers, since only one logger can be
+    /// installed with the `log` crate. Records above the configured level
+    /// are dropped before reaching any sink.
+    pub(crate) struct CombinedLogger {
+        level: LevelFilter,
+        sinks: Vec<Box<dyn log::Log>>,
+    }
+    
+    impl CombinedLogger {
+        /// Creates a logger forwarding records up to `level` to every sink
+        pub(crate) fn new(level: LevelFilter, sinks: Vec<Box<dyn log::Log>>) -> Self {
+            Self { level, sinks }
+        }
+    }
+    
+    impl log::Log for CombinedLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= self.level && self.sinks.iter().any(|sink| sink.enabled(metadata))
+        }
+        
+        fn log(&self, record: &log::Record) {
+            if record.level() > self.level {
+                return;
+            }
+            for sink in self.sinks.iter().filter(|sink| sink.enabled(record.metadata())) {
+                sink.log(record);
+            }
+        }
+        
+        fn flush(&self) {
+            for sink in &self.sinks {
+                sink.flush();
+            }
+        }
+    }
+    
     // Private helper functions for logger initialization
     
-    fn init_syslog(level: LevelFilter, facility: syslog::Facility, identifier: String) -> Result<()> {
+    fn syslog_logger(facility: syslog::Facility, identifier: String) -> Result<syslog::BasicLogger> {
         // Create a formatter with the specified identifier
         let formatter = syslog::Formatter3164 {
             facility,
@@ -750,8 +1132,22 @@ pub mod init {
         let syslog_logger = syslog::unix(formatter)
             .map_err(|e| Error::UnexpectedError(format!("Failed to connect to syslog: {}", e)))?;
         
+        Ok(syslog::BasicLogger::new(syslog_logger))
+    }
+    
+    fn terminal_logger(level: LevelFilter) -> env_logger::Logger {
+        // Create a terminal logger using env_logger
+        use env_logger::{Builder, Env};
+        
+        // Build a logger with the specified level
+        let mut builder = Builder::from_env(Env::default().default_filter_or(level.to_string()));
+        builder.format_timestamp_secs(); // Add timestamps to logs
+        builder.build()
+    }
+    
+    fn init_syslog(level: LevelFilter, facility: syslog::Facility, identifier: String) -> Result<()> {
         // Initialize the logger
-        log::set_boxed_logger(Box::new(syslog::BasicLogger::new(syslog_logger)))
+        log::set_boxed_logger(Box::new(syslog_logger(facility, identifier)?))
             .map_err(|e| Error::UnexpectedError(format!("Failed to set logger: {}", e)))?;
         
         // Set the log level
@@ -761,29 +1157,85 @@ pub mod init {
     }
     
     fn init_terminal(level: LevelFilter) -> Result<()> {
-        // Create a terminal logger using env_logger
-        use env_logger::{Builder, Env};
+        // Initialize the logger
+        let logger = terminal_logger(level);
+        let max_level = logger.filter();
+        log::set_boxed_logger(Box::new(logger))
+            .map_err(|e| Error::UnexpectedError(format!("Failed to initialize terminal logger: {}", e)))?;
         
-        // Build a logger with the specified level
-        let mut builder = Builder::from_env(Env::default().default_filter_or(level.to_string()));
-        builder.format_timestamp_secs(); // Add timestamps to logs
+        // Set the log level
+        log::set_max_level(max_level);
+        
+        Ok(())
+    }
+    
+    fn init_custom(level: LevelFilter, logger: Arc<dyn log::Log>) -> Result<()> {
+        // Filter by level before the records reach the caller's logger
+        let logger = CombinedLogger::new(level, vec![Box::new(logger)]);
         
         // Initialize the logger
-        builder.try_init()
-            .map_err(|e| Error::UnexpectedError(format!("Failed to initialize terminal logger: {}", e)))?;
+        log::set_boxed_logger(Box::new(logger))
+            .map_err(|e| Error::UnexpectedError(format!("Failed to set logger: {}", e)))?;
+        
+        // Set the log level
+        log::set_max_level(level);
+        
+        Ok(())
+    }
+    
+    fn init_file(level: LevelFilter, path: &Path, config: &LogConfig) -> Result<()> {
+        // Initialize the logger
+        log::set_boxed_logger(Box::new(FileLogger::open(path, level, config.clone())?))
+            .map_err(|e| Error::UnexpectedError(format!("Failed to set logger: {}", e)))?;
+        
+        // Set the log level
+        log::set_max_level(level);
+        
+        Ok(())
+    }
+    
+    fn init_syslog_and_file(
+        level: LevelFilter,
+        facility: syslog::Facility,
+        identifier: String,
+        path: &Path,
+        config: &LogConfig,
+    ) -> Result<()> {
+        // Send every record to both syslog and the file
+        let logger = CombinedLogger::new(
+            level,
+            vec![
+                Box::new(syslog_logger(facility, identifier)?),
+                Box::new(FileLogger::open(path, level, config.clone())?),
+            ],
+        );
+        
+        // Initialize the logger
+        log::set_boxed_logger(Box::new(logger))
+            .map_err(|e| Error::UnexpectedError(format!("Failed to set logger: {}", e)))?;
+        
+        // Set the log level
+        log::set_max_level(level);
         
         Ok(())
     }
     
     fn init_both(level: LevelFilter, facility: syslog::Facility, identifier: String) -> Result<()> {
-        // Create a logger that sends to both syslog and terminal
+        // Send every record to both syslog and the terminal
+        let logger = CombinedLogger::new(
+            level,
+            vec![
+                Box::new(syslog_logger(facility, identifier)?),
+                Box::new(terminal_logger(level)),
+            ],
+        );
         
-        // Initialize syslog logger
-        init_syslog(level, facility, identifier)?;
+        // Initialize the logger
+        log::set_boxed_logger(Box::new(logger))
+            .map_err(|e| Error::UnexpectedError(format!("Failed to set logger: {}", e)))?;
         
-        // Also initialize terminal logger (this will fail if a logger is already set)
-        // So we just print to stderr directly
-        eprintln!("Note: Dual output logging is limited. Logs will primarily go to syslog, with some messages to terminal.");
+        // Set the log level
+        log::set_max_level(level);
         
         Ok(())
     }
@@ -957,13 +1409,9 @@ mod tests {
     
     #[test]
     fn test_logger_config() {
-        // Test that the logger configuration can be initialized and retrieved
-        let config = LogConfig {
-            include_timestamps: false,
-            include_component: true,
-            include_operation: false,
-            json_format: true,
-        };
+        // Test that the logger configuration can be initialized and retrieved.
+        // Tests share the global configuration, so they all install the same one.
+        let config = crate::testing::test_log_config();
         
         logger::init(config.clone());
         
@@ -974,6 +1422,214 @@ mod tests {
         assert_eq!(retrieved_config.json_format, config.json_format);
     }
     
+    #[test]
+    fn test_json_event_is_valid_json() {
+        let message = "value \"quoted\" with \\ backslash\nand newline\u{1}";
+        let data = logger::json_string(&format!("{:?}", Some(("USER", "a\"b"))));
+        let line = logger::json_event("DEBUG", LogComponent::Parser, LogOperation::Parse, message, "data", &data);
+        
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["level"], "DEBUG");
+        assert_eq!(parsed["component"], "PARSER");
+        assert_eq!(parsed["operation"], "PARSE");
+        assert_eq!(parsed["message"], message);
+        assert_eq!(parsed["data"], r#"Some(("USER", "a\"b"))"#);
+        
+        let error = crate::error::Error::InvalidValue("KEY".to_string(), "say \"hi\"".to_string());
+        let error_data = format!(
+            "{{\"code\":{},\"message\":{}}}",
+            logger::json_string(error.code()),
+            logger::json_string(&error.to_string())
+        );
+        let line = logger::json_event("ERROR", LogComponent::General, LogOperation::Error, "failed", "error", &error_data);
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["error"]["code"], "INVALID_VALUE");
+        assert_eq!(parsed["error"]["message"], error.to_string());
+    }
+    
+    /// Sink that records the messages it receives
+    struct RecordingSink(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+    
+    impl log::Log for RecordingSink {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+        
+        fn log(&self, record: &log::Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+        
+        fn flush(&self) {}
+    }
+    
+    #[test]
+    fn test_custom_logger_receives_tagged_messages() {
+        // The capturing test logger is installed through init::with_logger
+        crate::testing::capture_logs();
+        
+        crate::log_warn!(LogComponent::Validator, LogOperation::Validate, "custom logger marker");
+        
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        let message = warnings
+            .iter()
+            .find(|message| message.contains("custom logger marker"))
+            .expect("message should reach the custom logger");
+        assert!(message.contains("VALIDATOR"));
+        assert!(message.contains("VALIDATE"));
+    }
+    
+    #[test]
+    fn test_custom_destination_debug() {
+        let destination = LogDestination::Custom(Arc::new(RecordingSink(Default::default())));
+        assert_eq!(format!("{:?}", destination), "Custom");
+        assert_eq!(format!("{:?}", LogDestination::File(PathBuf::from("/tmp/a.log"))), "File(\"/tmp/a.log\")");
+    }
+    
+    #[test]
+    fn test_combined_logger_reaches_both_sinks() {
+        use log::Log;
+        
+        let first = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let second = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let logger = init::CombinedLogger::new(
+            LevelFilter::Info,
+            vec![Box::new(RecordingSink(first.clone())), Box::new(RecordingSink(second.clone()))],
+        );
+        
+        logger.log(&log::Record::builder()
+            .level(log::Level::Warn)
+            .args(format_args!("parsed arguments"))
+            .build());
+        logger.log(&log::Record::builder()
+            .level(log::Level::Debug)
+            .args(format_args!("too verbose"))
+            .build());
+        
+        assert_eq!(*first.lock().unwrap(), ["parsed arguments"]);
+        assert_eq!(*second.lock().unwrap(), ["parsed arguments"]);
+        assert!(logger.enabled(&log::Metadata::builder().level(log::Level::Info).build()));
+        assert!(!logger.enabled(&log::Metadata::builder().level(log::Level::Debug).build()));
+    }
+    
+    #[test]
+    fn test_file_logger_appends_lines() {
+        use log::Log;
+        
+        let path = std::env::temp_dir().join(format!("pam-args-file-logger-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let config = LogConfig {
+            include_timestamps: false,
+            ..LogConfig::default()
+        };
+        
+        let logger = init::FileLogger::open(&path, LevelFilter::Info, config.clone()).unwrap();
+        logger.log(&log::Record::builder()
+            .level(log::Level::Warn)
+            .args(format_args!("[PARSER][PARSE] first"))
+            .build());
+        logger.log(&log::Record::builder()
+            .level(log::Level::Debug)
+            .args(format_args!("filtered out"))
+            .build());
+        
+        // Reopening appends instead of truncating
+        let logger = init::FileLogger::open(&path, LevelFilter::Info, config).unwrap();
+        logger.log(&log::Record::builder()
+            .level(log::Level::Info)
+            .args(format_args!("second"))
+            .build());
+        
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(content, "WARN [PARSER][PARSE] first\nINFO second\n");
+    }
+    
+    #[test]
+    fn test_file_logger_open_failure() {
+        let path = std::path::Path::new("/nonexistent-dir/pam-args.log");
+        match init::FileLogger::open(path, LevelFilter::Info, LogConfig::default()) {
+            Err(Error::UnexpectedError(msg)) => assert!(msg.contains("/nonexistent-dir/pam-args.log")),
+            Err(other) => panic!("Expected UnexpectedError, got {:?}", other),
+            Ok(_) => panic!("Expected an error for an unwritable path"),
+        }
+    }
+    
+    #[test]
+    fn test_format_prefix_styles() {
+        let render = |style: LogPrefixStyle, separator: &str| {
+            let config = LogConfig {
+                prefix_style: style,
+                component_operation_separator: separator.to_string(),
+                ..LogConfig::default()
+            };
+            logger::format_prefix(&config, LogComponent::Parser, LogOperation::Parse)
+        };
+        
+        // Default matches the historical format
+        assert_eq!(
+            logger::format_prefix(&LogConfig::default(), LogComponent::Parser, LogOperation::Parse),
+            "[PARSER][PARSE]"
+        );
+        
+        assert_eq!(render(LogPrefixStyle::Brackets, ""), "[PARSER][PARSE]");
+        assert_eq!(render(LogPrefixStyle::Brackets, " "), "[PARSER] [PARSE]");
+        assert_eq!(render(LogPrefixStyle::Colon, ""), "PARSER:PARSE:");
+        assert_eq!(render(LogPrefixStyle::Colon, " "), "PARSER: PARSE:");
+        assert_eq!(render(LogPrefixStyle::None, "/"), "PARSER/PARSE");
+        
+        // Disabled parts are omitted along with the separator
+        let config = LogConfig {
+            include_operation: false,
+            component_operation_separator: "/".to_string(),
+            ..LogConfig::default()
+        };
+        assert_eq!(
+            logger::format_prefix(&config, LogComponent::Parser, LogOperation::Parse),
+            "[PARSER]"
+        );
+    }
+    
+    #[test]
+    fn test_redacted_arguments() {
+        let args = vec!["DEBUG".to_string(), "PASSWORD=hunter2".to_string(), "USER=admin".to_string()];
+        let config = LogConfig {
+            include_timestamps: false,
+            ..LogConfig::default()
+        };
+        let redacted = logger::redact_args(&config, &args);
+        assert_eq!(redacted, ["DEBUG", "PASSWORD=***", "USER=admin"]);
+        
+        let text = logger::format_event(&config, "DEBUG", LogComponent::Parser, LogOperation::Parse, "Parsing", Some(&redacted));
+        assert_eq!(text, r#"[PARSER][PARSE] Parsing - ["DEBUG", "PASSWORD=***", "USER=admin"]"#);
+        assert!(!text.contains("hunter2"));
+        
+        let json_config = LogConfig {
+            json_format: true,
+            ..config.clone()
+        };
+        let json = logger::format_event(&json_config, "DEBUG", LogComponent::Parser, LogOperation::Parse, "Parsing", Some(&redacted));
+        let event: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(event["data"], r#"["DEBUG", "PASSWORD=***", "USER=admin"]"#);
+        assert!(!json.contains("hunter2"));
+        
+        // Keys are matched without case and the list is configurable
+        let custom = LogConfig {
+            redact_keys: vec!["user".to_string()],
+            ..config
+        };
+        assert_eq!(
+            logger::redact_args(&custom, &args),
+            ["DEBUG", "PASSWORD=hunter2", "USER=***"]
+        );
+        
+        // Every element of a bracketed group or line is checked
+        let grouped = vec!["[USER=a,PASSWORD=x]".to_string(), "DEBUG TOKEN='a b,c' HOST=h".to_string()];
+        assert_eq!(
+            logger::redact_args(&LogConfig::default(), &grouped),
+            ["[USER=a,PASSWORD=***]", "DEBUG TOKEN=*** HOST=h"]
+        );
+    }
+    
     #[test]
     fn test_specialized_logging_functions() {
         // This test just verifies that the specialized logging functions don't panic
@@ -0,0 +1,4466 @@
+//! Argument parser for the pam-args library.
+//!
+//! This module ties the argument definitions, configuration, tokenizer and
+//! storage together. An `ArgumentParser` holds the registered flags and
+//! key-value pairs, and parsing classifies every token as a flag, an explicit
+//! key-value pair, a multi key-value pair or non-argument text, in that order.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::{self, BufRead};
+use std::sync::{Arc, OnceLock};
+
+use crate::args::{AllowedKeyValueFormats, Flag, KeyValue, TypedValue};
+use crate::config::{BracketDuplicatePolicy, ParserConfig};
+use crate::conversion::{converter, format, ConverterConfig, FormatDetectionResult, FromArgValue};
+use crate::error::{Error, Result};
+use crate::help;
+use crate::logging::logger::{redact_value, redact_with};
+use crate::logging::{LogComponent, LogOperation};
+use crate::storage::{DefaultKeyValueStore, KeyValueStore, NonArgTextStore};
+use crate::tokenizer::{Tokenizer, TokenizerConfig};
+use crate::utils::case::{self, Normalizer};
+use crate::utils::{escaping, strings, TextProcessingConfig};
+use log::{debug, trace};
+
+/// Maximum nesting of `${KEY}` references expanded within a single value
+const MAX_TEMPLATE_DEPTH: usize = 8;
+
+/// Maximum number of `${KEY}` references substituted while expanding one value
+const MAX_TEMPLATE_SUBSTITUTIONS: usize = 256;
+
+/// Maximum length of a value after its references are expanded
+const MAX_EXPANDED_LENGTH: usize = 64 * 1024;
+
+/// Parser for PAM module arguments
+///
+/// Flags and key-value pairs are registered with the builder methods, and
+/// `parse` turns a list of arguments into a `ParseResult`.
+///
+/// # Examples
+///
+/// ```
+/// use pam_args::{ArgumentParser, Flag, KeyValue};
+///
+/// let parser = ArgumentParser::new()
+///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+///     .key_value(KeyValue::new("USER", "Username for authentication"));
+///
+/// let result = parser.parse(vec!["DEBUG".to_string(), "USER=admin".to_string()]).unwrap();
+/// assert!(result.is_present("DEBUG"));
+/// assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArgumentParser {
+    /// Parser configuration
+    config: ParserConfig,
+
+    /// Registered flag definitions
+    flags: Vec<Flag>,
+
+    /// Registered key-value definitions
+    key_values: Vec<KeyValue>,
+
+    /// Key collecting unrecognized key-value pairs, if any
+    catch_all: Option<String>,
+
+    /// Name of the positional slot and whether it is required, if any
+    positional: Option<(String, bool)>,
+
+    /// Whether parsing fails when no argument is given at all
+    require_any: bool,
+
+    /// Converter for key-value pairs without their own type converter, if any
+    default_converter: Option<FallbackConverter>,
+
+    /// Name of the flag requesting the help text, if any
+    help_flag: Option<String>,
+
+    /// Name of the flag requesting the version and the version text, if any
+    version_flag: Option<(String, String)>,
+
+    /// Outcome of validating the definitions, computed on first use
+    validated: OnceLock<Result<()>>,
+}
+
+/// Type-erased conversion of a key's value, given the key, value and conversion settings
+type ConvertFn = dyn Fn(&str, &str, &ConverterConfig) -> Result<TypedValue> + Send + Sync;
+
+/// Type converter used for keys without their own, see `ArgumentParser::default_converter`
+#[derive(Clone)]
+struct FallbackConverter(Arc<ConvertFn>);
+
+impl std::fmt::Debug for FallbackConverter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FallbackConverter(..)")
+    }
+}
+
+/// Where the value of a key came from
+///
+/// Reported by `ParseResult::source_of`. The parser reads arguments only,
+/// so values from other sources will be reported with new variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// The key was given in the arguments
+    Argument,
+
+    /// The key was not given and has a default value
+    Default,
+
+    /// The key was not given and was set by a flag that implies it
+    Implied,
+}
+
+/// Number of parsed arguments of each kind
+///
+/// Returned by `ParseResult::counts`. Every key given in the arguments counts
+/// once, in exactly one of `key_values`, `key_only` and `empty_values`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArgCounts {
+    /// Flags that were present
+    pub flags: usize,
+
+    /// Keys with a non-empty value
+    pub key_values: usize,
+
+    /// Keys given without a value, as in `KEY`
+    pub key_only: usize,
+
+    /// Keys given with an empty value, as in `KEY=`
+    pub empty_values: usize,
+
+    /// Entries of non-argument text
+    pub non_arg_text: usize,
+}
+
+/// Result of a successful parse
+///
+/// Holds the flags that were present, the key-value store and the collected
+/// non-argument text.
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    /// Names of the flags that were present, in order of first appearance
+    flags: Vec<String>,
+
+    /// Explicit and multi key-value pairs
+    store: DefaultKeyValueStore,
+
+    /// Text that did not match any argument
+    non_arg_text: NonArgTextStore,
+
+    /// Configuration used when converting values
+    conversion: ConverterConfig,
+
+    /// Comment lines read before the argument line, when retained
+    comments: Vec<String>,
+
+    /// Leading token of a parsed line, when captured as the module name
+    module_name: Option<String>,
+
+    /// Values produced by key-value type converters, by normalized key
+    typed_values: HashMap<String, TypedValue>,
+
+    /// Default values of the registered key-value pairs, by normalized key
+    defaults: HashMap<String, String>,
+
+    /// Separator for joining adjacent non-argument text, if enabled
+    join_non_arg: Option<char>,
+
+    /// Whether the last recorded token was non-argument text
+    last_was_non_arg: bool,
+
+    /// Function canonicalizing argument names in place of case normalization, if any
+    key_normalizer: Option<Normalizer>,
+
+    /// Keys set by flag implications, by normalized key
+    implied: HashSet<String>,
+
+    /// Entries collected under a repeatable key, by normalized key
+    repeated: HashMap<String, Vec<String>>,
+
+    /// Captured positional tokens, by normalized slot name
+    positionals: HashMap<String, String>,
+
+    /// Values of flags given as `FLAG=true` or `FLAG=false`, by normalized name
+    flag_values: HashMap<String, bool>,
+
+    /// Type converter failures collected with `collect_conversion_errors`
+    conversion_errors: Vec<(String, Error)>,
+
+    /// Names of registered arguments as they were typed, by normalized canonical name
+    spellings: HashMap<String, String>,
+
+    /// Whether values are lowercased when they are stored
+    lowercase_values: bool,
+
+    /// Tokens kept as non-argument text or catch-all entries, in input order
+    unrecognized: Vec<String>,
+}
+
+impl ArgumentParser {
+    /// Creates a new parser with default configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ArgumentParser;
+    ///
+    /// let parser = ArgumentParser::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_config(ParserConfig::default())
+    }
+
+    /// Creates a new parser with the given configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The parser configuration to use
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder()
+    ///     .collect_non_argument_text(true)
+    ///     .build();
+    /// let parser = ArgumentParser::with_config(config);
+    /// ```
+    pub fn with_config(config: ParserConfig) -> Self {
+        Self {
+            config,
+            flags: Vec::new(),
+            key_values: Vec::new(),
+            catch_all: None,
+            positional: None,
+            require_any: false,
+            default_converter: None,
+            help_flag: None,
+            version_flag: None,
+            validated: OnceLock::new(),
+        }
+    }
+
+    /// Registers a flag
+    ///
+    /// # Arguments
+    ///
+    /// * `flag` - The flag definition
+    ///
+    /// # Returns
+    ///
+    /// The parser with the flag registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    /// ```
+    pub fn flag(mut self, flag: Flag) -> Self {
+        self.flags.push(flag);
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Registers a key-value pair
+    ///
+    /// # Arguments
+    ///
+    /// * `key_value` - The key-value definition
+    ///
+    /// # Returns
+    ///
+    /// The parser with the key-value pair registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username for authentication"));
+    /// ```
+    pub fn key_value(mut self, key_value: KeyValue) -> Self {
+        self.key_values.push(key_value);
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Requires at least one argument to be given
+    ///
+    /// Input without any flag, key-value pair, catch-all entry or positional
+    /// token is rejected with `Error::InvalidInput("no arguments provided")`.
+    /// Non-argument text and default values do not count. Unlike
+    /// `KeyValue::required`, this does not care which argument is given.
+    ///
+    /// # Returns
+    ///
+    /// The parser requiring at least one argument
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .require_any();
+    ///
+    /// assert!(parser.parse(vec!["DEBUG".to_string()]).is_ok());
+    /// assert_eq!(
+    ///     parser.parse(Vec::new()).unwrap_err(),
+    ///     Error::InvalidInput("no arguments provided".to_string())
+    /// );
+    /// ```
+    pub fn require_any(mut self) -> Self {
+        self.require_any = true;
+        self
+    }
+
+    /// Converts every key-value pair without its own type converter to `T`
+    ///
+    /// Values of keys that have no `KeyValue::type_converter` are converted
+    /// with `FromArgValue` and the parser's conversion settings, and the
+    /// result is available from `ParseResult::typed_value_of::<T>`. A key's
+    /// own type converter always takes precedence. A value that cannot be
+    /// converted fails the parse, or is collected with
+    /// `collect_conversion_errors`. Keys given without a value are skipped.
+    ///
+    /// # Returns
+    ///
+    /// The parser with the default converter set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{converter, ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("WIDTH", "Width in pixels"))
+    ///     .key_value(KeyValue::new("SCALE", "Scale factor")
+    ///         .type_converter(converter::from_str::<f64>()))
+    ///     .default_converter::<u32>();
+    ///
+    /// let result = parser.parse(vec!["WIDTH=80".to_string(), "SCALE=1.5".to_string()]).unwrap();
+    /// assert_eq!(result.typed_value_of::<u32>("WIDTH"), Some(&80));
+    /// assert_eq!(result.typed_value_of::<f64>("SCALE"), Some(&1.5));
+    /// ```
+    pub fn default_converter<T: FromArgValue + Send + Sync + 'static>(mut self) -> Self {
+        self.default_converter = Some(FallbackConverter(Arc::new(|key, value, conversion| {
+            converter::convert_for_key::<T>(value, key, Some(conversion))
+                .map(|converted| Arc::new(converted) as TypedValue)
+        })));
+        self
+    }
+
+    /// Adds the flag and key-value definitions of another parser
+    ///
+    /// The definitions are appended after this parser's own, and the merged
+    /// set is checked like `validate` does, so a name used by both parsers is
+    /// a `DuplicateArgName` error. On error this parser is left unchanged.
+    /// The configuration, catch-all key and positional slot of this parser
+    /// are kept; those of `other` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The parser whose definitions to add
+    ///
+    /// # Returns
+    ///
+    /// Ok if the merged definitions are consistent
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found in the merged definitions
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let mut parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    /// let module = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username"));
+    ///
+    /// parser.extend(&module).unwrap();
+    /// let result = parser.parse(vec!["DEBUG".to_string(), "USER=admin".to_string()]).unwrap();
+    /// assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+    /// ```
+    pub fn extend(&mut self, other: &ArgumentParser) -> Result<()> {
+        let mut merged = self.clone();
+        merged.flags.extend(other.flags.iter().cloned());
+        merged.key_values.extend(other.key_values.iter().cloned());
+        merged.validated = OnceLock::new();
+        merged.validate()?;
+        *self = merged;
+        Ok(())
+    }
+
+    /// Collects unrecognized key-value pairs under a catch-all key
+    ///
+    /// Tokens of the form `KEY=VALUE` (or `KEY=`) whose key is not registered
+    /// are kept as they were given, with the value processed like any other,
+    /// and read back in input order with `ParseResult::entries_of`. Known
+    /// keys are matched as usual, and bare words are still flags, multi
+    /// key-value pairs or non-argument text. The catch-all takes precedence
+    /// over multi key-value pairs, and its name may not be used by another
+    /// argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name to collect the unrecognized pairs under
+    ///
+    /// # Returns
+    ///
+    /// The parser with the catch-all key set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username"))
+    ///     .catch_all("EXTRA");
+    ///
+    /// let args = vec!["USER=admin".to_string(), "retry=3".to_string(), "mode=fast".to_string()];
+    /// let result = parser.parse(args).unwrap();
+    /// assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+    /// assert_eq!(result.entries_of("EXTRA"), vec!["retry=3", "mode=fast"]);
+    /// ```
+    pub fn catch_all(mut self, key: &str) -> Self {
+        self.catch_all = Some(key.to_string());
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Captures the first positional token into a named slot
+    ///
+    /// The first bare word outside brackets that is not a registered flag
+    /// or key is stored in the slot and read back with
+    /// `ParseResult::positional`. Later bare words take the usual path to
+    /// multi key-value pairs or non-argument text. A required positional
+    /// that is missing is reported as `RequiredArgMissing`, and the name may
+    /// not be used by another argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the positional slot
+    /// * `required` - Whether parsing fails when no positional token is given
+    ///
+    /// # Returns
+    ///
+    /// The parser with the positional slot set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .positional("PROFILE", true)
+    ///     .key_value(KeyValue::new("USER", "Username"));
+    ///
+    /// let result = parser.parse(vec!["strict".to_string(), "USER=admin".to_string()]).unwrap();
+    /// assert_eq!(result.positional("PROFILE"), Some("strict"));
+    /// ```
+    pub fn positional(mut self, name: &str, required: bool) -> Self {
+        self.positional = Some((name.to_string(), required));
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Registers a flag that requests the help text
+    ///
+    /// The flag is registered like any other. When it is given, parsing
+    /// stops with `Error::HelpRequested` carrying the text of `help`, before
+    /// required arguments, dependencies and exclusions are checked, so the
+    /// caller can print the text instead of treating it as a failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the help flag
+    ///
+    /// # Returns
+    ///
+    /// The parser with the help flag registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username").required())
+    ///     .help_flag("HELP");
+    ///
+    /// match parser.parse(vec!["HELP".to_string()]) {
+    ///     Err(Error::HelpRequested(text)) => assert!(text.contains("Username")),
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// ```
+    pub fn help_flag(mut self, name: &str) -> Self {
+        self.flags.push(Flag::new(name, "Print this help text"));
+        self.help_flag = Some(name.to_string());
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Registers a flag that requests the version
+    ///
+    /// The flag is registered like any other. When it is given, parsing
+    /// stops with `Error::VersionRequested` carrying `version`, before
+    /// required arguments, dependencies and exclusions are checked. The help
+    /// flag wins when both are given.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the version flag
+    /// * `version` - The version text to report
+    ///
+    /// # Returns
+    ///
+    /// The parser with the version flag registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error};
+    ///
+    /// let parser = ArgumentParser::new().version_flag("VERSION", "pam_example 1.2.0");
+    ///
+    /// assert_eq!(
+    ///     parser.parse(vec!["VERSION".to_string()]).unwrap_err(),
+    ///     Error::VersionRequested("pam_example 1.2.0".to_string())
+    /// );
+    /// ```
+    pub fn version_flag(mut self, name: &str, version: &str) -> Self {
+        self.flags.push(Flag::new(name, "Print the version"));
+        self.version_flag = Some((name.to_string(), version.to_string()));
+        self.validated = OnceLock::new();
+        self
+    }
+
+    /// Checks the registered definitions for conflicts
+    ///
+    /// Two arguments may not share a name or alias, a key with allowed values
+    /// must have a format that takes a value, and default values must fit
+    /// their key's formats and allowed values. Names differing only by
+    /// case and required keys with a default are reported with a warning,
+    /// or for the latter an error with `strict_definitions`.
+    ///
+    /// The check runs once: the outcome is cached until another argument is
+    /// registered, so warnings are logged once however often the parser is
+    /// used. `parse` calls this first, so calling it directly is only needed
+    /// to report definition errors before any input is seen.
+    ///
+    /// # Returns
+    ///
+    /// Ok if the definitions are consistent, or the first problem found
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("DEBUG", "Debug level"));
+    ///
+    /// assert_eq!(parser.validate(), Err(Error::DuplicateArgName("DEBUG".to_string())));
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        self.validated.get_or_init(|| self.validate_definitions()).clone()
+    }
+
+    /// Returns the parser configuration
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ArgumentParser;
+    ///
+    /// let parser = ArgumentParser::new();
+    /// assert!(parser.config().is_case_sensitive());
+    /// ```
+    pub fn config(&self) -> &ParserConfig {
+        &self.config
+    }
+
+    /// Checks whether input relies on multi key-value handling
+    ///
+    /// Returns true if any argument has bracketed content holding a token
+    /// that is neither a registered flag nor a registered key-value pair.
+    /// Such tokens are only stored as key-value pairs when
+    /// `enable_multi_key_value` is set, so modules can use this to warn when
+    /// the option is off. Arguments that fail to tokenize are ignored here
+    /// and reported by `parse`.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to inspect
+    ///
+    /// # Returns
+    ///
+    /// Whether parsing the arguments needs multi key-value handling
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// assert!(parser.needs_multi_key_value(&["DEBUG", "[HOST=localhost,PORT=22]"]));
+    /// assert!(!parser.needs_multi_key_value(&["DEBUG", "[DEBUG]"]));
+    /// ```
+    pub fn needs_multi_key_value(&self, args: &[&str]) -> bool {
+        let tokenizer = Tokenizer::with_config(TokenizerConfig::from(&self.config));
+        args.iter().any(|arg| {
+            let Ok(tokenized) = tokenizer.tokenize_arg(arg) else {
+                return false;
+            };
+            tokenized.groups.iter().flat_map(|group| &tokenized.tokens[group.clone()]).any(|token| {
+                let token = token.trim();
+                !token.is_empty()
+                    && self.find_flag(token).is_none()
+                    && self.find_key_value(format::detect(token).key.trim()).is_none()
+            })
+        })
+    }
+
+    /// Returns the registered arguments that did not appear in a parse result
+    ///
+    /// This shows which options a given configuration does not exercise.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - A result produced by this parser
+    ///
+    /// # Returns
+    ///
+    /// Names of the unused flags followed by the unused key-value pairs, in
+    /// registration order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("USER", "Username for authentication"));
+    ///
+    /// let result = parser.parse(vec!["DEBUG".to_string()]).unwrap();
+    /// assert_eq!(parser.unused_after(&result), vec!["USER"]);
+    /// ```
+    pub fn unused_after(&self, result: &ParseResult) -> Vec<&str> {
+        self.flags
+            .iter()
+            .map(|flag| flag.name())
+            .chain(self.key_values.iter().map(|kv| kv.name()))
+            .filter(|name| !result.is_present(name))
+            .collect()
+    }
+
+    /// Generates help text describing the registered arguments
+    ///
+    /// Flags are listed before key-value pairs, each in registration order,
+    /// with descriptions aligned in a second column and wrapped to
+    /// `ParserConfig::help_width`. Required arguments are marked, and allowed
+    /// values, defaults, aliases, dependencies, exclusions and examples are
+    /// listed under the description.
+    ///
+    /// # Returns
+    ///
+    /// The help text, or an empty string if no arguments are registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("USER", "Username for authentication").required());
+    ///
+    /// let help = parser.help();
+    /// assert!(help.contains("DEBUG         Enable debug mode"));
+    /// assert!(help.contains("USER=<value>  Username for authentication (required)"));
+    /// ```
+    pub fn help(&self) -> String {
+        help::render(&self.flags, &self.key_values, self.config.help_width())
+    }
+
+    /// Parses a list of arguments
+    ///
+    /// Each argument is tokenized first, so a bracketed argument such as
+    /// `[HOST=localhost,PORT=22]` yields one token per element. Every token is
+    /// then matched against the registered flags and key-value pairs. Tokens
+    /// that match neither are stored as multi key-value pairs or collected as
+    /// non-argument text when the configuration enables it.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to parse
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// * Two registered arguments share a name
+    /// * An argument is malformed (unclosed quotes or brackets, nested brackets)
+    /// * A key-value pair is given in a format it does not allow
+    /// * A key-value pair is given a value outside its allowed values
+    /// * An argument is not recognized and cannot be collected
+    /// * A required key-value pair is missing
+    /// * A present argument is missing one of its dependencies
+    /// * Two mutually exclusive arguments are both present
+    ///
+    /// A help or version flag registered with `help_flag` or `version_flag`
+    /// stops parsing with `Error::HelpRequested` or `Error::VersionRequested`.
+    ///
+    /// With `ParserConfigBuilder::error_context` set, the error is wrapped in
+    /// `Error::WithContext` together with `args`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder()
+    ///     .enable_multi_key_value(true)
+    ///     .collect_non_argument_text(true)
+    ///     .build();
+    /// let parser = ArgumentParser::with_config(config)
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// let args = vec!["DEBUG", "[HOST=localhost,PORT=22]", "hello"];
+    /// let result = parser.parse(args.into_iter().map(String::from).collect()).unwrap();
+    ///
+    /// assert!(result.is_present("DEBUG"));
+    /// assert_eq!(result.value_of::<u16>("PORT"), Some(22));
+    /// assert_eq!(result.non_argument_text(), vec!["hello"]);
+    /// ```
+    pub fn parse(&self, args: Vec<String>) -> Result<ParseResult> {
+        let result = self.parse_args(&args);
+        if self.config.error_context() {
+            result.map_err(|e| e.with_context(&args))
+        } else {
+            result
+        }
+    }
+
+    /// Parses the arguments, without attaching them to a returned error
+    fn parse_args(&self, args: &[String]) -> Result<ParseResult> {
+        debug!("Parsing {} argument(s)", args.len());
+        self.validate()?;
+
+        let tokenizer = Tokenizer::with_config(TokenizerConfig::from(&self.config));
+        trace!("Tokenizer config: {:?}", tokenizer.config());
+        let text_config = TextProcessingConfig::from(&self.config);
+
+        let mut result = ParseResult::new(&self.config);
+        for key_value in &self.key_values {
+            if let Some(default) = key_value.get_default_value() {
+                let key = result.normalize_key(key_value.name());
+                let default = if self.config.lowercase_values() {
+                    default.to_lowercase()
+                } else {
+                    default.to_string()
+                };
+                result.defaults.insert(key, default);
+            }
+        }
+
+        for arg in args {
+            if let Some(max) = self.config.max_arg_length() {
+                if arg.len() > max {
+                    debug!("Rejecting argument of {} bytes", arg.len());
+                    return Err(Error::InvalidInput(format!("argument longer than {} bytes", max)));
+                }
+            }
+            let stripped;
+            let arg = if self.config.strip_invisible_chars() {
+                stripped = strings::strip_invisible(arg);
+                &stripped
+            } else {
+                arg
+            };
+            let tokenized = tokenizer.tokenize_arg(arg)?;
+            trace!(
+                "Argument '{}' produced {} token(s) (bracketed: {})",
+                self.redacted(arg), tokenized.tokens.len(), tokenized.has_bracketed_content
+            );
+            if tokenized.has_bracketed_content
+                && self.config.require_multi_key_value_for_brackets()
+                && !self.config.enable_multi_key_value()
+            {
+                debug!("Rejecting bracketed argument '{}'", self.redacted(arg));
+                return Err(Error::InvalidInput("bracketed groups are not enabled".to_string()));
+            }
+            let mut group_keys = vec![HashSet::new(); tokenized.groups.len()];
+            for (index, token) in tokenized.tokens.iter().enumerate() {
+                if token.trim().is_empty() {
+                    continue;
+                }
+                // Whitespace around an element belongs to its value unless values are trimmed
+                let token = if self.config.trim_values() { token.trim() } else { token.as_str() };
+                let group = tokenized.groups.iter().position(|group| group.contains(&index));
+                if let Some(group) = group {
+                    if !self.check_group_duplicate(token, &mut group_keys[group])? {
+                        continue;
+                    }
+                }
+                self.classify_token(token, group.is_some(), &text_config, &mut result)?;
+            }
+        }
+
+        self.check_short_circuit(&result)?;
+        self.apply_implications(&mut result)?;
+        if self.config.enable_value_templating() {
+            self.expand_templates(&mut result)?;
+        }
+
+        if self.config.required_implies_dependency_check() {
+            self.validate_required_dependencies(&result)?;
+        }
+        self.validate_required(&result)?;
+        self.validate_dependencies(&result)?;
+        self.validate_exclusions(&result)?;
+        self.convert_typed_values(&mut result)?;
+        self.apply_bindings(&result);
+
+        debug!(
+            "Parsed {} flag(s), {} key(s) and {} non-argument text item(s)",
+            result.flags.len(),
+            result.store.len(),
+            result.non_arg_text.len()
+        );
+        Ok(result)
+    }
+
+    /// Parses the arguments and builds a value from the result
+    ///
+    /// This is the common "parse, then fill in my configuration" pattern in a
+    /// single call.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to parse
+    /// * `builder` - Function building the value from the parse result
+    ///
+    /// # Returns
+    ///
+    /// The value returned by `builder`, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `parse`, and otherwise any error returned
+    /// by `builder`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error, Flag, KeyValue};
+    ///
+    /// struct Config {
+    ///     debug: bool,
+    ///     width: u32,
+    /// }
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("WIDTH", "Output width"));
+    ///
+    /// let config = parser.parse_into(vec!["DEBUG".to_string(), "WIDTH=80".to_string()], |result| {
+    ///     Ok(Config {
+    ///         debug: result.is_present("DEBUG"),
+    ///         width: result.value_of("WIDTH").ok_or(Error::RequiredArgMissing("WIDTH".to_string()))?,
+    ///     })
+    /// }).unwrap();
+    ///
+    /// assert!(config.debug);
+    /// assert_eq!(config.width, 80);
+    /// ```
+    pub fn parse_into<T, F>(&self, args: Vec<String>, builder: F) -> Result<T>
+    where
+        F: FnOnce(&ParseResult) -> Result<T>,
+    {
+        let result = self.parse(args)?;
+        builder(&result)
+    }
+
+    /// Parses a raw argument line
+    ///
+    /// The line is split into arguments on whitespace outside quotes and
+    /// brackets, as it would be in a PAM configuration file, and then parsed
+    /// like `parse`. When `ParserConfig::capture_module_name` is set, the
+    /// leading token is taken as the module name instead of an argument.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The raw argument line
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnclosedDelimiter` if a quote or bracket is not closed,
+    /// and otherwise the same errors as `parse`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("MESSAGE", "Message to display"));
+    ///
+    /// let result = parser.parse_line("DEBUG MESSAGE='hello world'").unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// assert_eq!(result.value_of::<String>("MESSAGE"), Some("hello world".to_string()));
+    /// ```
+    pub fn parse_line(&self, line: &str) -> Result<ParseResult> {
+        trace!("Parsing line: '{}'", self.redacted(line));
+        let text_config = TextProcessingConfig::from(&self.config);
+        let mut args = strings::split_whitespace(line, &text_config)?;
+
+        let module_name = if self.config.capture_module_name() && !args.is_empty() {
+            Some(args.remove(0))
+        } else {
+            None
+        };
+
+        let mut result = self.parse(args)?;
+        result.module_name = module_name;
+        Ok(result)
+    }
+
+    /// Parses arguments given as OS strings, such as raw PAM `argv` entries
+    ///
+    /// Each argument must be valid UTF-8. The first one that is not is
+    /// reported with its position and the byte offset of the first invalid
+    /// sequence, which helps locating encoding problems in PAM
+    /// configuration files.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to parse
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidInput` for an argument that is not valid UTF-8,
+    /// and otherwise the same errors as `parse`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ffi::OsString;
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// let result = parser.parse_os(&[OsString::from("DEBUG")]).unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// ```
+    pub fn parse_os<S: AsRef<OsStr>>(&self, args: &[S]) -> Result<ParseResult> {
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                std::str::from_utf8(arg.as_ref().as_encoded_bytes())
+                    .map(str::to_string)
+                    .map_err(|e| {
+                        debug!("Argument {} is not valid UTF-8", index + 1);
+                        Error::InvalidInput(format!(
+                            "argument {} is not valid UTF-8 at byte {}",
+                            index + 1,
+                            e.valid_up_to()
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<String>>>()?;
+        self.parse(args)
+    }
+
+    /// Parses the whole option set given as one joined string
+    ///
+    /// PAM sometimes passes all options in a single string such as
+    /// `[DEBUG,USER=admin] EXTRA`. The string is split on whitespace outside
+    /// quotes and brackets and then parsed; this is `parse_line` under the
+    /// name callers look for when handling raw PAM input.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The joined argument string
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `parse_line`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder()
+    ///     .collect_non_argument_text(true)
+    ///     .build();
+    /// let parser = ArgumentParser::with_config(config)
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("USER", "Username"));
+    ///
+    /// let result = parser.parse_str("[DEBUG,USER=admin] EXTRA").unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+    /// assert_eq!(result.non_argument_text(), vec!["EXTRA"]);
+    /// ```
+    pub fn parse_str(&self, s: &str) -> Result<ParseResult> {
+        self.parse_line(s)
+    }
+
+    /// Parses the first non-comment line of a reader
+    ///
+    /// Blank lines and lines starting with `ParserConfig::comment_prefix`,
+    /// `#` by default, are skipped. If the reader has no other lines, an
+    /// empty argument list is parsed. When `ParserConfig::retain_comments` is
+    /// set, the skipped comment lines are kept verbatim and available from
+    /// `ParseResult::comments`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to read the argument line from
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnexpectedError` if the reader fails, and otherwise the
+    /// same errors as `parse_line`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    /// use std::io::Cursor;
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// let input = Cursor::new("# module arguments\nDEBUG\n");
+    /// let result = parser.parse_reader(input).unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// ```
+    pub fn parse_reader<R: BufRead>(&self, reader: R) -> Result<ParseResult> {
+        let mut comments = Vec::new();
+        let mut result = None;
+        for line in reader.lines() {
+            let line = line
+                .map_err(|e| Error::UnexpectedError(format!("Failed to read input: {}", e)))?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with(self.config.comment_prefix()) {
+                trace!("Skipping line: '{}'", self.redacted(&line));
+                if !trimmed.is_empty() && self.config.retain_comments() {
+                    comments.push(line);
+                }
+                continue;
+            }
+            result = Some(self.parse_line(&line)?);
+            break;
+        }
+
+        let mut result = match result {
+            Some(result) => result,
+            None => {
+                debug!("No argument line found in input");
+                self.parse(Vec::new())?
+            }
+        };
+        result.comments = comments;
+        Ok(result)
+    }
+
+    /// Parses a single line read from standard input
+    ///
+    /// Only the first line is read, and it is parsed with `parse_line` even
+    /// if it is blank or a comment: piped input is taken as given. Empty
+    /// input, or no input at all, is parsed as an empty argument list. Use
+    /// `parse_reader` with `io::stdin().lock()` to skip leading comment lines
+    /// instead.
+    ///
+    /// # Returns
+    ///
+    /// The parse result, or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnexpectedError` if standard input cannot be read, and
+    /// otherwise the same errors as `parse_line`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// // echo "DEBUG" | my-module
+    /// let result = parser.parse_stdin().unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// ```
+    pub fn parse_stdin(&self) -> Result<ParseResult> {
+        self.parse_first_line(io::stdin().lock())
+    }
+
+    /// Reads the first line of a reader and parses it
+    fn parse_first_line<R: BufRead>(&self, mut reader: R) -> Result<ParseResult> {
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| Error::UnexpectedError(format!("Failed to read input: {}", e)))?;
+        self.parse_line(line.trim_end_matches(['\r', '\n']))
+    }
+
+    /// Checks that no two registered arguments share a name
+    ///
+    /// Names are compared after normalizing them with the key normalizer or
+    /// the configured case sensitivity, and flags and key-value pairs share one namespace.
+    /// Names that differ only by case are reported with a warning: in
+    /// case-sensitive mode they stay distinct but are easily confused, and in
+    /// case-insensitive mode the warning names both spellings before the
+    /// `DuplicateArgName` error.
+    fn validate_definitions(&self) -> Result<()> {
+        let flag_names = self.flags.iter().flat_map(|flag| names_of(flag.name(), flag.get_aliases()));
+        let key_value_names = self.key_values.iter().flat_map(|kv| names_of(kv.name(), kv.get_aliases()));
+        let names = flag_names
+            .chain(key_value_names)
+            .chain(self.catch_all.as_deref())
+            .chain(self.positional.as_ref().map(|(name, _)| name.as_str()));
+
+        let mut seen = HashSet::new();
+        let mut folded: HashMap<String, &str> = HashMap::new();
+        for name in names {
+            let first = *folded.entry(case::normalize(name, false)).or_insert(name);
+            if first != name {
+                crate::log_warn!(
+                    LogComponent::Parser,
+                    LogOperation::Validate,
+                    &format!("Argument names '{}' and '{}' differ only by case", first, name)
+                );
+            }
+
+            if !seen.insert(self.config.normalize_key(name)) {
+                debug!("Argument name '{}' is registered more than once", name);
+                return Err(Error::DuplicateArgName(name.to_string()));
+            }
+        }
+
+        for key_value in &self.key_values {
+            let takes_value = key_value
+                .get_allowed_formats()
+                .iter()
+                .any(|format| format.is_compatible_with(AllowedKeyValueFormats::KeyValue));
+            if key_value.get_allowed_values().is_some() && !takes_value {
+                debug!("Argument '{}' has allowed values but cannot take a value", key_value.name());
+                return Err(Error::InvalidInput(format!(
+                    "Argument '{}' has allowed values, but none of its formats accepts KEY=VALUE",
+                    key_value.name()
+                )));
+            }
+
+            if let Some(default) = key_value.get_default_value() {
+                self.validate_default_value(key_value, default)?;
+                if key_value.is_required() {
+                    let message = format!(
+                        "Required argument '{}' has a default value, so it can never be missing",
+                        key_value.name()
+                    );
+                    if self.config.strict_definitions() {
+                        return Err(Error::InvalidInput(message));
+                    }
+                    crate::log_warn!(LogComponent::Parser, LogOperation::Validate, &message);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a default value could have been given as an argument
+    ///
+    /// The default must fit the allowed formats as `KEY=default` and be one
+    /// of the allowed values.
+    fn validate_default_value(&self, key_value: &KeyValue, default: &str) -> Result<()> {
+        let detected = format::detect(&format!("{}={}", key_value.name(), default));
+        if format::validate(&detected, key_value.get_allowed_formats()).is_err() {
+            debug!(
+                "Default value '{}' of '{}' does not fit its allowed formats",
+                redact_value(key_value.name(), default), key_value.name()
+            );
+            return Err(Error::InvalidValue(key_value.name().to_string(), default.to_string()));
+        }
+        self.validate_allowed_value(key_value, default)
+    }
+
+    /// Classifies a single token and records it in the parse result
+    ///
+    /// Explicit flags and key-value pairs are matched first, then multi
+    /// key-value pairs, then non-argument text.
+    fn classify_token(
+        &self,
+        token: &str,
+        bracketed: bool,
+        text_config: &TextProcessingConfig,
+        result: &mut ParseResult,
+    ) -> Result<()> {
+        if let Some(flag) = self.find_flag(token.trim()) {
+            trace!("Token '{}' is flag '{}'", token, flag.name());
+            result.add_flag(flag.name());
+            result.record_spelling(flag.name(), token.trim());
+            return Ok(());
+        }
+
+        let (detected, quoted) = match self.detect_quoted_key(token) {
+            Some(detected) => (detected, true),
+            None => (format::detect(token), false),
+        };
+        let key = if quoted { detected.key.as_str() } else { detected.key.trim() };
+
+        if let (Some(flag), Some(value)) = (self.find_flag(key), &detected.value) {
+            trace!("Token '{}' is flag '{}' with a value", token, flag.name());
+            let value = self.process_value(value, text_config);
+            let enabled = converter::convert_for_key::<bool>(&value, flag.name(), Some(self.config.conversion()))?;
+            result.set_flag_value(flag.name(), enabled);
+            result.record_spelling(flag.name(), key);
+            return Ok(());
+        }
+
+        if let Some(key_value) = self.find_key_value(key) {
+            trace!("Token '{}' is key-value '{}'", self.redacted(token), key_value.name());
+            format::validate(&detected, key_value.get_allowed_formats())?;
+            let value = detected.value.as_deref().map(|v| self.process_value(v, text_config));
+            if let Some(value) = value.as_deref().filter(|value| !self.is_template(value)) {
+                self.validate_allowed_value(key_value, value)?;
+            }
+            result.add_key_value(key_value.name(), value.as_deref());
+            result.record_spelling(key_value.name(), key);
+            return Ok(());
+        }
+
+        if let Some(value) = &detected.value {
+            if self.config.validate_key_names() && !quoted && !strings::is_valid_key_name(key) {
+                debug!("Rejecting token '{}' with an invalid key name", self.redacted(token));
+                return Err(Error::InvalidKeyValue(token.to_string()));
+            }
+            if key.is_empty() {
+                trace!("Token '{}' has an empty key", self.redacted(token));
+                let value = self.process_value(value, text_config);
+                result.add_key_value("", Some(&value));
+                return Ok(());
+            }
+        }
+
+        if let (Some(catch_all), Some(value)) = (&self.catch_all, &detected.value) {
+            trace!("Token '{}' is collected under '{}'", self.redacted(token), catch_all);
+            let value = self.process_value(value, text_config);
+            result.add_entry(catch_all, &format!("{}={}", key, value));
+            result.unrecognized.push(token.to_string());
+            return Ok(());
+        }
+
+        if let Some((name, _)) = &self.positional {
+            if detected.value.is_none() && !bracketed && result.positional(name).is_none() {
+                trace!("Token '{}' is positional '{}'", self.redacted(token), name);
+                result.set_positional(name, token);
+                return Ok(());
+            }
+        }
+
+        if self.is_multi_key_value(&detected.format, key, bracketed, quoted) {
+            if !bracketed && self.config.require_brackets_for_multi() {
+                if let Some(value) = &detected.value {
+                    self.reject_unbracketed_group(token, value, text_config)?;
+                }
+            }
+            trace!("Token '{}' is multi key-value '{}'", self.redacted(token), key);
+            let value = detected.value.as_deref().map(|v| self.process_value(v, text_config));
+            result.add_key_value(key, value.as_deref());
+            return Ok(());
+        }
+
+        if self.config.collect_non_argument_text() {
+            trace!("Token '{}' is non-argument text", self.redacted(token));
+            result.add_non_arg_text(token);
+            result.unrecognized.push(token.to_string());
+            return Ok(());
+        }
+
+        Err(Error::UnrecognizedArg(token.to_string()))
+    }
+
+    /// Checks a value against the allowed values of its key-value definition
+    ///
+    /// The comparison honors the configured value case sensitivity. An empty
+    /// value is only accepted if the empty string is one of the allowed values.
+    fn validate_allowed_value(&self, key_value: &KeyValue, value: &str) -> Result<()> {
+        let Some(allowed) = key_value.get_allowed_values() else {
+            return Ok(());
+        };
+
+        let case_sensitive = self.config.is_case_sensitive_values();
+        if allowed.iter().any(|a| case::compare(a, value, case_sensitive)) {
+            Ok(())
+        } else {
+            debug!("Value '{}' is not allowed for '{}'", redact_value(key_value.name(), value), key_value.name());
+            Err(Error::InvalidValue(key_value.name().to_string(), value.to_string()))
+        }
+    }
+
+    /// Runs the type converters registered on key-value pairs
+    ///
+    /// Keys given without a value have nothing to convert and are skipped.
+    /// Failures end the parse unless `collect_conversion_errors` is set, in
+    /// which case they are recorded in the result.
+    fn convert_typed_values(&self, result: &mut ParseResult) -> Result<()> {
+        for key_value in &self.key_values {
+            let key = result.normalize_key(key_value.name());
+            let Some(value) = result.store.get(&key) else {
+                continue;
+            };
+            let converted = key_value.convert_typed(value).or_else(|| {
+                let fallback = self.default_converter.as_ref()?;
+                Some((fallback.0)(key_value.name(), value, self.config.conversion()))
+            });
+            if let Some(converted) = converted {
+                match converted {
+                    Ok(converted) => {
+                        trace!("Converted '{}' with its type converter", key_value.name());
+                        result.typed_values.insert(key, converted);
+                    }
+                    Err(error) if self.config.collect_conversion_errors() => {
+                        debug!("Collecting conversion error for '{}': {}", key_value.name(), error);
+                        result.conversion_errors.push((key_value.name().to_string(), error));
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports the presence of each bound flag to its binding
+    fn apply_bindings(&self, result: &ParseResult) {
+        for flag in self.flags.iter().filter(|flag| flag.has_binding()) {
+            let present = result.is_present(flag.name());
+            crate::log_debug!(
+                LogComponent::FieldBinding,
+                LogOperation::Operation,
+                &format!("Binding flag '{}' to {}", flag.name(), present)
+            );
+            flag.apply_binding(present);
+        }
+    }
+
+    /// Stops parsing when the help or version flag was given
+    fn check_short_circuit(&self, result: &ParseResult) -> Result<()> {
+        if let Some(name) = &self.help_flag {
+            if result.is_present(name) {
+                debug!("Help flag '{}' given", name);
+                return Err(Error::HelpRequested(self.help()));
+            }
+        }
+        if let Some((name, version)) = &self.version_flag {
+            if result.is_present(name) {
+                debug!("Version flag '{}' given", name);
+                return Err(Error::VersionRequested(version.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the key-value pairs implied by the flags that were present
+    ///
+    /// A key the user gave explicitly keeps its own value, even when it was
+    /// given without one, and when several flags imply the same key the first
+    /// registered flag wins. Implied values are checked against the allowed
+    /// values of their key, or after expansion when they contain `${KEY}`
+    /// references, and are converted like explicit ones.
+    fn apply_implications(&self, result: &mut ParseResult) -> Result<()> {
+        let present: Vec<&Flag> = self.flags.iter().filter(|flag| result.is_present(flag.name())).collect();
+        for flag in present {
+            for (key, value) in flag.implications() {
+                let key_value = self.find_key_value(key);
+                let name = key_value.map_or(key.as_str(), |kv| kv.name());
+                if result.has_key(name) {
+                    trace!("Keeping the given value of '{}' implied by flag '{}'", name, flag.name());
+                    continue;
+                }
+                if let Some(key_value) = key_value.filter(|_| !self.is_template(value)) {
+                    self.validate_allowed_value(key_value, value)?;
+                }
+                trace!("Flag '{}' implies '{}={}'", flag.name(), name, redact_value(name, value));
+                result.add_implied_key_value(name, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Expands `${KEY}` references in the parsed values
+    ///
+    /// References are resolved against the values as given, so the order of
+    /// the arguments does not matter. Expanded values are checked against the
+    /// formats and allowed values of their key, which are skipped for
+    /// templated values while the tokens are classified. Referenced values are expanded in turn,
+    /// up to `MAX_TEMPLATE_DEPTH` levels, which also stops reference cycles.
+    /// Expanding a value may substitute at most `MAX_TEMPLATE_SUBSTITUTIONS`
+    /// references and produce at most `MAX_EXPANDED_LENGTH` characters, so
+    /// values that reference each other several times cannot fan out
+    /// exponentially.
+    fn expand_templates(&self, result: &mut ParseResult) -> Result<()> {
+        let values: HashMap<String, String> = result
+            .store
+            .inner()
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.clone()?)))
+            .collect();
+
+        for (key, value) in values.iter().filter(|(_, value)| value.contains("${")) {
+            let name = self.find_key_value(key).map_or(key.as_str(), |kv| kv.name());
+            let mut substitutions = 0;
+            let expanded = Self::expand_value(value, &values, result, 0, &mut substitutions).map_err(|reason| {
+                debug!("Cannot expand value of '{}': {}", name, reason);
+                Error::InvalidValue(name.to_string(), format!("{} ({})", redact_value(name, value), reason))
+            })?;
+            trace!("Expanded value of '{}' to '{}'", name, redact_value(name, &expanded));
+            result.store.add(key, Some(&expanded));
+            if let Some(key_value) = self.find_key_value(key) {
+                let format = if expanded.is_empty() {
+                    AllowedKeyValueFormats::KeyEquals
+                } else {
+                    AllowedKeyValueFormats::KeyValue
+                };
+                let detected = FormatDetectionResult { format, key: name.to_string(), value: Some(expanded.clone()) };
+                format::validate(&detected, key_value.get_allowed_formats())?;
+                self.validate_allowed_value(key_value, &expanded)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a value is checked only once its `${KEY}` references are expanded
+    fn is_template(&self, value: &str) -> bool {
+        self.config.enable_value_templating() && value.contains("${")
+    }
+
+    /// Expands the references in one value, returning the reason on failure
+    fn expand_value(
+        value: &str,
+        values: &HashMap<String, String>,
+        result: &ParseResult,
+        depth: usize,
+        substitutions: &mut usize,
+    ) -> std::result::Result<String, String> {
+        if depth > MAX_TEMPLATE_DEPTH {
+            return Err(format!(
+                "references nested deeper than {} levels, possibly a cycle",
+                MAX_TEMPLATE_DEPTH
+            ));
+        }
+
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let Some(length) = rest[start + 2..].find('}') else {
+                break;
+            };
+            *substitutions += 1;
+            if *substitutions > MAX_TEMPLATE_SUBSTITUTIONS {
+                return Err(format!("more than {} references to expand", MAX_TEMPLATE_SUBSTITUTIONS));
+            }
+            let name = &rest[start + 2..start + 2 + length];
+            let referenced = values
+                .get(&result.normalize_key(name))
+                .ok_or_else(|| format!("unresolved reference ${{{}}}", name))?;
+
+            expanded.push_str(&rest[..start]);
+            expanded.push_str(&Self::expand_value(referenced, values, result, depth + 1, substitutions)?);
+            rest = &rest[start + 3 + length..];
+            if expanded.len() > MAX_EXPANDED_LENGTH {
+                return Err(format!("expands to more than {} characters", MAX_EXPANDED_LENGTH));
+            }
+        }
+        expanded.push_str(rest);
+        if expanded.len() > MAX_EXPANDED_LENGTH {
+            return Err(format!("expands to more than {} characters", MAX_EXPANDED_LENGTH));
+        }
+        Ok(expanded)
+    }
+
+    /// Checks that every required key-value pair was given
+    ///
+    /// A key given as `KEY=` counts as present, and so does a key with a
+    /// default value; only keys that were omitted entirely are reported.
+    fn validate_required(&self, result: &ParseResult) -> Result<()> {
+        if self.require_any && !result.has_arguments() {
+            debug!("No arguments were provided");
+            return Err(Error::InvalidInput("no arguments provided".to_string()));
+        }
+        if let Some((name, true)) = &self.positional {
+            if result.positional(name).is_none() {
+                debug!("Required positional '{}' is missing", name);
+                return Err(Error::RequiredArgMissing(name.clone()));
+            }
+        }
+        for key_value in self.key_values.iter().filter(|kv| kv.is_required()) {
+            if !result.has_key(key_value.name()) && key_value.get_default_value().is_none() {
+                debug!("Required argument '{}' is missing", key_value.name());
+                return Err(Error::RequiredArgMissing(key_value.name().to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the dependencies of required key-value pairs, present or not
+    ///
+    /// Used when dependency errors take precedence over missing required
+    /// arguments, so that a required argument whose dependency is also
+    /// missing is reported as `DependencyNotMet`.
+    fn validate_required_dependencies(&self, result: &ParseResult) -> Result<()> {
+        for key_value in self.key_values.iter().filter(|kv| kv.is_required()) {
+            let dependencies = key_value.dependencies();
+            if let Some(missing) = dependencies.iter().find(|dependency| !result.is_present(dependency)) {
+                debug!("Required argument '{}' requires '{}', which is missing", key_value.name(), missing);
+                return Err(Error::DependencyNotMet(key_value.name().to_string(), missing.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every present argument has its dependencies present
+    ///
+    /// Arguments that were not given are skipped, so an absent optional
+    /// argument never fails because of its dependencies.
+    fn validate_dependencies(&self, result: &ParseResult) -> Result<()> {
+        let flags = self.flags.iter().map(|flag| (flag.name(), flag.dependencies()));
+        let key_values = self.key_values.iter().map(|kv| (kv.name(), kv.dependencies()));
+
+        for (name, dependencies) in flags.chain(key_values) {
+            if !result.is_present(name) {
+                continue;
+            }
+            if let Some(missing) = dependencies.iter().find(|dependency| !result.is_present(dependency)) {
+                debug!("Argument '{}' requires '{}', which is missing", name, missing);
+                return Err(Error::DependencyNotMet(name.to_string(), missing.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no present argument is given together with one it excludes
+    ///
+    /// Only presence is considered, so an exclusion declared on one side of a
+    /// pair applies regardless of which argument came first.
+    fn validate_exclusions(&self, result: &ParseResult) -> Result<()> {
+        let flags = self.flags.iter().map(|flag| (flag.name(), flag.exclusions()));
+        let key_values = self.key_values.iter().map(|kv| (kv.name(), kv.exclusions()));
+
+        for (name, exclusions) in flags.chain(key_values) {
+            if !result.is_present(name) {
+                continue;
+            }
+            if let Some(excluded) = exclusions.iter().find(|excluded| result.is_present(excluded)) {
+                debug!("Arguments '{}' and '{}' are mutually exclusive", name, excluded);
+                return Err(Error::MutuallyExclusiveArgs(name.to_string(), excluded.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the registered flag matching a token by name or alias
+    fn find_flag(&self, token: &str) -> Option<&Flag> {
+        self.flags.iter().find(|flag| {
+            names_of(flag.name(), flag.get_aliases()).any(|name| self.config.keys_match(name, token))
+        })
+    }
+
+    /// Finds the registered key-value pair matching a key by name or alias
+    fn find_key_value(&self, key: &str) -> Option<&KeyValue> {
+        self.key_values.iter().find(|kv| {
+            names_of(kv.name(), kv.get_aliases()).any(|name| self.config.keys_match(name, key))
+        })
+    }
+
+    /// Checks whether an unmatched token should be stored as a multi key-value pair
+    ///
+    /// When non-argument text is also collected, bare keys are only treated as
+    /// multi key-value pairs inside brackets, since they cannot be told apart
+    /// from ordinary words otherwise.
+    fn is_multi_key_value(&self, detected: &AllowedKeyValueFormats, key: &str, bracketed: bool, quoted: bool) -> bool {
+        if !self.config.enable_multi_key_value() || !(quoted || strings::is_valid_key_name(key)) {
+            return false;
+        }
+
+        if *detected == AllowedKeyValueFormats::KeyOnly
+            && self.config.collect_non_argument_text()
+            && !bracketed
+        {
+            return false;
+        }
+
+        detected.is_compatible_with_any(self.config.multi_key_value_formats())
+    }
+
+    /// Applies the bracket duplicate policy to an element of a bracketed group
+    ///
+    /// `seen` holds the normalized keys given earlier in the same group.
+    /// Returns whether the element should be parsed; flags are always parsed.
+    fn check_group_duplicate(&self, token: &str, seen: &mut HashSet<String>) -> Result<bool> {
+        let policy = self.config.bracket_duplicate_policy();
+        if policy == BracketDuplicatePolicy::Overwrite || self.find_flag(token.trim()).is_some() {
+            return Ok(true);
+        }
+
+        let detected = self.detect_quoted_key(token).unwrap_or_else(|| format::detect(token));
+        let key = detected.key.trim();
+        let name = self.find_key_value(key).map_or(key, |kv| kv.name());
+        if seen.insert(self.config.normalize_key(name)) {
+            return Ok(true);
+        }
+
+        match policy {
+            BracketDuplicatePolicy::Error => {
+                debug!("Key '{}' is repeated within a bracketed group", name);
+                Err(Error::DuplicateArgName(name.to_string()))
+            }
+            BracketDuplicatePolicy::KeepFirst => {
+                trace!("Ignoring repeated key '{}' in a bracketed group", name);
+                Ok(false)
+            }
+            BracketDuplicatePolicy::Overwrite => Ok(true),
+        }
+    }
+
+    /// Splits a token with a quoted key, when quoted keys are allowed
+    ///
+    /// Returns None unless the token starts with a quote character whose
+    /// closing quote ends the token or is followed by `=`.
+    fn detect_quoted_key(&self, token: &str) -> Option<FormatDetectionResult> {
+        if !self.config.allow_quoted_keys() {
+            return None;
+        }
+        let quote = token.chars().next()?;
+        if quote != self.config.single_quote() && quote != self.config.double_quote() {
+            return None;
+        }
+        let inner = &token[quote.len_utf8()..];
+        let close = inner.find(quote)?;
+        let key = inner[..close].to_string();
+        let (format, value) = match &inner[close + quote.len_utf8()..] {
+            "" => (AllowedKeyValueFormats::KeyOnly, None),
+            "=" => (AllowedKeyValueFormats::KeyEquals, Some(String::new())),
+            rest => (AllowedKeyValueFormats::KeyValue, Some(rest.strip_prefix('=')?.to_string())),
+        };
+        Some(FormatDetectionResult { format, key, value })
+    }
+
+    /// Rejects a bare token that holds several multi key-value pairs
+    ///
+    /// The value holds another pair when one of its delimiter-separated
+    /// elements, outside quotes, contains `=`.
+    fn reject_unbracketed_group(&self, token: &str, value: &str, text_config: &TextProcessingConfig) -> Result<()> {
+        let elements = strings::smart_split(value, self.config.delimiter(), text_config)?;
+        if elements.iter().skip(1).any(|element| format::detect(element).value.is_some()) {
+            debug!("Rejecting unbracketed group '{}'", self.redacted(token));
+            return Err(Error::InvalidKeyValue(token.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Masks sensitive values in an argument or line before it is logged
+    fn redacted(&self, text: &str) -> String {
+        redact_with(text, &[self.config.open_bracket(), self.config.close_bracket(), self.config.delimiter()])
+    }
+
+    /// Trims a value if configured and strips enclosing quotes
+    ///
+    /// Escaped quotes inside a quoted value become literal quote characters.
+    fn process_value(&self, value: &str, text_config: &TextProcessingConfig) -> String {
+        let value = if self.config.trim_values() {
+            strings::smart_trim(value, text_config)
+        } else {
+            value.to_string()
+        };
+
+        let mut chars = value.chars();
+        match (chars.next(), chars.next_back()) {
+            (Some(first), Some(last))
+                if first == last
+                    && (first == text_config.single_quote || first == text_config.double_quote) =>
+            {
+                escaping::unescape_quotes(chars.as_str(), text_config)
+            }
+            _ => value,
+        }
+    }
+}
+
+/// Returns an argument's canonical name followed by its aliases
+fn names_of<'a>(name: &'a str, aliases: &'a [String]) -> impl Iterator<Item = &'a str> {
+    std::iter::once(name).chain(aliases.iter().map(String::as_str))
+}
+
+impl Default for ArgumentParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParseResult {
+    /// Creates an empty result for a parser with the given configuration
+    pub(crate) fn new(config: &ParserConfig) -> Self {
+        let key_normalizer = config.key_normalizer().cloned().map(Normalizer);
+        Self {
+            flags: Vec::new(),
+            // Keys are normalized before they reach a store with a custom normalizer
+            store: DefaultKeyValueStore::new(config.is_case_sensitive() || key_normalizer.is_some()),
+            non_arg_text: NonArgTextStore::new(),
+            conversion: config.conversion().clone(),
+            comments: Vec::new(),
+            module_name: None,
+            typed_values: HashMap::new(),
+            defaults: HashMap::new(),
+            join_non_arg: config.join_adjacent_non_arg(),
+            last_was_non_arg: false,
+            key_normalizer,
+            implied: HashSet::new(),
+            repeated: HashMap::new(),
+            positionals: HashMap::new(),
+            flag_values: HashMap::new(),
+            conversion_errors: Vec::new(),
+            spellings: HashMap::new(),
+            lowercase_values: config.lowercase_values(),
+            unrecognized: Vec::new(),
+        }
+    }
+
+    /// Normalizes a key the way the parser that produced this result does
+    pub(crate) fn normalize_key(&self, key: &str) -> String {
+        match &self.key_normalizer {
+            Some(normalizer) => (normalizer.0)(key),
+            None => self.store.normalize_key(key),
+        }
+    }
+
+    /// Records a flag as present, keeping the first appearance only
+    pub(crate) fn add_flag(&mut self, name: &str) {
+        self.last_was_non_arg = false;
+        self.flag_values.remove(&self.normalize_key(name));
+        if !self.is_present(name) {
+            self.flags.push(name.to_string());
+        }
+    }
+
+    /// Records a flag given with an explicit boolean value
+    ///
+    /// A true value makes the flag present and a false one clears it; the
+    /// last occurrence of the flag wins.
+    pub(crate) fn set_flag_value(&mut self, name: &str, enabled: bool) {
+        let normalized = self.normalize_key(name);
+        if enabled {
+            self.add_flag(name);
+        } else {
+            self.last_was_non_arg = false;
+            let flags = std::mem::take(&mut self.flags);
+            self.flags = flags.into_iter().filter(|flag| self.normalize_key(flag) != normalized).collect();
+        }
+        self.flag_values.insert(normalized, enabled);
+    }
+
+    /// Records a key, with its value if it had one
+    pub(crate) fn add_key_value(&mut self, key: &str, value: Option<&str>) {
+        self.last_was_non_arg = false;
+        let key = self.normalize_key(key);
+        match value {
+            Some(value) if self.lowercase_values => self.store.add(&key, Some(&value.to_lowercase())),
+            _ => self.store.add(&key, value),
+        }
+    }
+
+    /// Adds an entry to a repeatable key
+    pub(crate) fn add_entry(&mut self, key: &str, entry: &str) {
+        self.last_was_non_arg = false;
+        let key = self.normalize_key(key);
+        self.repeated.entry(key).or_default().push(entry.to_string());
+    }
+
+    /// Fills a positional slot
+    pub(crate) fn set_positional(&mut self, name: &str, token: &str) {
+        self.last_was_non_arg = false;
+        let name = self.normalize_key(name);
+        self.positionals.insert(name, token.to_string());
+    }
+
+    /// Checks whether any flag, key, catch-all entry or positional was given
+    pub(crate) fn has_arguments(&self) -> bool {
+        !self.flags.is_empty()
+            || !self.flag_values.is_empty()
+            || self.store.len() > 0
+            || !self.repeated.is_empty()
+            || !self.positionals.is_empty()
+    }
+
+    /// Records how the name of a registered argument was typed
+    pub(crate) fn record_spelling(&mut self, canonical: &str, typed: &str) {
+        let canonical = self.normalize_key(canonical);
+        self.spellings.insert(canonical, typed.to_string());
+    }
+
+    /// Records a key-value pair implied by a flag
+    pub(crate) fn add_implied_key_value(&mut self, key: &str, value: &str) {
+        self.add_key_value(key, Some(value));
+        self.implied.insert(self.normalize_key(key));
+    }
+
+    /// Records text that did not match any argument
+    ///
+    /// Text directly following other non-argument text is joined onto it
+    /// when `join_adjacent_non_arg` is configured.
+    pub(crate) fn add_non_arg_text(&mut self, text: &str) {
+        match self.join_non_arg {
+            Some(separator) if self.last_was_non_arg => self.non_arg_text.append_to_last(separator, text),
+            _ => self.non_arg_text.add(text),
+        }
+        self.last_was_non_arg = true;
+    }
+
+    /// Checks whether a flag or key was present in the parsed arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The flag or key name to check
+    ///
+    /// # Returns
+    ///
+    /// true if the argument was present, false otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .flag(Flag::new("QUIET", "Suppress output"));
+    ///
+    /// let result = parser.parse(vec!["DEBUG".to_string()]).unwrap();
+    /// assert!(result.is_present("DEBUG"));
+    /// assert!(!result.is_present("QUIET"));
+    /// ```
+    pub fn is_present(&self, name: &str) -> bool {
+        self.flags
+            .iter()
+            .any(|flag| self.normalize_key(flag) == self.normalize_key(name))
+            || self.has_key(name)
+    }
+
+    /// Returns the value of a key converted to the requested type
+    ///
+    /// The value is converted with `FromArgValue` using the parser's
+    /// conversion configuration (see `ParserConfigBuilder::conversion`).
+    ///
+    /// When converting to `Option<T>`, an empty value and any of the
+    /// configured `none_values` ("none", "null" by default) become
+    /// `Some(None)`. Earlier versions only treated the empty value this way;
+    /// set `recognize_none_values` to false to keep "none" as a literal.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    ///
+    /// The converted value, or the converted default if the key is absent.
+    /// None if there is no value or default, or the value cannot be converted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("WIDTH", "Output width"));
+    ///
+    /// let result = parser.parse(vec!["WIDTH=80".to_string()]).unwrap();
+    /// assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+    /// ```
+    pub fn value_of<T: FromArgValue>(&self, key: &str) -> Option<T> {
+        converter::convert_for_key(self.raw_value(key)?, key, Some(&self.conversion)).ok()
+    }
+
+    /// Returns the converted values of several keys at once
+    ///
+    /// Unlike `value_of`, a value that cannot be converted is reported as
+    /// an error rather than as None.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to look up
+    ///
+    /// # Returns
+    ///
+    /// One entry per key, in the order given: the key and its converted
+    /// value or default, `Ok(None)` if it has neither, or the conversion error
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("WIDTH", "Output width"))
+    ///     .key_value(KeyValue::new("HEIGHT", "Output height"));
+    ///
+    /// let result = parser.parse(vec!["WIDTH=80".to_string()]).unwrap();
+    /// let values = result.values::<u32>(&["WIDTH", "HEIGHT"]);
+    /// assert_eq!(values[0], ("WIDTH".to_string(), Ok(Some(80))));
+    /// assert_eq!(values[1], ("HEIGHT".to_string(), Ok(None)));
+    /// ```
+    pub fn values<T: FromArgValue>(&self, keys: &[&str]) -> Vec<(String, Result<Option<T>>)> {
+        keys.iter()
+            .map(|key| {
+                let value = self
+                    .raw_value(key)
+                    .map(|value| converter::convert_for_key(value, key, Some(&self.conversion)))
+                    .transpose();
+                (key.to_string(), value)
+            })
+            .collect()
+    }
+
+    /// Returns the stored value of a key, or its default if it was not given
+    fn raw_value(&self, key: &str) -> Option<&str> {
+        let normalized = self.normalize_key(key);
+        if self.store.has_key(&normalized) {
+            self.store.get(&normalized)
+        } else {
+            self.defaults.get(&normalized).map(String::as_str)
+        }
+    }
+
+    /// Returns the value of a key converted to a set of elements
+    ///
+    /// Elements are separated by the conversion delimiter or by whitespace,
+    /// and quoted elements stay whole. This is `value_of::<HashSet<T>>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    ///
+    /// The set of converted elements, or None if there is no value or an
+    /// element cannot be converted
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("CAPS", "Capabilities"));
+    ///
+    /// let result = parser.parse(vec!["CAPS=read,write,read".to_string()]).unwrap();
+    /// let caps = result.set_of::<String>("CAPS").unwrap();
+    /// assert_eq!(caps.len(), 2);
+    /// assert!(caps.contains("write"));
+    /// ```
+    pub fn set_of<T: FromArgValue + Eq + std::hash::Hash>(&self, key: &str) -> Option<HashSet<T>> {
+        self.value_of::<HashSet<T>>(key)
+    }
+
+    /// Gets the value produced by a key's type converter
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key whose converted value to get
+    ///
+    /// # Returns
+    ///
+    /// The converted value, or None if the key has no type converter, was not
+    /// given a value, or was converted to a different type than `T`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{converter, ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("PORT", "Server port")
+    ///         .type_converter(converter::from_str::<u16>()));
+    ///
+    /// let result = parser.parse(vec!["PORT=8080".to_string()]).unwrap();
+    /// assert_eq!(result.typed_value_of::<u16>("PORT"), Some(&8080));
+    /// assert_eq!(result.typed_value_of::<i32>("PORT"), None);
+    /// ```
+    pub fn typed_value_of<T: Any>(&self, key: &str) -> Option<&T> {
+        self.typed_values.get(&self.normalize_key(key))?.downcast_ref::<T>()
+    }
+
+    /// Returns an independent copy of this result
+    ///
+    /// This is `clone` under a name that states the semantics: flags, keys,
+    /// non-argument text and every other recorded detail are copied, so
+    /// changes to either result never show in the other. Values produced by
+    /// type converters are immutable and shared rather than copied, which
+    /// keeps the copy cheap for results holding large converted values.
+    ///
+    /// # Returns
+    ///
+    /// A deep copy of the result
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username"));
+    ///
+    /// let result = parser.parse(vec!["USER=admin".to_string()]).unwrap();
+    /// let base = result.snapshot();
+    /// assert_eq!(base.value_of::<String>("USER"), Some("admin".to_string()));
+    /// ```
+    pub fn snapshot(&self) -> ParseResult {
+        self.clone()
+    }
+
+    /// Counts the parsed arguments by kind
+    ///
+    /// Only keys whose `source_of` is `ValueSource::Argument` are counted, so
+    /// default values and keys set by flag implications are left out, since
+    /// they were not supplied.
+    ///
+    /// # Returns
+    ///
+    /// The number of flags, keys by kind of value, and non-argument text entries
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("USER", "Username"));
+    ///
+    /// let result = parser.parse(vec!["DEBUG".to_string(), "USER=admin".to_string()]).unwrap();
+    /// let counts = result.counts();
+    /// assert_eq!(counts.flags, 1);
+    /// assert_eq!(counts.key_values, 1);
+    /// ```
+    pub fn counts(&self) -> ArgCounts {
+        let mut counts = ArgCounts {
+            flags: self.flags.len(),
+            non_arg_text: self.non_arg_text.len(),
+            ..ArgCounts::default()
+        };
+        let supplied = self.store.inner().iter().filter(|(key, _)| !self.implied.contains(*key));
+        for (_, value) in supplied {
+            match value.as_deref() {
+                None => counts.key_only += 1,
+                Some("") => counts.empty_values += 1,
+                Some(_) => counts.key_values += 1,
+            }
+        }
+        counts
+    }
+
+    /// Checks that every argument was recognized
+    ///
+    /// With `collect_non_argument_text` or a catch-all key, unrecognized
+    /// tokens are kept instead of failing the parse. This fails at a point
+    /// of the caller's choosing instead, as a strict parse would have.
+    ///
+    /// # Returns
+    ///
+    /// Ok if no token was kept as non-argument text or a catch-all entry
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnrecognizedArg` with the first such token
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Error, Flag, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder().collect_non_argument_text(true).build();
+    /// let parser = ArgumentParser::with_config(config)
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"));
+    ///
+    /// let result = parser.parse(vec!["DEBUG".to_string(), "stray".to_string()]).unwrap();
+    /// assert_eq!(result.ensure_all_recognized(), Err(Error::UnrecognizedArg("stray".to_string())));
+    /// ```
+    pub fn ensure_all_recognized(&self) -> Result<()> {
+        match self.unrecognized.first() {
+            Some(token) => Err(Error::UnrecognizedArg(token.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the type converter failures collected during the parse
+    ///
+    /// Only populated when `collect_conversion_errors` is set; otherwise the
+    /// first failure ends the parse.
+    ///
+    /// # Returns
+    ///
+    /// The key names and errors, in the order the keys were registered
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{converter, ArgumentParser, KeyValue, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder().collect_conversion_errors(true).build();
+    /// let parser = ArgumentParser::with_config(config)
+    ///     .key_value(KeyValue::new("PORT", "Server port")
+    ///         .type_converter(converter::from_str::<u16>()));
+    ///
+    /// let result = parser.parse(vec!["PORT=http".to_string()]).unwrap();
+    /// assert_eq!(result.conversion_errors().len(), 1);
+    /// assert_eq!(result.conversion_errors()[0].0, "PORT");
+    /// ```
+    pub fn conversion_errors(&self) -> &[(String, Error)] {
+        &self.conversion_errors
+    }
+
+    /// Checks whether a key was present in the parsed arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to check
+    ///
+    /// # Returns
+    ///
+    /// true if the key was present, with or without a value
+    pub fn has_key(&self, key: &str) -> bool {
+        self.store.has_key(&self.normalize_key(key))
+    }
+
+    /// Checks whether a key was missing from the parsed arguments
+    ///
+    /// This is the inverse of `has_key`. A key given without a value
+    /// (`KEY` or `KEY=`) is present, not absent, even though `value_of`
+    /// may return None for it. A key that only has a default value is
+    /// absent, since it did not appear in the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to check
+    ///
+    /// # Returns
+    ///
+    /// true if the key did not appear in the input at all
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{AllowedKeyValueFormats, ArgumentParser, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username")
+    ///         .allowed_formats(&[AllowedKeyValueFormats::KeyOnly]))
+    ///     .key_value(KeyValue::new("HOST", "Hostname"));
+    ///
+    /// let result = parser.parse(vec!["USER".to_string()]).unwrap();
+    /// assert!(!result.is_absent("USER"));
+    /// assert!(result.is_absent("HOST"));
+    /// ```
+    pub fn is_absent(&self, key: &str) -> bool {
+        !self.has_key(key)
+    }
+
+    /// Returns the state of a flag
+    ///
+    /// Flags may be given bare or with an explicit boolean value, such as
+    /// `DEBUG=false`. A flag set to false is not present for `is_present`,
+    /// but this reports it, so the two forms can be told apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The flag name
+    ///
+    /// # Returns
+    ///
+    /// `Some(true)` for a bare flag, the given value for `FLAG=true` or
+    /// `FLAG=false`, and None if the flag was not given
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .flag(Flag::new("AUDIT", "Enable auditing"))
+    ///     .flag(Flag::new("QUIET", "Suppress output"));
+    ///
+    /// let result = parser.parse(vec!["DEBUG".to_string(), "AUDIT=false".to_string()]).unwrap();
+    /// assert_eq!(result.flag_value("DEBUG"), Some(true));
+    /// assert_eq!(result.flag_value("AUDIT"), Some(false));
+    /// assert_eq!(result.flag_value("QUIET"), None);
+    /// ```
+    pub fn flag_value(&self, name: &str) -> Option<bool> {
+        let normalized = self.normalize_key(name);
+        if let Some(enabled) = self.flag_values.get(&normalized) {
+            return Some(*enabled);
+        }
+        self.flags
+            .iter()
+            .any(|flag| self.normalize_key(flag) == normalized)
+            .then_some(true)
+    }
+
+    /// Returns the name of an argument as the user typed it
+    ///
+    /// Arguments are stored under their canonical name. This reports the
+    /// alias or spelling that was actually used, for echoing back in
+    /// messages. If an argument was given more than once, the last
+    /// spelling is reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `canonical` - The registered name of the flag or key
+    ///
+    /// # Returns
+    ///
+    /// The name as typed, or None if the argument was not given
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode").alias("D"));
+    ///
+    /// let result = parser.parse(vec!["D".to_string()]).unwrap();
+    /// assert_eq!(result.matched_alias("DEBUG"), Some("D"));
+    /// ```
+    pub fn matched_alias(&self, canonical: &str) -> Option<&str> {
+        self.spellings.get(&self.normalize_key(canonical)).map(String::as_str)
+    }
+
+    /// Returns the token captured by a positional slot
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name given to `ArgumentParser::positional`
+    ///
+    /// # Returns
+    ///
+    /// The captured token, or None if no positional token was given
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ArgumentParser;
+    ///
+    /// let parser = ArgumentParser::new().positional("PROFILE", false);
+    ///
+    /// let result = parser.parse(Vec::new()).unwrap();
+    /// assert_eq!(result.positional("PROFILE"), None);
+    /// ```
+    pub fn positional(&self, name: &str) -> Option<&str> {
+        self.positionals.get(&self.normalize_key(name)).map(String::as_str)
+    }
+
+    /// Returns the entries collected under a repeatable key
+    ///
+    /// The catch-all key set with `ArgumentParser::catch_all` collects each
+    /// unrecognized `KEY=VALUE` pair as one entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The repeatable key
+    ///
+    /// # Returns
+    ///
+    /// The entries in input order, empty if there are none
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::ArgumentParser;
+    ///
+    /// let parser = ArgumentParser::new().catch_all("EXTRA");
+    ///
+    /// let result = parser.parse(vec!["a=1".to_string(), "a=2".to_string()]).unwrap();
+    /// assert_eq!(result.entries_of("EXTRA"), vec!["a=1", "a=2"]);
+    /// assert!(result.entries_of("OTHER").is_empty());
+    /// ```
+    pub fn entries_of(&self, key: &str) -> Vec<&str> {
+        self.repeated
+            .get(&self.normalize_key(key))
+            .map(|entries| entries.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Reports where the value of a key came from
+    ///
+    /// A key given in the arguments is reported as `Argument` even when it
+    /// was given without a value. Without an argument, a key implied by a
+    /// present flag is `Implied`, and a key with a default value is `Default`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up
+    ///
+    /// # Returns
+    ///
+    /// The source of the key's value, or None if it has no value from any source
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValue, ValueSource};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .key_value(KeyValue::new("USER", "Username"))
+    ///     .key_value(KeyValue::new("PORT", "Port").default_value("22"))
+    ///     .key_value(KeyValue::new("HOST", "Hostname"));
+    ///
+    /// let result = parser.parse(vec!["USER=admin".to_string()]).unwrap();
+    /// assert_eq!(result.source_of("USER"), Some(ValueSource::Argument));
+    /// assert_eq!(result.source_of("PORT"), Some(ValueSource::Default));
+    /// assert_eq!(result.source_of("HOST"), None);
+    /// ```
+    pub fn source_of(&self, key: &str) -> Option<ValueSource> {
+        let normalized = self.normalize_key(key);
+        if self.implied.contains(&normalized) {
+            Some(ValueSource::Implied)
+        } else if self.store.has_key(&normalized) {
+            Some(ValueSource::Argument)
+        } else if self.defaults.contains_key(&normalized) {
+            Some(ValueSource::Default)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the names of the flags that were present
+    ///
+    /// # Returns
+    ///
+    /// Flag names in order of first appearance
+    pub fn flags(&self) -> Vec<&str> {
+        self.flags.iter().map(String::as_str).collect()
+    }
+
+    /// Returns the keys that were present
+    ///
+    /// # Returns
+    ///
+    /// The keys of explicit and multi key-value pairs, sorted
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys = self.store.keys();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Returns the collected non-argument text
+    ///
+    /// # Returns
+    ///
+    /// The non-argument text items in order of appearance
+    pub fn non_argument_text(&self) -> Vec<&str> {
+        self.non_arg_text.texts().iter().map(String::as_str).collect()
+    }
+
+    /// Returns the module name captured from the start of a parsed line
+    ///
+    /// # Returns
+    ///
+    /// The leading token of the line, or `None` unless
+    /// `ParserConfig::capture_module_name` is set and the line was not empty
+    pub fn module_name(&self) -> Option<&str> {
+        self.module_name.as_deref()
+    }
+
+    /// Returns the comment lines retained while parsing a reader
+    ///
+    /// # Returns
+    ///
+    /// The comment lines exactly as read, in order, or an empty slice unless
+    /// `ParserConfig::retain_comments` is set
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Returns a stable fingerprint of the parsed content
+    ///
+    /// The fingerprint covers the flags, key-value pairs and non-argument
+    /// text, each sorted first, so it does not depend on the order in which
+    /// the arguments were given. It uses FNV-1a rather than the standard
+    /// library hasher, so the value is the same across runs and Rust versions.
+    ///
+    /// # Returns
+    ///
+    /// A 64-bit fingerprint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, Flag, KeyValue};
+    ///
+    /// let parser = ArgumentParser::new()
+    ///     .flag(Flag::new("DEBUG", "Enable debug mode"))
+    ///     .key_value(KeyValue::new("USER", "Username for authentication"));
+    ///
+    /// let a = parser.parse(vec!["DEBUG".to_string(), "USER=admin".to_string()]).unwrap();
+    /// let b = parser.parse(vec!["USER=admin".to_string(), "DEBUG".to_string()]).unwrap();
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+
+        let mut flags: Vec<String> = self
+            .flags
+            .iter()
+            .map(|flag| self.normalize_key(flag))
+            .collect();
+        flags.sort_unstable();
+        hasher.write_section(b'F');
+        for flag in &flags {
+            hasher.write_str(flag);
+        }
+
+        let mut entries: Vec<(&String, &Option<String>)> = self.store.inner().iter().collect();
+        entries.sort_unstable();
+        hasher.write_section(b'K');
+        for (key, value) in entries {
+            hasher.write_str(key);
+            match value {
+                Some(value) => {
+                    hasher.write_section(b'=');
+                    hasher.write_str(value);
+                }
+                None => hasher.write_section(b'-'),
+            }
+        }
+
+        let mut texts = self.non_argument_text();
+        texts.sort_unstable();
+        hasher.write_section(b'T');
+        for text in texts {
+            hasher.write_str(text);
+        }
+
+        hasher.finish()
+    }
+
+    /// Returns the underlying key-value store
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::{ArgumentParser, KeyValueStore, ParserConfig};
+    ///
+    /// let config = ParserConfig::builder().enable_multi_key_value(true).build();
+    /// let result = ArgumentParser::with_config(config)
+    ///     .parse(vec!["PORT=8080".to_string()])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result.key_value_store().get("PORT"), Some("8080"));
+    /// ```
+    pub fn key_value_store(&self) -> &DefaultKeyValueStore {
+        &self.store
+    }
+}
+
+/// 64-bit FNV-1a hasher used for `ParseResult::fingerprint`
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes a string followed by a terminator that cannot occur in UTF-8
+    fn write_str(&mut self, s: &str) {
+        self.write(s.as_bytes());
+        self.write(&[0xff]);
+    }
+
+    /// Writes a section marker so adjacent sections cannot run together
+    fn write_section(&mut self, marker: u8) {
+        self.write(&[0xfe, marker]);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        assert_dependency_not_met, assert_error_variant, assert_invalid_value, assert_mutually_exclusive_args,
+        assert_required_arg_missing, create_pam_arg_string, ErrorSimulator, TestAssertions,
+    };
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn parser() -> ArgumentParser {
+        ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("QUIET", "Suppress output"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("WIDTH", "Output width"))
+    }
+
+    #[test]
+    fn test_flags_and_key_values() {
+        let result = parser().parse(args(&["DEBUG", "USER=admin", "WIDTH=80"])).unwrap();
+
+        assert!(result.is_present("DEBUG"));
+        assert!(!result.is_present("QUIET"));
+        assert_eq!(result.flags(), vec!["DEBUG"]);
+        assert_eq!(result.keys(), vec!["USER", "WIDTH"]);
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+        assert!(result.non_argument_text().is_empty());
+    }
+
+    #[test]
+    fn test_repeated_flag_counted_once() {
+        let result = parser().parse(args(&["DEBUG", "DEBUG"])).unwrap();
+        assert_eq!(result.flags(), vec!["DEBUG"]);
+    }
+
+    #[test]
+    fn test_bracketed_arguments() {
+        let result = parser().parse(args(&["[DEBUG, USER='john doe']"])).unwrap();
+
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("john doe".to_string()));
+    }
+
+    #[test]
+    fn test_bracketed_elements_are_trimmed() {
+        let result = parser().parse(args(&["[ DEBUG , USER=admin ]"])).unwrap();
+
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+    }
+
+    #[test]
+    fn test_bracketed_elements_keep_whitespace_without_trimming() {
+        let input = args(&["[DEBUG, USER=' john ', WIDTH=80 ]"]);
+
+        let result = parser().parse(input.clone()).unwrap();
+        assert_eq!(result.value_of::<String>("USER"), Some("john".to_string()));
+        assert_eq!(result.value_of::<String>("WIDTH"), Some("80".to_string()));
+
+        let config = ParserConfig::builder().trim_values(false).build();
+        let result = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("WIDTH", "Output width"))
+            .parse(input)
+            .unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.key_value_store().get("USER"), Some(" john "));
+        assert_eq!(result.key_value_store().get("WIDTH"), Some("80 "));
+    }
+
+    #[test]
+    fn test_quoted_values_are_unquoted() {
+        let result = parser().parse(args(&["USER=\"admin\"", "WIDTH=' 80 '"])).unwrap();
+
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+    }
+
+    #[test]
+    fn test_parse_error_context() {
+        let input = args(&["DEBUG", "USER=admin", "unknown"]);
+        let result = parser().parse(input.clone());
+        assert!(matches!(result, Err(Error::UnrecognizedArg(_))));
+
+        let config = ParserConfig::builder().error_context(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+        let err = parser.parse(input.clone()).unwrap_err();
+        assert_eq!(err.cause(), &Error::UnrecognizedArg("unknown".to_string()));
+        assert!(matches!(&err, Error::WithContext(_, context) if *context == input));
+        assert!(err.to_string().contains("DEBUG USER=admin unknown"), "{}", err);
+    }
+
+    #[test]
+    fn test_unrecognized_argument() {
+        let result = parser().parse(args(&["DEBUG", "unknown"]));
+        assert!(matches!(result, Err(Error::UnrecognizedArg(ref a)) if a == "unknown"));
+    }
+
+    #[test]
+    fn test_disallowed_format() {
+        let result = parser().parse(args(&["USER"]));
+        assert!(matches!(result, Err(Error::InvalidKeyValue(_))));
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("RESET", "Reset state")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyOnly]),
+        );
+        let result = parser.parse(args(&["RESET"])).unwrap();
+        assert!(result.has_key("RESET"));
+        assert_eq!(result.value_of::<String>("RESET"), None);
+    }
+
+    #[test]
+    fn test_case_insensitive_names() {
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let result = parser.parse(args(&["debug", "user=admin"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert!(result.is_present("Debug"));
+        assert_eq!(result.value_of::<String>("User"), Some("admin".to_string()));
+    }
+
+    #[test]
+    fn test_case_sensitive_names() {
+        let result = parser().parse(args(&["debug"]));
+        assert!(matches!(result, Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_multi_key_value() {
+        let config = ParserConfig::builder().enable_multi_key_value(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let result = parser.parse(args(&["USER=admin", "[HOST=localhost,PORT=22]"])).unwrap();
+        assert_eq!(result.keys(), vec!["HOST", "PORT", "USER"]);
+        assert_eq!(result.key_value_store().get("HOST"), Some("localhost"));
+
+        // KEY= is not allowed by the default multi key-value formats
+        assert!(matches!(parser.parse(args(&["HOST="])), Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_non_argument_text() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"));
+
+        let result = parser.parse(args(&["DEBUG", "hello", "world"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.non_argument_text(), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_key_only_multi_key_value_needs_brackets_with_text() {
+        let config = ParserConfig::builder()
+            .enable_multi_key_value(true)
+            .multi_key_value_formats(&[AllowedKeyValueFormats::KeyAll])
+            .collect_non_argument_text(true)
+            .build();
+        let parser = ArgumentParser::with_config(config);
+
+        let result = parser.parse(args(&["[KEY1=VALUE1,KEY2,KEY3=]", "some", "text", "KEY4=x"])).unwrap();
+        assert_eq!(result.keys(), vec!["KEY1", "KEY2", "KEY3", "KEY4"]);
+        assert_eq!(result.key_value_store().get("KEY3"), Some(""));
+        assert_eq!(result.non_argument_text(), vec!["some", "text"]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(matches!(parser().parse(args(&["[DEBUG"])), Err(Error::UnclosedDelimiter(_))));
+        assert!(matches!(parser().parse(args(&["[DEBUG,[QUIET]]"])), Err(Error::NestedBrackets(_))));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let config = ParserConfig::builder()
+            .enable_multi_key_value(true)
+            .collect_non_argument_text(true)
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("QUIET", "Suppress output"));
+
+        let a = parser.parse(args(&["DEBUG", "QUIET", "HOST=x", "PORT=22", "one", "two"])).unwrap();
+        let b = parser.parse(args(&["two", "[PORT=22,HOST=x]", "QUIET", "one", "DEBUG"])).unwrap();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.fingerprint(), a.clone().fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_differs_for_different_content() {
+        let config = ParserConfig::builder()
+            .enable_multi_key_value(true)
+            .multi_key_value_formats(&[AllowedKeyValueFormats::KeyAll])
+            .collect_non_argument_text(true)
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"));
+
+        let fingerprint = |items: &[&str]| parser.parse(args(items)).unwrap().fingerprint();
+        let base = fingerprint(&["DEBUG", "HOST=x"]);
+
+        assert_ne!(base, fingerprint(&["HOST=x"]));
+        assert_ne!(base, fingerprint(&["DEBUG", "HOST=y"]));
+        assert_ne!(base, fingerprint(&["DEBUG", "HOST=x", "text"]));
+        assert_ne!(fingerprint(&["[KEY=]"]), fingerprint(&["[KEY]"]));
+        assert_ne!(fingerprint(&["[AB=C]"]), fingerprint(&["[A=BC]"]));
+        assert_ne!(fingerprint(&["DEBUG"]), fingerprint(&["text"]));
+    }
+
+    #[test]
+    fn test_required_argument_missing() {
+        let parser = parser().key_value(KeyValue::new("HOST", "Host").required());
+
+        let result = parser.parse(args(&["DEBUG", "USER=admin"]));
+        assert_required_arg_missing(&result, "HOST");
+
+        let result = parser.parse(args(&["HOST=localhost"])).unwrap();
+        assert_eq!(result.value_of::<String>("HOST"), Some("localhost".to_string()));
+    }
+
+    #[test]
+    fn test_required_argument_with_empty_value() {
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("HOST", "Host")
+                .required()
+                .allowed_formats(&[AllowedKeyValueFormats::KeyValue, AllowedKeyValueFormats::KeyEquals]),
+        );
+
+        let result = parser.parse(args(&["HOST="])).unwrap();
+        assert!(result.has_key("HOST"));
+        assert_eq!(result.key_value_store().get("HOST"), Some(""));
+    }
+
+    #[test]
+    fn test_required_argument_case_sensitivity() {
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("HOST", "Host").required());
+        assert!(parser.parse(args(&["host=localhost"])).is_ok());
+
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("HOST", "Host").required())
+            .key_value(KeyValue::new("host", "Lowercase host"));
+        assert_required_arg_missing(&parser.parse(args(&["host=localhost"])), "HOST");
+    }
+
+    #[test]
+    fn test_mutually_exclusive_flags() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode").excludes("QUIET"))
+            .flag(Flag::new("QUIET", "Suppress output"));
+        let simulator = ErrorSimulator::new();
+
+        let result = parser.parse(simulator.mutually_exclusive_args("DEBUG", "QUIET"));
+        assert_mutually_exclusive_args(&result, "DEBUG", "QUIET");
+
+        let result = parser.parse(simulator.mutually_exclusive_args("QUIET", "DEBUG"));
+        assert_mutually_exclusive_args(&result, "DEBUG", "QUIET");
+
+        assert!(parser.parse(args(&["DEBUG"])).is_ok());
+        assert!(parser.parse(args(&["QUIET"])).is_ok());
+    }
+
+    #[test]
+    fn test_mutually_exclusive_key_values() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("ANONYMOUS", "Log in anonymously"))
+            .key_value(KeyValue::new("USER", "Username").conflicts_with_all(&["ANONYMOUS", "TOKEN"]))
+            .key_value(KeyValue::new("TOKEN", "Access token"));
+        let simulator = ErrorSimulator::new();
+
+        let result = parser.parse(simulator.mutually_exclusive_args("ANONYMOUS", "USER=admin"));
+        assert_mutually_exclusive_args(&result, "USER", "ANONYMOUS");
+
+        let result = parser.parse(simulator.mutually_exclusive_args("TOKEN=abc", "USER=admin"));
+        assert_mutually_exclusive_args(&result, "USER", "TOKEN");
+
+        assert!(parser.parse(args(&["ANONYMOUS", "TOKEN=abc"])).is_ok());
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let result = parser().parse_line("DEBUG USER='john doe' [WIDTH=80]").unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("john doe".to_string()));
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+
+        let result = parser().parse_line("USER='unclosed");
+        assert!(matches!(result, Err(Error::UnclosedDelimiter(_))));
+    }
+
+    #[test]
+    fn test_flag_implications() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("PARANOID", "Enable all hardening options").implies("STRICT", "true"))
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("STRICT", "Strict checking"));
+
+        // Implied when the flag is present
+        let result = parser.parse(args(&["PARANOID"])).unwrap();
+        assert_eq!(result.value_of::<bool>("STRICT"), Some(true));
+
+        // Not implied when the flag is absent
+        let result = parser.parse(args(&["DEBUG"])).unwrap();
+        assert!(!result.has_key("STRICT"));
+
+        // An explicit value wins, whatever the order
+        let result = parser.parse(args(&["PARANOID", "STRICT=false"])).unwrap();
+        assert_eq!(result.value_of::<bool>("STRICT"), Some(false));
+        let result = parser.parse(args(&["STRICT=false", "PARANOID"])).unwrap();
+        assert_eq!(result.value_of::<bool>("STRICT"), Some(false));
+    }
+
+    #[test]
+    fn test_implied_value_must_be_allowed() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("FAST", "Favor speed").implies("MODE", "turbo"))
+            .key_value(KeyValue::new("MODE", "Mode").allowed_values(&["fast", "safe"]));
+
+        assert!(matches!(parser.parse(args(&["FAST"])), Err(Error::InvalidValue(key, _)) if key == "MODE"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_os_reports_invalid_utf8_position() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let args = [OsStr::from_bytes(b"DEBUG"), OsStr::from_bytes(b"USER=ad\xFFmin")];
+        assert_eq!(
+            parser.parse_os(&args).unwrap_err(),
+            Error::InvalidInput("argument 2 is not valid UTF-8 at byte 7".to_string())
+        );
+
+        // A truncated multi-byte sequence is reported where it starts
+        let args = [OsStr::from_bytes(b"USER=caf\xC3")];
+        assert_eq!(
+            parser.parse_os(&args).unwrap_err(),
+            Error::InvalidInput("argument 1 is not valid UTF-8 at byte 8".to_string())
+        );
+
+        let args = [OsStr::from_bytes(b"DEBUG"), OsStr::from_bytes("USER=caf\u{e9}".as_bytes())];
+        let result = parser.parse_os(&args).unwrap();
+        assert_eq!(result.value_of::<String>("USER"), Some("caf\u{e9}".to_string()));
+    }
+
+    #[test]
+    fn test_default_converter() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("WIDTH", "Width"))
+            .key_value(KeyValue::new("HEIGHT", "Height"))
+            .key_value(KeyValue::new("SCALE", "Scale").type_converter(converter::from_str::<f64>()))
+            .default_converter::<u32>();
+
+        let result = parser.parse(args(&["WIDTH=80", "HEIGHT=24", "SCALE=1.5"])).unwrap();
+
+        // Applies to keys without their own converter
+        assert_eq!(result.typed_value_of::<u32>("WIDTH"), Some(&80));
+        assert_eq!(result.typed_value_of::<u32>("HEIGHT"), Some(&24));
+
+        // The per-key converter wins
+        assert_eq!(result.typed_value_of::<f64>("SCALE"), Some(&1.5));
+        assert_eq!(result.typed_value_of::<u32>("SCALE"), None);
+
+        // Values the default converter rejects fail the parse
+        assert!(parser.parse(args(&["WIDTH=wide"])).is_err());
+
+        // Without a default converter only per-key converters run
+        let parser = ArgumentParser::new().key_value(KeyValue::new("WIDTH", "Width"));
+        let result = parser.parse(args(&["WIDTH=80"])).unwrap();
+        assert_eq!(result.typed_value_of::<u32>("WIDTH"), None);
+    }
+
+    #[test]
+    fn test_require_any() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("PORT", "Port").default_value("22"))
+            .require_any();
+        let no_arguments = Error::InvalidInput("no arguments provided".to_string());
+
+        // Empty input, including input with only defaults or free text
+        assert_eq!(parser.parse(args(&[])).unwrap_err(), no_arguments);
+        assert_eq!(parser.parse(args(&["just", "text"])).unwrap_err(), no_arguments);
+
+        // Minimal input
+        assert!(parser.parse(args(&["DEBUG"])).is_ok());
+        assert!(parser.parse(args(&["DEBUG=false"])).is_ok());
+        assert!(parser.parse(args(&["PORT=2222"])).is_ok());
+
+        let parser = ArgumentParser::new().positional("PROFILE", false).require_any();
+        assert!(parser.parse(args(&["strict"])).is_ok());
+
+        // Without require_any empty input is fine
+        assert!(ArgumentParser::new().parse(args(&[])).is_ok());
+    }
+
+    #[test]
+    fn test_help_and_version_flags() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username for authentication").required())
+            .help_flag("HELP")
+            .version_flag("VERSION", "pam_example 1.2.0");
+
+        // The help text is returned before the missing USER is reported
+        match parser.parse(args(&["DEBUG", "HELP"])).unwrap_err() {
+            Error::HelpRequested(text) => {
+                assert_eq!(text, parser.help());
+                assert!(text.contains("Username for authentication"));
+                assert!(text.contains("Print this help text"));
+            }
+            other => panic!("expected HelpRequested, got {:?}", other),
+        }
+        assert_eq!(
+            parser.parse(args(&["VERSION"])).unwrap_err(),
+            Error::VersionRequested("pam_example 1.2.0".to_string())
+        );
+        assert!(matches!(
+            parser.parse(args(&["VERSION", "HELP"])).unwrap_err(),
+            Error::HelpRequested(_)
+        ));
+
+        // Without either flag parsing proceeds as usual
+        assert_eq!(
+            parser.parse(args(&["DEBUG"])).unwrap_err(),
+            Error::RequiredArgMissing("USER".to_string())
+        );
+        assert!(parser.parse(args(&["USER=admin"])).is_ok());
+    }
+
+    #[test]
+    fn test_bracket_duplicate_policy() {
+        let parser_with = |policy| {
+            let config = ParserConfig::builder()
+                .enable_multi_key_value(true)
+                .bracket_duplicate_policy(policy)
+                .build();
+            ArgumentParser::with_config(config)
+                .flag(Flag::new("DEBUG", "Enable debug mode"))
+                .key_value(KeyValue::new("USER", "Username").alias("LOGIN"))
+        };
+
+        // Overwrite: the last value in the group wins
+        let parser = parser_with(BracketDuplicatePolicy::Overwrite);
+        let result = parser.parse(args(&["[A=1,A=2]"])).unwrap();
+        assert_eq!(result.value_of::<i32>("A"), Some(2));
+
+        // KeepFirst: the first value in the group wins
+        let parser = parser_with(BracketDuplicatePolicy::KeepFirst);
+        let result = parser.parse(args(&["[A=1,A=2,USER=a,LOGIN=b]"])).unwrap();
+        assert_eq!(result.value_of::<i32>("A"), Some(1));
+        assert_eq!(result.value_of::<String>("USER"), Some("a".to_string()));
+
+        // Error: a repeated key, also by alias, is rejected
+        let parser = parser_with(BracketDuplicatePolicy::Error);
+        assert_eq!(parser.parse(args(&["[A=1,A=2]"])).unwrap_err(), Error::DuplicateArgName("A".to_string()));
+        assert_eq!(
+            parser.parse(args(&["[USER=a,LOGIN=b]"])).unwrap_err(),
+            Error::DuplicateArgName("USER".to_string())
+        );
+
+        // Repeats across groups and arguments, and repeated flags, still overwrite
+        let result = parser.parse(args(&["A=0", "[A=1,B=2,DEBUG]", "[A=3,DEBUG,DEBUG]"])).unwrap();
+        assert_eq!(result.value_of::<i32>("A"), Some(3));
+        assert_eq!(result.value_of::<i32>("B"), Some(2));
+        assert!(result.is_present("DEBUG"));
+    }
+
+    #[test]
+    fn test_ensure_all_recognized() {
+        let config = ParserConfig::builder()
+            .collect_non_argument_text(true)
+            .join_adjacent_non_arg(Some(' '))
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        // Without unknowns
+        let result = parser.parse(args(&["DEBUG", "USER=admin"])).unwrap();
+        assert_eq!(result.ensure_all_recognized(), Ok(()));
+
+        // With unknowns, the first token is reported
+        let result = parser.parse(args(&["DEBUG", "first", "second", "USER=admin", "third"])).unwrap();
+        assert_eq!(result.ensure_all_recognized(), Err(Error::UnrecognizedArg("first".to_string())));
+
+        // Catch-all entries count as unknowns too
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("USER", "Username"))
+            .catch_all("EXTRA");
+        let result = parser.parse(args(&["USER=admin"])).unwrap();
+        assert_eq!(result.ensure_all_recognized(), Ok(()));
+        let result = parser.parse(args(&["USER=admin", "retry=3"])).unwrap();
+        assert_eq!(result.ensure_all_recognized(), Err(Error::UnrecognizedArg("retry=3".to_string())));
+    }
+
+    #[test]
+    fn test_lowercase_values() {
+        let config = ParserConfig::builder()
+            .lowercase_values(true)
+            .enable_multi_key_value(true)
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("Host", "Hostname"))
+            .key_value(KeyValue::new("Mode", "Mode").allowed_values(&["Fast", "Safe"]))
+            .key_value(KeyValue::new("Domain", "Domain").default_value("Example.COM"));
+
+        let result = parser.parse(args(&["Host=Server.Example.COM", "Mode=Fast", "[Zone=EU-West]"])).unwrap();
+        assert_eq!(result.value_of::<String>("Host"), Some("server.example.com".to_string()));
+        assert_eq!(result.value_of::<String>("Mode"), Some("fast".to_string()));
+        assert_eq!(result.value_of::<String>("Zone"), Some("eu-west".to_string()));
+        assert_eq!(result.value_of::<String>("Domain"), Some("example.com".to_string()));
+
+        // Keys keep their case
+        assert_eq!(result.keys(), vec!["Host", "Mode", "Zone"]);
+        assert!(!result.has_key("host"));
+
+        // Allowed values are checked against the value as given
+        assert!(matches!(parser.parse(args(&["Mode=fast"])), Err(Error::InvalidValue(..))));
+
+        // case_sensitive_values never changes the stored value
+        let config = ParserConfig::builder().case_sensitive_values(false).build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("Mode", "Mode").allowed_values(&["fast"]));
+        let result = parser.parse(args(&["Mode=FAST"])).unwrap();
+        assert_eq!(result.value_of::<String>("Mode"), Some("FAST".to_string()));
+    }
+
+    #[test]
+    fn test_extend() {
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let mut parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+        let module = ArgumentParser::new()
+            .flag(Flag::new("AUDIT", "Enable auditing"))
+            .key_value(KeyValue::new("PORT", "Port").default_value("22"));
+
+        parser.extend(&module).unwrap();
+        // The receiver's case-insensitive configuration applies to the added definitions
+        let result = parser.parse(args(&["debug", "audit", "user=admin"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert!(result.is_present("AUDIT"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert_eq!(result.value_of::<u16>("PORT"), Some(22));
+        assert!(module.parse(args(&["DEBUG"])).is_err());
+
+        // A conflicting name is rejected and leaves the parser unchanged
+        let conflicting = ArgumentParser::new()
+            .flag(Flag::new("VERBOSE", "Verbose output"))
+            .key_value(KeyValue::new("user", "Login name"));
+        assert_eq!(parser.extend(&conflicting), Err(Error::DuplicateArgName("user".to_string())));
+        assert!(parser.validate().is_ok());
+        assert!(matches!(parser.parse(args(&["VERBOSE"])), Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_strict_secure_parsing() {
+        let parser = ArgumentParser::with_config(ParserConfig::strict_secure())
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        // Invisible characters are removed before matching
+        let result = parser.parse(args(&["DE\u{200B}BUG", "USER=ad\u{FEFF}min"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+
+        // Overlong arguments, unknown arguments and invalid keys are rejected
+        let long = format!("USER={}", "a".repeat(1024));
+        assert!(matches!(parser.parse(args(&[&long])), Err(Error::InvalidInput(message)) if !message.contains("aaa")));
+        assert!(matches!(parser.parse(args(&["stray"])), Err(Error::UnrecognizedArg(_))));
+        assert!(matches!(parser.parse(args(&["=admin"])), Err(Error::InvalidKeyValue(_))));
+
+        // Without the preset invisible characters are kept
+        let parser = ArgumentParser::new().flag(Flag::new("DEBUG", "Enable debug mode"));
+        assert!(matches!(parser.parse(args(&["DE\u{200B}BUG"])), Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_matched_alias() {
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode").alias("D"))
+            .flag(Flag::new("QUIET", "Suppress output"))
+            .key_value(KeyValue::new("USER", "Username").alias("LOGIN"))
+            .key_value(KeyValue::new("HOST", "Hostname"));
+
+        let result = parser.parse(args(&["D", "login=admin", "Host=example.com"])).unwrap();
+        assert_eq!(result.matched_alias("DEBUG"), Some("D"));
+        assert_eq!(result.matched_alias("USER"), Some("login"));
+        assert_eq!(result.matched_alias("HOST"), Some("Host"));
+        assert_eq!(result.matched_alias("QUIET"), None);
+
+        // Values are still read by the canonical name
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert!(!result.keys().contains(&"login"));
+    }
+
+    #[test]
+    fn test_values() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("WIDTH", "Output width"))
+            .key_value(KeyValue::new("HEIGHT", "Output height"))
+            .key_value(KeyValue::new("DEPTH", "Output depth").default_value("3"))
+            .key_value(KeyValue::new("MARGIN", "Margin"));
+
+        let result = parser.parse(args(&["WIDTH=80", "HEIGHT=tall"])).unwrap();
+        let values = result.values::<u32>(&["MARGIN", "HEIGHT", "WIDTH", "DEPTH"]);
+
+        let keys: Vec<&str> = values.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["MARGIN", "HEIGHT", "WIDTH", "DEPTH"]);
+        assert_eq!(values[0].1, Ok(None));
+        assert!(values[1].1.is_err());
+        assert_eq!(values[2].1, Ok(Some(80)));
+        assert_eq!(values[3].1, Ok(Some(3)));
+
+        // value_of hides the conversion error
+        assert_eq!(result.value_of::<u32>("HEIGHT"), None);
+    }
+
+    #[test]
+    fn test_empty_key() {
+        let tokenized = Tokenizer::new().tokenize_arg("=value").unwrap();
+        assert_eq!(tokenized.tokens, vec!["=value"]);
+        let tokenized = Tokenizer::new().tokenize_arg("==").unwrap();
+        assert_eq!(tokenized.tokens, vec!["=="]);
+
+        // Without validation the value is stored under the empty key
+        let parser = ArgumentParser::new().key_value(KeyValue::new("USER", "Username"));
+        let result = parser.parse(args(&["=value"])).unwrap();
+        assert_eq!(result.value_of::<String>(""), Some("value".to_string()));
+        let result = parser.parse(args(&["=="])).unwrap();
+        assert_eq!(result.value_of::<String>(""), Some("=".to_string()));
+
+        // With validation both are rejected
+        let config = ParserConfig::builder().validate_key_names(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("USER", "Username"))
+            .catch_all("EXTRA");
+        assert!(matches!(parser.parse(args(&["=value"])), Err(Error::InvalidKeyValue(token)) if token == "=value"));
+        assert!(matches!(parser.parse(args(&["=="])), Err(Error::InvalidKeyValue(token)) if token == "=="));
+        assert!(matches!(parser.parse(args(&["bad-key=1"])), Err(Error::InvalidKeyValue(_))));
+
+        // Registered and valid unregistered keys are unaffected
+        let result = parser.parse(args(&["USER=admin", "other=1"])).unwrap();
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert_eq!(result.entries_of("EXTRA"), vec!["other=1"]);
+    }
+
+    #[test]
+    fn test_snapshot_is_independent() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("PORT", "Port").type_converter(converter::from_str::<u16>()));
+
+        let mut result = parser.parse(args(&["USER=admin", "PORT=22", "base"])).unwrap();
+        let base = result.snapshot();
+
+        // Overlay per-request arguments on the original
+        result.add_flag("DEBUG");
+        result.add_key_value("USER", Some("guest"));
+        result.add_key_value("EXTRA", None);
+        result.add_non_arg_text("request");
+
+        assert!(!base.is_present("DEBUG"));
+        assert_eq!(base.value_of::<String>("USER"), Some("admin".to_string()));
+        assert!(!base.has_key("EXTRA"));
+        assert_eq!(base.non_argument_text(), vec!["base"]);
+        assert_eq!(base.typed_value_of::<u16>("PORT"), Some(&22));
+
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("guest".to_string()));
+        assert_eq!(result.non_argument_text(), vec!["base", "request"]);
+        assert_ne!(base.fingerprint(), result.fingerprint());
+    }
+
+    #[test]
+    fn test_counts() {
+        let config = ParserConfig::builder()
+            .collect_non_argument_text(true)
+            .enable_multi_key_value(true)
+            .multi_key_value_formats(&[AllowedKeyValueFormats::KeyAll])
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("QUIET", "Suppress output"))
+            .flag(Flag::new("AUDIT", "Enable auditing"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("PORT", "Port").default_value("22"));
+
+        let result = parser
+            .parse(args(&["DEBUG", "QUIET", "USER=admin", "[MODE=fast, CACHE, EMPTY=]", "some", "text"]))
+            .unwrap();
+        assert_eq!(
+            result.counts(),
+            ArgCounts {
+                flags: 2,
+                key_values: 2,
+                key_only: 1,
+                empty_values: 1,
+                non_arg_text: 2,
+            }
+        );
+
+        assert_eq!(parser.parse(args(&[])).unwrap().counts(), ArgCounts::default());
+
+        // Keys set by flag implications were not supplied
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("SECURE", "Use the secure profile").implies("MODE", "safe"))
+            .key_value(KeyValue::new("MODE", "Operation mode"));
+        let result = parser.parse(args(&["SECURE"])).unwrap();
+        assert_eq!(result.source_of("MODE"), Some(ValueSource::Implied));
+        assert_eq!(result.counts(), ArgCounts { flags: 1, ..ArgCounts::default() });
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        let config = ParserConfig::builder().collect_conversion_errors(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("PORT", "Server port").type_converter(converter::from_str::<u16>()))
+            .key_value(KeyValue::new("RETRIES", "Retry count").type_converter(converter::from_str::<u8>()));
+
+        let result = parser.parse(args(&["PORT=8080", "RETRIES=many"])).unwrap();
+        assert_eq!(result.typed_value_of::<u16>("PORT"), Some(&8080));
+        assert_eq!(result.typed_value_of::<u8>("RETRIES"), None);
+        assert_eq!(result.conversion_errors().len(), 1);
+        assert_eq!(result.conversion_errors()[0].0, "RETRIES");
+
+        let result = parser.parse(args(&["PORT=8080", "RETRIES=3"])).unwrap();
+        assert!(result.conversion_errors().is_empty());
+
+        // Without the option the first failure ends the parse
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("RETRIES", "Retry count").type_converter(converter::from_str::<u8>()));
+        assert!(parser.parse(args(&["RETRIES=many"])).is_err());
+    }
+
+    #[test]
+    fn test_quoted_keys() {
+        let config = ParserConfig::builder()
+            .allow_quoted_keys(true)
+            .enable_multi_key_value(true)
+            .build();
+        let parser = ArgumentParser::with_config(config).key_value(KeyValue::new("my key", "A key with a space"));
+
+        let result = parser
+            .parse(args(&["\"my key\"=value", "'other key'='quoted value'", "\"a=b\"=c", "plain=1"]))
+            .unwrap();
+        assert_eq!(result.value_of::<String>("my key"), Some("value".to_string()));
+        assert_eq!(result.value_of::<String>("other key"), Some("quoted value".to_string()));
+        assert_eq!(result.value_of::<String>("a=b"), Some("c".to_string()));
+        assert_eq!(result.value_of::<i32>("plain"), Some(1));
+        assert!(!result.has_key("\"my key\""));
+
+        // A quoted key must be followed by `=` or end the token
+        assert!(parser.parse(args(&["\"my key\"x=value"])).is_err());
+
+        // Without the option the quotes are part of the key
+        let config = ParserConfig::builder().enable_multi_key_value(true).build();
+        let parser = ArgumentParser::with_config(config).key_value(KeyValue::new("my key", "A key with a space"));
+        assert!(matches!(parser.parse(args(&["\"my key\"=value"])), Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_require_brackets_for_multi() {
+        let config = ParserConfig::builder()
+            .enable_multi_key_value(true)
+            .require_brackets_for_multi(true)
+            .build();
+        let parser = ArgumentParser::with_config(config).key_value(KeyValue::new("LIST", "List"));
+
+        // Bracketed groups are accepted
+        let result = parser.parse(args(&["[A=1,B=2]"])).unwrap();
+        assert_eq!(result.value_of::<i32>("A"), Some(1));
+        assert_eq!(result.value_of::<i32>("B"), Some(2));
+
+        // Bare groups are rejected
+        assert!(matches!(parser.parse(args(&["A=1,B=2"])), Err(Error::InvalidKeyValue(token)) if token == "A=1,B=2"));
+
+        // Single pairs, quoted delimiters and registered keys are not groups
+        let result = parser.parse(args(&["A=1", "B='x,y=z'", "C=x,y", "LIST=a,b=c"])).unwrap();
+        assert_eq!(result.value_of::<i32>("A"), Some(1));
+        assert_eq!(result.value_of::<String>("B"), Some("x,y=z".to_string()));
+        assert_eq!(result.value_of::<String>("C"), Some("x,y".to_string()));
+        assert_eq!(result.value_of::<String>("LIST"), Some("a,b=c".to_string()));
+
+        // Without the option the rest of a bare group is the value
+        let config = ParserConfig::builder().enable_multi_key_value(true).build();
+        let parser = ArgumentParser::with_config(config);
+        let result = parser.parse(args(&["A=1,B=2"])).unwrap();
+        assert_eq!(result.value_of::<String>("A"), Some("1,B=2".to_string()));
+    }
+
+    #[test]
+    fn test_flag_value() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("AUDIT", "Enable auditing"))
+            .flag(Flag::new("STRICT", "Strict checking"))
+            .flag(Flag::new("QUIET", "Suppress output"));
+
+        let result = parser.parse(args(&["DEBUG", "AUDIT=true", "STRICT=false"])).unwrap();
+
+        // Bare
+        assert_eq!(result.flag_value("DEBUG"), Some(true));
+        assert!(result.is_present("DEBUG"));
+
+        // =true
+        assert_eq!(result.flag_value("AUDIT"), Some(true));
+        assert!(result.is_present("AUDIT"));
+
+        // =false
+        assert_eq!(result.flag_value("STRICT"), Some(false));
+        assert!(!result.is_present("STRICT"));
+
+        // Absent
+        assert_eq!(result.flag_value("QUIET"), None);
+
+        // The last occurrence wins
+        let result = parser.parse(args(&["DEBUG", "DEBUG=no"])).unwrap();
+        assert_eq!(result.flag_value("DEBUG"), Some(false));
+        let result = parser.parse(args(&["DEBUG=0", "DEBUG"])).unwrap();
+        assert_eq!(result.flag_value("DEBUG"), Some(true));
+
+        assert!(matches!(parser.parse(args(&["DEBUG=maybe"])), Err(Error::InvalidBoolValue(_))));
+    }
+
+    #[test]
+    fn test_positional() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .positional("PROFILE", true);
+
+        // The first bare word fills the slot, later ones are non-argument text
+        let result = parser.parse(args(&["DEBUG", "strict", "USER=admin", "extra", "words"])).unwrap();
+        assert_eq!(result.positional("PROFILE"), Some("strict"));
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert_eq!(result.non_argument_text(), vec!["extra", "words"]);
+
+        // A required positional must be given
+        assert!(matches!(
+            parser.parse(args(&["DEBUG", "USER=admin"])),
+            Err(Error::RequiredArgMissing(name)) if name == "PROFILE"
+        ));
+
+        // An optional one may be left out
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .positional("PROFILE", false);
+        let result = parser.parse(args(&["DEBUG"])).unwrap();
+        assert_eq!(result.positional("PROFILE"), None);
+        assert!(matches!(parser.parse(args(&["one", "two"])), Err(Error::UnrecognizedArg(token)) if token == "two"));
+    }
+
+    #[test]
+    fn test_catch_all() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .catch_all("EXTRA");
+
+        let result = parser
+            .parse(args(&["retry=3", "USER=admin", "[mode='fast', empty=]", "DEBUG", "retry=4", "text"]))
+            .unwrap();
+
+        // Unknown pairs accumulate in input order
+        assert_eq!(result.entries_of("EXTRA"), vec!["retry=3", "mode=fast", "empty=", "retry=4"]);
+
+        // Known arguments route normally
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert!(result.is_present("DEBUG"));
+        assert!(!result.has_key("retry"));
+        assert_eq!(result.non_argument_text(), vec!["text"]);
+
+        // Without a catch-all, unknown pairs are errors again
+        let parser = ArgumentParser::new().key_value(KeyValue::new("USER", "Username"));
+        assert!(matches!(parser.parse(args(&["retry=3"])), Err(Error::UnrecognizedArg(_))));
+    }
+
+    #[test]
+    fn test_catch_all_name_conflict() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("EXTRA", "Extra options"))
+            .catch_all("EXTRA");
+        assert!(matches!(parser.validate(), Err(Error::DuplicateArgName(name)) if name == "EXTRA"));
+    }
+
+    #[test]
+    fn test_allowed_values_need_a_value_format() {
+        // Allowed values on a key that never takes a value
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("MODE", "Mode")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyOnly])
+                .allowed_values(&["fast", "safe"]),
+        );
+        assert!(matches!(parser.validate(), Err(Error::InvalidInput(message)) if message.contains("MODE")));
+        assert!(matches!(parser.parse(args(&["MODE"])), Err(Error::InvalidInput(_))));
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("MODE", "Mode")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyOnly, AllowedKeyValueFormats::KeyEquals])
+                .allowed_values(&["fast"]),
+        );
+        assert!(matches!(parser.validate(), Err(Error::InvalidInput(_))));
+
+        // Any format that takes a value makes the combination valid
+        for formats in [
+            vec![AllowedKeyValueFormats::KeyValue],
+            vec![AllowedKeyValueFormats::KeyOnly, AllowedKeyValueFormats::KeyValue],
+            vec![AllowedKeyValueFormats::KeyAll],
+        ] {
+            let parser = ArgumentParser::new().key_value(
+                KeyValue::new("MODE", "Mode")
+                    .allowed_formats(&formats)
+                    .allowed_values(&["fast", "safe"]),
+            );
+            assert!(parser.validate().is_ok(), "formats {:?}", formats);
+        }
+
+        // A key-only key without allowed values is fine
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("MODE", "Mode").allowed_formats(&[AllowedKeyValueFormats::KeyOnly]));
+        assert!(parser.validate().is_ok());
+    }
+
+    #[test]
+    fn test_source_of() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("PARANOID", "Enable all hardening options").implies("STRICT", "true"))
+            .key_value(KeyValue::new("STRICT", "Strict checking").default_value("false"))
+            .key_value(KeyValue::new("PORT", "Port").default_value("22"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        // Only the default supplies the value
+        let result = parser.parse(args(&[])).unwrap();
+        assert_eq!(result.source_of("STRICT"), Some(ValueSource::Default));
+        assert_eq!(result.source_of("USER"), None);
+
+        // Only the argument supplies the value
+        let result = parser.parse(args(&["USER=admin", "PORT=2222"])).unwrap();
+        assert_eq!(result.source_of("USER"), Some(ValueSource::Argument));
+        assert_eq!(result.source_of("PORT"), Some(ValueSource::Argument));
+
+        // The implication takes precedence over the default
+        let result = parser.parse(args(&["PARANOID"])).unwrap();
+        assert_eq!(result.source_of("STRICT"), Some(ValueSource::Implied));
+        assert_eq!(result.value_of::<bool>("STRICT"), Some(true));
+
+        // An explicit argument takes precedence over the implication
+        let result = parser.parse(args(&["PARANOID", "STRICT=false"])).unwrap();
+        assert_eq!(result.source_of("STRICT"), Some(ValueSource::Argument));
+    }
+
+    #[test]
+    fn test_key_normalizer() {
+        let config = ParserConfig::builder()
+            .enable_multi_key_value(true)
+            .key_normalizer(|name| name.strip_prefix("legacy_").unwrap_or(name).to_string())
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("debug", "Enable debug mode"))
+            .key_value(KeyValue::new("user", "Username"))
+            .key_value(KeyValue::new("port", "Port").default_value("22"));
+
+        let result = parser
+            .parse(args(&["legacy_debug", "legacy_user=admin", "[legacy_mode=fast]"]))
+            .unwrap();
+        assert!(result.is_present("debug"));
+        assert!(result.is_present("legacy_debug"));
+        assert_eq!(result.value_of::<String>("user"), Some("admin".to_string()));
+        assert_eq!(result.value_of::<String>("legacy_user"), Some("admin".to_string()));
+        assert_eq!(result.value_of::<u16>("legacy_port"), Some(22));
+        assert_eq!(result.keys(), vec!["mode", "user"]);
+
+        // The normalizer replaces case normalization
+        assert!(matches!(parser.parse(args(&["DEBUG"])), Err(Error::UnrecognizedArg(_))));
+
+        // Registration uses the normalizer for duplicate detection
+        let config = ParserConfig::builder()
+            .key_normalizer(|name| name.strip_prefix("legacy_").unwrap_or(name).to_string())
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("debug", "Enable debug mode"))
+            .flag(Flag::new("legacy_debug", "Enable debug mode"));
+        assert!(matches!(parser.validate(), Err(Error::DuplicateArgName(name)) if name == "legacy_debug"));
+    }
+
+    #[test]
+    fn test_join_adjacent_non_arg() {
+        let config = ParserConfig::builder()
+            .collect_non_argument_text(true)
+            .join_adjacent_non_arg(Some(' '))
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        // Adjacent text across and within arguments is joined
+        let result = parser.parse(args(&["Hello", "big world", "DEBUG"])).unwrap();
+        assert_eq!(result.non_argument_text(), vec!["Hello big world"]);
+
+        // Text separated by a recognized argument stays distinct
+        let result = parser.parse(args(&["first", "part", "USER=admin", "second", "DEBUG", "third"])).unwrap();
+        assert_eq!(result.non_argument_text(), vec!["first part", "second", "third"]);
+
+        // Without a separator every token is its own entry
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config);
+        let result = parser.parse(args(&["Hello", "world"])).unwrap();
+        assert_eq!(result.non_argument_text(), vec!["Hello", "world"]);
+    }
+
+    #[test]
+    fn test_is_absent() {
+        let parser = ArgumentParser::new()
+            .key_value(
+                KeyValue::new("USER", "Username")
+                    .allowed_formats(&[AllowedKeyValueFormats::KeyValue, AllowedKeyValueFormats::KeyOnly]),
+            )
+            .key_value(KeyValue::new("HOST", "Hostname"))
+            .key_value(KeyValue::new("PORT", "Port").default_value("22"))
+            .key_value(KeyValue::new("MODE", "Mode"));
+
+        let result = parser.parse(args(&["USER", "HOST=example.com"])).unwrap();
+
+        // Key-only entries are present without a value
+        assert!(!result.is_absent("USER"));
+        assert_eq!(result.value_of::<String>("USER"), None);
+
+        // Valued entries are present
+        assert!(!result.is_absent("HOST"));
+
+        // Defaults do not make a key present
+        assert!(result.is_absent("PORT"));
+        assert_eq!(result.value_of::<u16>("PORT"), Some(22));
+
+        assert!(result.is_absent("MODE"));
+
+        TestAssertions::new(&result)
+            .assert_absent("MODE")
+            .assert_absent("PORT");
+    }
+
+    #[test]
+    fn test_parse_str_joined_arguments() {
+        let config = ParserConfig::builder().collect_non_argument_text(true).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("QUIET", "Suppress output"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("MESSAGE", "Message"));
+
+        let result = parser
+            .parse_str("[DEBUG, USER='john doe'] QUIET MESSAGE=\"a [b] c\" trailing text")
+            .unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert!(result.is_present("QUIET"));
+        assert_eq!(result.value_of::<String>("USER"), Some("john doe".to_string()));
+        assert_eq!(result.value_of::<String>("MESSAGE"), Some("a [b] c".to_string()));
+        assert_eq!(result.non_argument_text(), vec!["trailing", "text"]);
+
+        assert!(matches!(parser.parse_str("[DEBUG QUIET"), Err(Error::UnclosedDelimiter(_))));
+    }
+
+    #[test]
+    fn test_parse_first_line() {
+        let input = io::Cursor::new("DEBUG USER='john doe' [WIDTH=80]\nQUIET\n");
+        let result = parser().parse_first_line(input).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert!(!result.is_present("QUIET"));
+        assert_eq!(result.value_of::<String>("USER"), Some("john doe".to_string()));
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+
+        // Unlike parse_reader, a leading comment or blank line is not skipped
+        let result = parser().parse_first_line(io::Cursor::new("\nDEBUG\n")).unwrap();
+        assert!(!result.is_present("DEBUG"));
+        let result = parser().parse_first_line(io::Cursor::new("# DEBUG\nQUIET\n"));
+        assert!(matches!(result, Err(Error::UnrecognizedArg(ref a)) if a == "#"));
+    }
+
+    #[test]
+    fn test_parse_first_line_empty_input() {
+        let result = parser().parse_first_line(io::Cursor::new("")).unwrap();
+        assert!(result.flags().is_empty());
+        assert!(result.keys().is_empty());
+
+        let parser = parser().key_value(KeyValue::new("HOST", "Host").required());
+        assert_required_arg_missing(&parser.parse_first_line(io::Cursor::new("")), "HOST");
+    }
+
+    #[test]
+    fn test_parse_first_line_errors() {
+        struct FailingReader;
+
+        impl io::Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken pipe"))
+            }
+        }
+
+        let result = parser().parse_first_line(io::BufReader::new(FailingReader));
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+
+        let result = parser().parse_first_line(io::Cursor::new("USER='unclosed"));
+        assert!(matches!(result, Err(Error::UnclosedDelimiter(_))));
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        let input = io::Cursor::new(
+            "# pam-args configuration\n\n  # indented comment\nDEBUG USER=admin\nQUIET\n",
+        );
+        let result = parser().parse_reader(input).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert!(!result.is_present("QUIET"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+    }
+
+    #[test]
+    fn test_parse_reader_empty_input() {
+        let result = parser().parse_reader(io::Cursor::new("")).unwrap();
+        assert!(result.flags().is_empty());
+        assert!(result.keys().is_empty());
+
+        let result = parser().parse_reader(io::Cursor::new("# only comments\n   \n")).unwrap();
+        assert!(result.flags().is_empty());
+
+        let parser = parser().key_value(KeyValue::new("HOST", "Host").required());
+        assert_required_arg_missing(&parser.parse_reader(io::Cursor::new("")), "HOST");
+    }
+
+    #[test]
+    fn test_parse_reader_comment_prefix() {
+        let config = ParserConfig::builder().comment_prefix(';').retain_comments(true).build();
+        let parser = ArgumentParser::with_config(config).flag(Flag::new("DEBUG", "Enable debug mode"));
+
+        let result = parser.parse_reader(io::Cursor::new("; pam-args configuration
+  ;indented
+DEBUG
+")).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.comments(), ["; pam-args configuration", "  ;indented"]);
+
+        // The default prefix is an argument line now
+        let result = parser.parse_reader(io::Cursor::new("# note
+DEBUG
+"));
+        assert!(matches!(result, Err(Error::UnrecognizedArg(ref a)) if a == "#"));
+    }
+
+    #[test]
+    fn test_parse_reader_io_error() {
+        struct FailingReader;
+
+        impl io::Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken pipe"))
+            }
+        }
+
+        let result = parser().parse_reader(io::BufReader::new(FailingReader));
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+    }
+
+    #[test]
+    fn test_dependency_not_met() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("HOST", "Host").depends_on("USER"));
+        let simulator = ErrorSimulator::new();
+
+        let result = parser.parse(simulator.dependency_not_met("HOST=localhost", "USER"));
+        assert_dependency_not_met(&result, "HOST", "USER");
+
+        assert!(parser.parse(args(&["HOST=localhost", "USER=admin"])).is_ok());
+        assert!(parser.parse(args(&["USER=admin"])).is_ok());
+        assert!(parser.parse(args(&[])).is_ok());
+    }
+
+    #[test]
+    fn test_required_dependency_precedence() {
+        let definitions = |config: ParserConfig| {
+            ArgumentParser::with_config(config)
+                .flag(Flag::new("DEBUG", "Enable debug mode"))
+                .key_value(KeyValue::new("USER", "Username"))
+                .key_value(KeyValue::new("HOST", "Host").required().depends_on("USER"))
+        };
+
+        // By default the missing required argument is reported
+        let parser = definitions(ParserConfig::new());
+        assert_required_arg_missing(&parser.parse(args(&["DEBUG"])), "HOST");
+        assert_required_arg_missing(&parser.parse(args(&["USER=admin"])), "HOST");
+
+        // With the check enabled the missing dependency takes precedence
+        let config = ParserConfig::builder().required_implies_dependency_check(true).build();
+        let parser = definitions(config);
+        assert_dependency_not_met(&parser.parse(args(&["DEBUG"])), "HOST", "USER");
+        assert_required_arg_missing(&parser.parse(args(&["USER=admin"])), "HOST");
+        assert!(parser.parse(args(&["USER=admin", "HOST=localhost"])).is_ok());
+    }
+
+    #[test]
+    fn test_chained_dependencies() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("A", "Depends on B").depends_on("B"))
+            .flag(Flag::new("B", "Depends on C").depends_on("C"))
+            .flag(Flag::new("C", "No dependencies"));
+
+        assert_dependency_not_met(&parser.parse(args(&["A"])), "A", "B");
+        assert_dependency_not_met(&parser.parse(args(&["A", "B"])), "B", "C");
+        assert_dependency_not_met(&parser.parse(args(&["B"])), "B", "C");
+        assert!(parser.parse(args(&["A", "B", "C"])).is_ok());
+        assert!(parser.parse(args(&["C"])).is_ok());
+    }
+
+    #[test]
+    fn test_allowed_values() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("ALIGN", "Text alignment").allowed_values(&["LEFT", "CENTER", "RIGHT"]));
+        let simulator = ErrorSimulator::new();
+
+        let result = parser.parse(args(&["ALIGN=LEFT"])).unwrap();
+        assert_eq!(result.value_of::<String>("ALIGN"), Some("LEFT".to_string()));
+
+        assert_invalid_value(&parser.parse(simulator.invalid_value("ALIGN", "BOTTOM")), "ALIGN", "BOTTOM");
+        assert_invalid_value(&parser.parse(args(&["ALIGN=left"])), "ALIGN", "left");
+    }
+
+    #[test]
+    fn test_allowed_values_case_insensitive() {
+        let config = ParserConfig::builder()
+            .case_sensitive(false)
+            .case_sensitive_values(false)
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("ALIGN", "Text alignment").allowed_values(&["LEFT", "RIGHT"]));
+
+        let result = parser.parse(args(&["align=left"])).unwrap();
+        assert_eq!(result.value_of::<String>("ALIGN"), Some("left".to_string()));
+        assert_invalid_value(&parser.parse(args(&["align=up"])), "ALIGN", "up");
+    }
+
+    #[test]
+    fn test_allowed_values_edge_cases() {
+        let key_equals = [AllowedKeyValueFormats::KeyValue, AllowedKeyValueFormats::KeyEquals];
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("MODE", "Mode")
+                .allowed_formats(&key_equals)
+                .allowed_values::<&str>(&[]),
+        );
+        assert_invalid_value(&parser.parse(args(&["MODE=anything"])), "MODE", "anything");
+        assert_invalid_value(&parser.parse(args(&["MODE="])), "MODE", "");
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("MODE", "Mode")
+                .allowed_formats(&key_equals)
+                .allowed_values(&["fast"]),
+        );
+        assert_invalid_value(&parser.parse(args(&["MODE="])), "MODE", "");
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("MODE", "Mode")
+                .allowed_formats(&key_equals)
+                .allowed_values(&["fast", ""]),
+        );
+        assert!(parser.parse(args(&["MODE="])).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_names_case_sensitive() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("DEBUG", "Enable debug mode again"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(ref n)) if n == "DEBUG"));
+
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("USER", "Flag"))
+            .key_value(KeyValue::new("USER", "Key-value"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(ref n)) if n == "USER"));
+
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("USER", "Username again"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(_))));
+
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("env", "Environment flag"))
+            .flag(Flag::new("ENV", "Another environment flag"));
+        assert!(parser.parse(args(&["env", "ENV"])).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_names_case_insensitive() {
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let parser = ArgumentParser::with_config(config.clone())
+            .flag(Flag::new("env", "Environment flag"))
+            .flag(Flag::new("ENV", "Another environment flag"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(ref n)) if n == "ENV"));
+
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("User", "Flag"))
+            .key_value(KeyValue::new("USER", "Key-value"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(ref n)) if n == "USER"));
+    }
+
+    #[test]
+    fn test_logs_redact_sensitive_values() {
+        let secret = "s3cr3t-redaction-test";
+        let parser = ArgumentParser::with_config(ParserConfig::builder().enable_multi_key_value(true).build())
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+            .key_value(KeyValue::new("PASSWORD", "Password"));
+
+        crate::testing::capture_trace_logs(|| {
+            let input = args(&["USER=admin", &format!("[DEBUG,PASSWORD={}]", secret), &format!("TOKEN='{} x'", secret)]);
+            parser.parse(input).unwrap();
+            parser.parse_line(&format!("DEBUG [USER=a,PASSWORD={}]", secret)).unwrap();
+        });
+
+        let logs = crate::testing::all_captured_logs();
+        assert!(logs.iter().any(|message| message.contains("PASSWORD=***")), "{:?}", logs);
+        assert!(logs.iter().all(|message| !message.contains(secret)), "{:?}", logs);
+    }
+
+    #[test]
+    fn test_case_only_collision_warns() {
+        crate::testing::capture_logs();
+
+        let config = ParserConfig::builder().case_sensitive(false).build();
+        let parser = ArgumentParser::with_config(config)
+            .flag(Flag::new("Verbose", "Verbose output"))
+            .key_value(KeyValue::new("VERBOSE", "Verbosity level"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(_))));
+
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        assert!(warnings
+            .iter()
+            .any(|msg| msg.contains("'Verbose' and 'VERBOSE' differ only by case")));
+
+        // Exact duplicates are only an error, not a case warning
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("TRACE_ONLY", "Trace"))
+            .flag(Flag::new("TRACE_ONLY", "Trace again"));
+        assert!(parser.parse(args(&[])).is_err());
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        assert!(!warnings.iter().any(|msg| msg.contains("TRACE_ONLY")));
+    }
+
+    #[test]
+    fn test_definition_warnings_logged_once() {
+        crate::testing::capture_logs();
+
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("Once_Only", "Mixed case"))
+            .key_value(KeyValue::new("ONCE_ONLY", "Upper case"));
+        parser.parse(args(&["Once_Only"])).unwrap();
+        parser.parse(args(&["ONCE_ONLY=1"])).unwrap();
+        parser.clone().parse(args(&[])).unwrap();
+
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        let count = warnings.iter().filter(|msg| msg.contains("'Once_Only' and 'ONCE_ONLY' differ only by case")).count();
+        assert_eq!(count, 1);
+
+        // Registering another argument validates the definitions again
+        let parser = parser.flag(Flag::new("EXTRA", "Extra flag"));
+        parser.parse(args(&[])).unwrap();
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        let count = warnings.iter().filter(|msg| msg.contains("'Once_Only' and 'ONCE_ONLY' differ only by case")).count();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_aliases_record_canonical_name() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode").alias("D"))
+            .key_value(KeyValue::new("USER", "Username").aliases(&["U", "LOGIN"]));
+
+        let result = parser.parse(args(&["D", "LOGIN=admin"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.flags(), ["DEBUG"]);
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert!(!result.has_key("LOGIN"));
+
+        let result = parser.parse(args(&["[DEBUG, U=root]"])).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("root".to_string()));
+    }
+
+    #[test]
+    fn test_duplicate_aliases() {
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode").alias("D"))
+            .key_value(KeyValue::new("DIR", "Directory").alias("D"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(name)) if name == "D"));
+
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("DETAILED", "Detailed output").alias("DEBUG"));
+        assert!(matches!(parser.parse(args(&[])), Err(Error::DuplicateArgName(name)) if name == "DEBUG"));
+    }
+
+    #[test]
+    fn test_default_values() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("WIDTH", "Output width").default_value("80"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let result = parser.parse(args(&[])).unwrap();
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(80));
+        assert!(!result.is_present("WIDTH"));
+        assert_eq!(result.value_of::<String>("USER"), None);
+
+        let result = parser.parse(args(&["WIDTH=120"])).unwrap();
+        assert_eq!(result.value_of::<i32>("WIDTH"), Some(120));
+    }
+
+    #[test]
+    fn test_required_argument_with_default() {
+        crate::testing::capture_logs();
+        let definition = KeyValue::new("REALM", "Authentication realm").required().default_value("LOCAL");
+
+        let parser = ArgumentParser::new().key_value(definition.clone());
+        let result = parser.parse(args(&[])).unwrap();
+        assert_eq!(result.value_of::<String>("REALM"), Some("LOCAL".to_string()));
+        parser.parse(args(&["REALM=CORP"])).unwrap();
+        let warnings = crate::testing::captured_logs(log::Level::Warn);
+        let count = warnings.iter().filter(|w| w.contains("Required argument 'REALM' has a default value")).count();
+        assert_eq!(count, 1);
+
+        let strict = ArgumentParser::with_config(ParserConfig::builder().strict_definitions(true).build())
+            .key_value(definition);
+        match strict.parse(args(&["REALM=CORP"])) {
+            Err(Error::InvalidInput(message)) => assert!(message.contains("'REALM'")),
+            other => panic!("Expected InvalidInput error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_default_values() {
+        // Reported before any input is seen, and again by every parse
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("MODE", "Mode").allowed_values(&["fast", "safe"]).default_value("slow"));
+        assert_invalid_value(&parser.validate(), "MODE", "slow");
+        assert_invalid_value(&parser.parse(args(&["MODE=fast"])), "MODE", "slow");
+
+        let parser = ArgumentParser::new().key_value(
+            KeyValue::new("NAME", "Name")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyValue])
+                .default_value(""),
+        );
+        assert_invalid_value(&parser.parse(args(&[])), "NAME", "");
+
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("MODE", "Mode").allowed_values(&["fast", "safe"]).default_value("safe"));
+        assert_eq!(parser.parse(args(&[])).unwrap().value_of::<String>("MODE"), Some("safe".to_string()));
+    }
+
+    #[test]
+    fn test_typed_value_of() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().case_sensitive(false).build())
+            .key_value(KeyValue::new("WIDTH", "Width in pixels").type_converter(converter::from_str::<i32>()))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let result = parser.parse(args(&["width=80", "USER=admin"])).unwrap();
+        assert_eq!(result.typed_value_of::<i32>("WIDTH"), Some(&80));
+        assert_eq!(result.typed_value_of::<i32>("width"), Some(&80));
+        assert_eq!(result.typed_value_of::<u32>("WIDTH"), None);
+        assert_eq!(result.typed_value_of::<String>("USER"), None);
+
+        let result = parser.parse(args(&["USER=admin"])).unwrap();
+        assert_eq!(result.typed_value_of::<i32>("WIDTH"), None);
+
+        let result = parser.parse(args(&["WIDTH=wide"]));
+        assert_invalid_value(&result, "WIDTH", "wide (invalid digit found in string)");
+    }
+
+    #[test]
+    fn test_help() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().help_width(60).build())
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .flag(Flag::new("QUIET", "Suppress output").excludes("DEBUG"))
+            .key_value(KeyValue::new("USER", "Username for authentication").required())
+            .key_value(KeyValue::new("WIDTH", "Output width").depends_on("USER"));
+
+        let help = parser.help();
+        for (name, description) in [
+            ("DEBUG", "Enable debug mode"),
+            ("QUIET", "Suppress output"),
+            ("USER=<value>", "Username for authentication"),
+            ("WIDTH=<value>", "Output width"),
+        ] {
+            assert!(help.contains(name) && help.contains(description), "{}", help);
+        }
+        assert!(help.contains("Username for authentication (required)"));
+        assert!(!help.contains("Output width (required)"));
+        assert!(help.contains("Conflicts with: DEBUG"));
+        assert!(help.contains("Requires: USER"));
+        assert!(help.find("QUIET").unwrap() < help.find("USER=").unwrap());
+        assert!(help.lines().all(|line| line.chars().count() <= 60));
+    }
+
+    #[test]
+    fn test_parse_into() {
+        #[derive(Debug, PartialEq)]
+        struct Settings {
+            debug: bool,
+            user: String,
+            width: i32,
+        }
+
+        let build = |result: &ParseResult| {
+            Ok(Settings {
+                debug: result.is_present("DEBUG"),
+                user: result.value_of("USER").unwrap_or_default(),
+                width: converter::convert_for_key(result.store.get("WIDTH").unwrap_or("0"), "WIDTH", None)?,
+            })
+        };
+
+        let settings = parser().parse_into(args(&["DEBUG", "USER=admin", "WIDTH=80"]), build).unwrap();
+        assert_eq!(settings, Settings { debug: true, user: "admin".to_string(), width: 80 });
+
+        // Errors from the builder are passed through
+        let result = parser().parse_into(args(&["WIDTH=wide"]), build);
+        assert!(matches!(result, Err(Error::InvalidIntValue(ref value)) if value == "wide"));
+
+        // Parse errors stop before the builder runs
+        let result = parser().parse_into(args(&["unknown"]), |_| -> Result<()> { panic!("builder should not run") });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_flag_binding_after_parse() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        struct Options {
+            debug: Arc<AtomicBool>,
+        }
+
+        let options = Options { debug: Arc::new(AtomicBool::new(false)) };
+        let debug = options.debug.clone();
+        let parser = ArgumentParser::new()
+            .flag(Flag::new("DEBUG", "Enable debug mode").bind(move |present| debug.store(present, Ordering::Relaxed)))
+            .flag(Flag::new("QUIET", "Suppress output").excludes("DEBUG"));
+
+        parser.parse(args(&["DEBUG"])).unwrap();
+        assert!(options.debug.load(Ordering::Relaxed));
+
+        parser.parse(args(&[])).unwrap();
+        assert!(!options.debug.load(Ordering::Relaxed));
+
+        // Bindings are left alone when parsing fails
+        options.debug.store(false, Ordering::Relaxed);
+        assert!(parser.parse(args(&["DEBUG", "QUIET"])).is_err());
+        assert!(!options.debug.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_brackets_without_multi_key_value() {
+        let input = args(&["[DEBUG, USER=admin]"]);
+
+        // Expanded by default even though multi key-value pairs are disabled
+        let result = parser().parse(input.clone()).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+
+        let strict = |multi_key_value: bool| {
+            ArgumentParser::with_config(
+                ParserConfig::builder()
+                    .require_multi_key_value_for_brackets(true)
+                    .enable_multi_key_value(multi_key_value)
+                    .build(),
+            )
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"))
+        };
+
+        match strict(false).parse(input.clone()) {
+            Err(Error::InvalidInput(message)) => assert_eq!(message, "bracketed groups are not enabled"),
+            other => panic!("Expected InvalidInput error, got {:?}", other),
+        }
+        assert!(strict(false).parse(args(&["DEBUG", "USER=admin"])).is_ok());
+
+        let result = strict(true).parse(input).unwrap();
+        assert!(result.is_present("DEBUG"));
+    }
+
+    #[test]
+    fn test_value_templating() {
+        let templating = |enabled: bool| {
+            ArgumentParser::with_config(ParserConfig::builder().enable_value_templating(enabled).build())
+                .key_value(KeyValue::new("URL", "Endpoint URL"))
+                .key_value(KeyValue::new("BASE", "Base URL"))
+                .key_value(KeyValue::new("HOST", "Server host"))
+                .key_value(KeyValue::new("PORT", "Server port"))
+        };
+
+        let input = args(&["URL=${BASE}/auth", "BASE=https://${HOST}:${PORT}", "HOST=localhost", "PORT=8443"]);
+        let result = templating(true).parse(input.clone()).unwrap();
+        assert_eq!(result.value_of::<String>("URL"), Some("https://localhost:8443/auth".to_string()));
+        assert_eq!(result.value_of::<String>("BASE"), Some("https://localhost:8443".to_string()));
+
+        // Off by default, and an unterminated reference is kept as written
+        let result = templating(false).parse(input).unwrap();
+        assert_eq!(result.value_of::<String>("URL"), Some("${BASE}/auth".to_string()));
+        let result = templating(true).parse(args(&["URL=${HOST", "HOST=localhost"])).unwrap();
+        assert_eq!(result.value_of::<String>("URL"), Some("${HOST".to_string()));
+    }
+
+    #[test]
+    fn test_value_templating_checks_expanded_values() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().enable_value_templating(true).build())
+            .flag(Flag::new("SECURE", "Use the secure profile").implies("MODE", "${DEFAULT_MODE}"))
+            .key_value(KeyValue::new("MODE", "Operation mode").allowed_values(&["fast", "safe"]))
+            .key_value(KeyValue::new("DEFAULT_MODE", "Mode used by profiles"))
+            .key_value(KeyValue::new("LEVEL", "Level")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyValue]))
+            .key_value(KeyValue::new("EMPTY", "Empty value"));
+
+        // A template expanding to an allowed value is accepted, also when implied
+        let result = parser.parse(args(&["MODE=${DEFAULT_MODE}", "DEFAULT_MODE=safe"])).unwrap();
+        assert_eq!(result.value_of::<String>("MODE"), Some("safe".to_string()));
+        let result = parser.parse(args(&["SECURE", "DEFAULT_MODE=fast"])).unwrap();
+        assert_eq!(result.value_of::<String>("MODE"), Some("fast".to_string()));
+
+        // A template expanding to a disallowed value is rejected
+        let result = parser.parse(args(&["MODE=${DEFAULT_MODE}", "DEFAULT_MODE=reckless"]));
+        assert_invalid_value(&result, "MODE", "reckless");
+        let result = parser.parse(args(&["SECURE", "DEFAULT_MODE=reckless"]));
+        assert_invalid_value(&result, "MODE", "reckless");
+
+        // An empty expansion must fit the formats of its key
+        let result = parser.parse(args(&["LEVEL=${EMPTY}", "EMPTY="]));
+        assert_error_variant(&result, |e| matches!(e, Error::InvalidKeyValue(_)));
+
+        // Without templating the raw value is checked as given
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("MODE", "Operation mode").allowed_values(&["fast", "safe"]));
+        let result = parser.parse(args(&["MODE=${DEFAULT_MODE}"]));
+        assert_invalid_value(&result, "MODE", "${DEFAULT_MODE}");
+    }
+
+    #[test]
+    fn test_value_templating_errors() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().enable_value_templating(true).build())
+            .key_value(KeyValue::new("URL", "Endpoint URL"))
+            .key_value(KeyValue::new("HOST", "Server host"));
+
+        let result = parser.parse(args(&["URL=https://${HOST}"]));
+        assert_invalid_value(&result, "URL", "https://${HOST} (unresolved reference ${HOST})");
+
+        let result = parser.parse(args(&["URL=${HOST}", "HOST=${URL}"]));
+        assert_error_variant(&result, |e| {
+            matches!(e, Error::InvalidValue(_, value) if value.contains("possibly a cycle"))
+        });
+
+        // Values of sensitive keys are redacted in the error
+        let parser = ArgumentParser::with_config(ParserConfig::builder().enable_value_templating(true).build())
+            .key_value(KeyValue::new("PASSWORD", "Password"));
+        let result = parser.parse(args(&["PASSWORD=s3cr3t-${SALT}"]));
+        assert_invalid_value(&result, "PASSWORD", "*** (unresolved reference ${SALT})");
+    }
+
+    #[test]
+    fn test_value_templating_limits_fan_out() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().enable_value_templating(true).build())
+            .key_value(KeyValue::new("A", "Level A"))
+            .key_value(KeyValue::new("B", "Level B"))
+            .key_value(KeyValue::new("C", "Level C"))
+            .key_value(KeyValue::new("D", "Level D"));
+
+        // Each level references the one below eight times, 584 substitutions in total
+        let fan_out = "${B}".repeat(8);
+        let result = parser.parse(args(&[
+            &format!("A={}", fan_out),
+            &format!("B={}", fan_out.replace('B', "C")),
+            &format!("C={}", fan_out.replace('B', "D")),
+            "D=x",
+        ]));
+        assert_error_variant(&result, |e| {
+            matches!(e, Error::InvalidValue(_, value) if value.contains("references to expand"))
+        });
+
+        // Few substitutions can still produce a value that is too long
+        let long = "x".repeat(MAX_EXPANDED_LENGTH / 2 + 1);
+        let result = parser.parse(args(&["A=${B}${B}", &format!("B={}", long)]));
+        assert_error_variant(&result, |e| {
+            matches!(e, Error::InvalidValue(_, value) if value.contains("characters"))
+        });
+    }
+
+    #[test]
+    fn test_parse_line_captures_module_name() {
+        let config = ParserConfig::builder()
+            .capture_module_name(true)
+            .collect_non_argument_text(true)
+            .build();
+        let capturing = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("USER", "Username"));
+
+        let line = create_pam_arg_string("pam_example.so", ["DEBUG", "USER=admin"]);
+        let result = capturing.parse_line(&line).unwrap();
+        assert_eq!(result.module_name(), Some("pam_example.so"));
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.value_of::<String>("USER"), Some("admin".to_string()));
+        assert!(result.non_argument_text().is_empty());
+
+        // A module name matching a flag is still not parsed as an option
+        let result = capturing.parse_line("DEBUG USER=admin").unwrap();
+        assert_eq!(result.module_name(), Some("DEBUG"));
+        assert!(!result.is_present("DEBUG"));
+
+        assert_eq!(capturing.parse_line("").unwrap().module_name(), None);
+
+        let result = parser().parse_line("DEBUG").unwrap();
+        assert_eq!(result.module_name(), None);
+        assert!(result.is_present("DEBUG"));
+    }
+
+    #[test]
+    fn test_parse_reader_retains_comments() {
+        let input = "# pam-args configuration\n\n  # indented comment\nDEBUG note\n# after\n";
+
+        let config = ParserConfig::builder()
+            .collect_non_argument_text(true)
+            .retain_comments(true)
+            .build();
+        let retaining = ArgumentParser::with_config(config)
+            .flag(Flag::new("DEBUG", "Enable debug mode"));
+        let result = retaining.parse_reader(io::Cursor::new(input)).unwrap();
+        assert!(result.is_present("DEBUG"));
+        assert_eq!(result.comments(), ["# pam-args configuration", "  # indented comment"]);
+        assert_eq!(result.non_argument_text(), vec!["note"]);
+
+        let result = retaining.parse_reader(io::Cursor::new("# only comments\n")).unwrap();
+        assert_eq!(result.comments(), ["# only comments"]);
+
+        let result = parser().parse_reader(io::Cursor::new(input)).unwrap_err();
+        assert!(matches!(result, Error::UnrecognizedArg(ref arg) if arg == "note"));
+        let result = parser().parse_reader(io::Cursor::new("# comment\nDEBUG\n")).unwrap();
+        assert!(result.comments().is_empty());
+    }
+
+    #[test]
+    fn test_escaped_quotes_in_values() {
+        let parser = ArgumentParser::new().key_value(KeyValue::new("KEY", "Value"));
+        let cases = [
+            (r#"KEY="a \"b\" c""#, r#"a "b" c"#),
+            (r"KEY='a \'b\' c'", "a 'b' c"),
+            (r#"KEY='a \"b\" c'"#, r#"a "b" c"#),
+            (r#"KEY="a \'b\' c""#, "a 'b' c"),
+            (r#"KEY="a 'b' c""#, "a 'b' c"),
+            (r#"[KEY="a \"b\" c"]"#, r#"a "b" c"#),
+        ];
+        for (line, expected) in cases {
+            let result = parser.parse_line(line).unwrap();
+            assert_eq!(result.value_of::<String>("KEY").as_deref(), Some(expected), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_value_of_uses_conversion_config() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("MODE", "File mode"))
+            .key_value(KeyValue::new("ENABLED", "Whether enabled"));
+        let result = parser.parse(args(&["MODE=0o755", "ENABLED=on"])).unwrap();
+        assert_eq!(result.value_of::<i32>("MODE"), Some(0o755));
+        assert_eq!(result.value_of::<bool>("ENABLED"), Some(true));
+
+        let config = ParserConfig::builder()
+            .conversion(ConverterConfig {
+                allow_radix_prefixes: false,
+                ..ConverterConfig::default()
+            })
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("MODE", "File mode"));
+        let result = parser.parse(args(&["MODE=0o755"])).unwrap();
+        assert_eq!(result.value_of::<i32>("MODE"), None);
+    }
+
+    #[test]
+    fn test_value_of_option_treats_none_values_as_absent() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("USER", "User name"))
+            .key_value(KeyValue::new("GROUP", "Group name"))
+            .key_value(KeyValue::new("SHELL", "Login shell"));
+        let result = parser.parse(args(&["USER=none", "GROUP=NULL", "SHELL=/bin/sh"])).unwrap();
+        assert_eq!(result.value_of::<Option<String>>("USER"), Some(None));
+        assert_eq!(result.value_of::<Option<String>>("GROUP"), Some(None));
+        assert_eq!(result.value_of::<Option<String>>("SHELL"), Some(Some("/bin/sh".to_string())));
+        assert_eq!(result.value_of::<String>("USER"), Some("none".to_string()));
+
+        let config = ParserConfig::builder()
+            .conversion(ConverterConfig {
+                recognize_none_values: false,
+                ..ConverterConfig::default()
+            })
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("USER", "User name"));
+        let result = parser.parse(args(&["USER=none"])).unwrap();
+        assert_eq!(result.value_of::<Option<String>>("USER"), Some(Some("none".to_string())));
+    }
+
+    #[test]
+    fn test_value_of_interprets_escapes() {
+        let banner = KeyValue::new("BANNER", "Login banner");
+        let parser = ArgumentParser::new().key_value(banner.clone());
+        let result = parser.parse(args(&["BANNER=Line1\\nLine2"])).unwrap();
+        assert_eq!(result.value_of::<String>("BANNER"), Some("Line1\\nLine2".to_string()));
+
+        let config = ParserConfig::builder()
+            .conversion(ConverterConfig {
+                interpret_escapes: true,
+                ..ConverterConfig::default()
+            })
+            .build();
+        let parser = ArgumentParser::with_config(config).key_value(banner);
+        let result = parser.parse(args(&["BANNER=Line1\\nLine2"])).unwrap();
+        assert_eq!(result.value_of::<String>("BANNER"), Some("Line1\nLine2".to_string()));
+    }
+
+    #[test]
+    fn test_value_of_uses_parser_escape_char() {
+        let config = ParserConfig::builder()
+            .conversion(ConverterConfig {
+                interpret_escapes: true,
+                ..ConverterConfig::default()
+            })
+            .escape_char('%')
+            .build();
+        let parser = ArgumentParser::with_config(config)
+            .key_value(KeyValue::new("BANNER", "Login banner"));
+        let result = parser.parse(args(&["BANNER=Line1%nLine2\\n"])).unwrap();
+        assert_eq!(result.value_of::<String>("BANNER"), Some("Line1\nLine2\\n".to_string()));
+    }
+
+    #[test]
+    fn test_set_of() {
+        let parser = ArgumentParser::new()
+            .key_value(KeyValue::new("CAPS", "Capabilities"))
+            .key_value(KeyValue::new("PORTS", "Open ports"));
+        let result = parser.parse(args(&["CAPS=write,'read only',write", "PORTS=22,x"])).unwrap();
+
+        let caps = result.set_of::<String>("CAPS").unwrap();
+        assert_eq!(caps, HashSet::from(["read only".to_string(), "write".to_string()]));
+        assert_eq!(result.set_of::<u16>("PORTS"), None);
+        assert_eq!(result.set_of::<String>("MISSING"), None);
+    }
+
+    #[test]
+    fn test_value_of_list() {
+        let parser = ArgumentParser::with_config(ParserConfig::builder().delimiter(';').build())
+            .key_value(KeyValue::new("GROUPS", "Allowed groups"));
+        let result = parser.parse(args(&["GROUPS=admin;staff;dev"])).unwrap();
+        assert_eq!(
+            result.value_of::<Vec<String>>("GROUPS"),
+            Some(vec!["admin".to_string(), "staff".to_string(), "dev".to_string()])
+        );
+        assert_eq!(result.value_of::<Vec<i32>>("GROUPS"), None);
+    }
+
+    #[test]
+    fn test_needs_multi_key_value() {
+        let parser = parser();
+
+        assert!(parser.needs_multi_key_value(&["DEBUG", "[HOST=localhost,PORT=22]"]));
+        assert!(parser.needs_multi_key_value(&["[USER=admin,EXTRA=1]"]));
+
+        // Registered arguments inside brackets need no multi key-value handling
+        assert!(!parser.needs_multi_key_value(&["[DEBUG,USER=admin]"]));
+        assert!(!parser.needs_multi_key_value(&["DEBUG", "HOST=localhost"]));
+        assert!(!parser.needs_multi_key_value(&[]));
+        assert!(!parser.needs_multi_key_value(&["[HOST=localhost"]));
+
+        // The advisory matches what parse does with the option off and on
+        assert!(parser.parse(args(&["[HOST=localhost]"])).is_err());
+        let config = ParserConfig::builder().enable_multi_key_value(true).build();
+        let multi = ArgumentParser::with_config(config);
+        let result = multi.parse(args(&["[HOST=localhost]"])).unwrap();
+        assert_eq!(result.value_of::<String>("HOST"), Some("localhost".to_string()));
+    }
+
+    #[test]
+    fn test_unused_after() {
+        let parser = parser();
+
+        let result = parser.parse(args(&["QUIET", "WIDTH=80"])).unwrap();
+        assert_eq!(parser.unused_after(&result), vec!["DEBUG", "USER"]);
+
+        let result = parser.parse(args(&["DEBUG", "QUIET", "USER=admin", "WIDTH=80"])).unwrap();
+        assert!(parser.unused_after(&result).is_empty());
+
+        let result = parser.parse(args(&[])).unwrap();
+        assert_eq!(parser.unused_after(&result), vec!["DEBUG", "QUIET", "USER", "WIDTH"]);
+    }
+}
@@ -6,7 +6,6 @@
 
 use std::collections::HashMap;
 use std::str::FromStr;
-use crate::error::{Error, Result};
 
 /// Trait defining the interface for key-value storage
 pub trait KeyValueStore {
@@ -185,6 +184,166 @@ impl DefaultKeyValueStore {
     pub fn clear(&mut self) {
         self.store.clear();
     }
+
+    /// Checks whether a key was given without a value
+    ///
+    /// A key-only entry such as `DEBUG` is stored as `None`, while a
+    /// key-equals entry such as `DEBUG=` is stored as `Some("")`. This
+    /// method is true only for the former.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to check
+    ///
+    /// # Returns
+    ///
+    /// true if the key is present and has no value
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(true);
+    /// store.add("DEBUG", None);
+    /// store.add("EMPTY", Some(""));
+    ///
+    /// assert!(store.is_key_only("DEBUG"));
+    /// assert!(!store.is_key_only("EMPTY"));
+    /// ```
+    pub fn is_key_only(&self, key: &str) -> bool {
+        let normalized_key = self.normalize_key(key);
+        matches!(self.store.get(&normalized_key), Some(None))
+    }
+
+    /// Checks whether a key was given with an explicitly empty value
+    ///
+    /// This is true for `KEY=` (stored as `Some("")`) and false for a
+    /// key-only `KEY` (stored as `None`).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to check
+    ///
+    /// # Returns
+    ///
+    /// true if the key is present with an empty value
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(true);
+    /// store.add("EMPTY", Some(""));
+    ///
+    /// assert!(store.is_empty_value("EMPTY"));
+    /// ```
+    pub fn is_empty_value(&self, key: &str) -> bool {
+        let normalized_key = self.normalize_key(key);
+        matches!(self.store.get(&normalized_key), Some(Some(value)) if value.is_empty())
+    }
+
+    /// Returns an iterator over all keys in the store
+    ///
+    /// Unlike `keys()`, this does not allocate. Keys are yielded in their
+    /// normalized form and in no particular order.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of key references
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(true);
+    /// store.add("USER", Some("admin"));
+    ///
+    /// assert_eq!(store.keys_iter().collect::<Vec<_>>(), vec!["USER"]);
+    /// ```
+    pub fn keys_iter(&self) -> impl Iterator<Item = &str> {
+        self.store.keys().map(|s| s.as_str())
+    }
+
+    /// Removes an entry from the store and returns it
+    ///
+    /// This supports consume-as-you-go processing: take each known option
+    /// as it is handled, and whatever remains afterwards is unexpected.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to remove
+    ///
+    /// # Returns
+    ///
+    /// `None` if the key is absent, `Some(None)` for a key-only entry, or
+    /// `Some(Some(value))` for an entry with a value
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(true);
+    /// store.add("USER", Some("admin"));
+    ///
+    /// assert_eq!(store.take("USER"), Some(Some("admin".to_string())));
+    /// assert_eq!(store.take("USER"), None);
+    /// ```
+    pub fn take(&mut self, key: &str) -> Option<Option<String>> {
+        let normalized_key = self.normalize_key(key);
+        self.store.remove(&normalized_key)
+    }
+
+    /// Returns a reference to the underlying map
+    ///
+    /// This is an escape hatch for bulk operations that the `KeyValueStore`
+    /// trait does not cover. Keys in the map are already normalized according
+    /// to the store's case sensitivity setting.
+    ///
+    /// # Returns
+    ///
+    /// A reference to the map of normalized keys to optional values
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(false);
+    /// store.add("USER", Some("admin"));
+    ///
+    /// assert_eq!(store.inner().get("user"), Some(&Some("admin".to_string())));
+    /// ```
+    pub fn inner(&self) -> &HashMap<String, Option<String>> {
+        &self.store
+    }
+
+    /// Consumes the store and returns the underlying map
+    ///
+    /// Keys in the map are already normalized according to the store's
+    /// case sensitivity setting.
+    ///
+    /// # Returns
+    ///
+    /// The map of normalized keys to optional values
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::DefaultKeyValueStore;
+    ///
+    /// let mut store = DefaultKeyValueStore::new(true);
+    /// store.add("DEBUG", None);
+    ///
+    /// let map = store.into_inner();
+    /// assert_eq!(map.get("DEBUG"), Some(&None));
+    /// ```
+    pub fn into_inner(self) -> HashMap<String, Option<String>> {
+        self.store
+    }
 }
 
 impl KeyValueStore for DefaultKeyValueStore {
@@ -252,6 +411,34 @@ impl NonArgTextStore {
         self.text.push(text.into());
     }
     
+    /// Appends text to the last entry, or adds it if the store is empty
+    ///
+    /// # Arguments
+    ///
+    /// * `separator` - The character placed between the last entry and the text
+    /// * `text` - The text to append
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use pam_args::storage::NonArgTextStore;
+    ///
+    /// let mut store = NonArgTextStore::new();
+    /// store.add("Hello");
+    /// store.append_to_last(' ', "world");
+    ///
+    /// assert_eq!(store.texts(), &["Hello world"]);
+    /// ```
+    pub fn append_to_last(&mut self, separator: char, text: &str) {
+        match self.text.last_mut() {
+            Some(last) => {
+                last.push(separator);
+                last.push_str(text);
+            }
+            None => self.add(text),
+        }
+    }
+    
     /// Adds multiple non-argument text strings to the store
     ///
     /// # Arguments
@@ -378,71 +565,11 @@ pub trait KeyValueStoreExt: KeyValueStore {
 // Implement the extension trait for any type that implements KeyValueStore
 impl<T: KeyValueStore> KeyValueStoreExt for T {}
 
-/// A trait for converting string arguments to specific types
-pub trait FromArgValue: Sized {
-    /// Converts a string argument value to this type
-    ///
-    /// # Arguments
-    ///
-    /// * `value` - The string value to convert
-    ///
-    /// # Returns
-    ///
-    /// The converted value or an error
-    fn from_arg_value(value: &str) -> Result<Self>;
-}
-
-// Implement FromArgValue for common types
-impl FromArgValue for String {
-    fn from_arg_value(value: &str) -> Result<Self> {
-        Ok(value.to_string())
-    }
-}
-
-impl FromArgValue for i32 {
-    fn from_arg_value(value: &str) -> Result<Self> {
-        value.parse::<i32>().map_err(|_| {
-            Error::InvalidIntValue(value.to_string())
-        })
-    }
-}
-
-impl FromArgValue for bool {
-    fn from_arg_value(value: &str) -> Result<Self> {
-        match value.to_lowercase().as_str() {
-            "true" | "yes" | "1" | "on" => Ok(true),
-            "false" | "no" | "0" | "off" => Ok(false),
-            _ => Err(Error::InvalidBoolValue(value.to_string())),
-        }
-    }
-}
-
-impl FromArgValue for char {
-    fn from_arg_value(value: &str) -> Result<Self> {
-        let mut chars = value.chars();
-        match (chars.next(), chars.next()) {
-            (Some(c), None) => Ok(c),
-            _ => Err(Error::InvalidInput(format!(
-                "Expected a single character, got: '{}'", value
-            ))),
-        }
-    }
-}
-
-// Implement FromArgValue for Option<T> types
-impl<T: FromArgValue> FromArgValue for Option<T> {
-    fn from_arg_value(value: &str) -> Result<Self> {
-        if value.is_empty() {
-            Ok(None)
-        } else {
-            T::from_arg_value(value).map(Some)
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::conversion::FromArgValue;
+    use crate::error::Error;
 
     #[test]
     fn test_default_store_creation() {
@@ -520,6 +647,84 @@ mod tests {
         assert!(store.is_empty());
     }
 
+    #[test]
+    fn test_default_store_inner() {
+        let mut store = DefaultKeyValueStore::new(false);
+        store.add("USER", Some("admin"));
+        store.add("DEBUG", None);
+
+        // Keys are stored normalized
+        let inner = store.inner();
+        assert_eq!(inner.len(), 2);
+        assert_eq!(inner.get("user"), Some(&Some("admin".to_string())));
+        assert_eq!(inner.get("debug"), Some(&None));
+        assert!(inner.get("USER").is_none());
+
+        let map = store.into_inner();
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key("user"));
+    }
+
+    #[test]
+    fn test_default_store_key_only_vs_empty_value() {
+        let mut store = DefaultKeyValueStore::new(true);
+        store.add("DEBUG", None);
+        store.add("EMPTY", Some(""));
+        store.add("USER", Some("admin"));
+
+        assert!(store.is_key_only("DEBUG"));
+        assert!(!store.is_empty_value("DEBUG"));
+
+        assert!(!store.is_key_only("EMPTY"));
+        assert!(store.is_empty_value("EMPTY"));
+
+        assert!(!store.is_key_only("USER"));
+        assert!(!store.is_empty_value("USER"));
+
+        assert!(!store.is_key_only("MISSING"));
+        assert!(!store.is_empty_value("MISSING"));
+
+        // Both read back as no value through get()
+        assert_eq!(store.get("DEBUG"), None);
+        assert_eq!(store.get("EMPTY"), Some(""));
+    }
+
+    #[test]
+    fn test_default_store_keys_iter() {
+        let mut store = DefaultKeyValueStore::new(false);
+        assert_eq!(store.keys_iter().count(), 0);
+
+        store.add("USER", Some("admin"));
+        store.add("DEBUG", None);
+        store.add("EMPTY", Some(""));
+
+        let mut from_iter: Vec<&str> = store.keys_iter().collect();
+        let mut from_keys = store.keys();
+        from_iter.sort_unstable();
+        from_keys.sort_unstable();
+        assert_eq!(from_iter, from_keys);
+        assert_eq!(from_iter, vec!["debug", "empty", "user"]);
+    }
+
+    #[test]
+    fn test_default_store_take() {
+        let mut store = DefaultKeyValueStore::new(false);
+        store.add("USER", Some("admin"));
+        store.add("DEBUG", None);
+
+        // Valued entry, looked up case-insensitively
+        assert_eq!(store.take("user"), Some(Some("admin".to_string())));
+        assert!(!store.has_key("USER"));
+
+        // Key-only entry
+        assert_eq!(store.take("DEBUG"), Some(None));
+
+        // Absent entry, including one already taken
+        assert_eq!(store.take("HOST"), None);
+        assert_eq!(store.take("USER"), None);
+        assert!(store.is_empty());
+    }
+
     #[test]
     fn test_non_arg_text_store() {
         let mut store = NonArgTextStore::new();
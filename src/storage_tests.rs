@@ -5,9 +5,11 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::storage::{DefaultKeyValueStore, KeyValueStore, KeyValueStoreExt, NonArgTextStore, FromArgValue};
+    use crate::conversion::{ConverterConfig, FromArgValue};
+    use crate::storage::{DefaultKeyValueStore, KeyValueStore, KeyValueStoreExt, NonArgTextStore};
     use crate::error::Error;
     use crate::AllowedKeyValueFormats;
+    use std::collections::HashSet;
 
     #[test]
     fn test_storage_integration() {
@@ -48,6 +50,10 @@ mod tests {
             "Even more text"
         ]);
         
+        // Append to the last entry
+        text_store.append_to_last(' ', "again");
+        assert_eq!(text_store.texts().last().map(String::as_str), Some("Even more text again"));
+        
         // Using FromArgValue trait
         let port_value = "8080";
         let port = i32::from_arg_value(port_value).unwrap();
@@ -113,6 +119,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_arg_value_hashset() {
+        // Duplicates collapse and each element is converted
+        let caps = HashSet::<String>::from_arg_value("read,write read, admin").unwrap();
+        assert_eq!(caps.len(), 3);
+        assert!(caps.contains("read"));
+        assert!(caps.contains("write"));
+        assert!(caps.contains("admin"));
+        
+        let ports = HashSet::<i32>::from_arg_value("22,80,22").unwrap();
+        assert_eq!(ports, HashSet::from([22, 80]));
+        
+        // A bad element fails the whole set
+        match HashSet::<i32>::from_arg_value("22,http,80") {
+            Err(Error::InvalidIntValue(val)) => assert_eq!(val, "http"),
+            _ => panic!("Expected InvalidIntValue error"),
+        }
+    }
+
+    #[test]
+    fn test_from_arg_value_for_key() {
+        #[derive(Debug)]
+        struct Mode(String);
+
+        impl FromArgValue for Mode {
+            fn from_arg_value_with_config(
+                value: &str,
+                _config: Option<&ConverterConfig>,
+            ) -> crate::error::Result<Self> {
+                match value {
+                    "enforce" | "permissive" => Ok(Mode(value.to_string())),
+                    _ => Err(Error::InvalidInput(value.to_string())),
+                }
+            }
+
+            fn from_arg_value_for_key(
+                value: &str,
+                key: &str,
+                config: Option<&ConverterConfig>,
+            ) -> crate::error::Result<Self> {
+                Self::from_arg_value_with_config(value, config)
+                    .map_err(|_| Error::InvalidValue(key.to_string(), value.to_string()))
+            }
+        }
+
+        assert_eq!(Mode::from_arg_value_for_key("enforce", "MODE", None).unwrap().0, "enforce");
+        match Mode::from_arg_value_for_key("off", "MODE", None) {
+            Err(Error::InvalidValue(key, value)) => {
+                assert_eq!(key, "MODE");
+                assert_eq!(value, "off");
+            },
+            _ => panic!("Expected InvalidValue error naming the key"),
+        }
+
+        // Built-in types use the default, which ignores the key
+        assert_eq!(
+            i32::from_arg_value_for_key("x", "WIDTH", None),
+            Err(Error::InvalidIntValue("x".to_string()))
+        );
+    }
+
     #[test]
     fn test_key_overwrite() {
         let mut store = DefaultKeyValueStore::new(true);
@@ -4,9 +4,14 @@
 //! the library and PAM modules that use it. These utilities provide functions for creating
 //! mock arguments, verifying parsing results, and simulating different input scenarios.
 
+#[cfg(test)]
+use crate::args::AllowedKeyValueFormats;
+use crate::config::ParserConfig;
+use crate::conversion::FromArgValue;
 use crate::error::{Error, Result};
-use std::str::FromStr;
+use crate::parser::{ArgumentParser, ParseResult};
 use colored::Colorize;
+use std::sync::{Mutex, Once};
 
 // Define macros at the top of the file so they're available throughout
 #[macro_export]
@@ -62,6 +67,9 @@ pub struct TestConfig {
     
     /// Whether to mix argument types in random order
     pub randomize_order: bool,
+    
+    /// Seed for the random order; the current time is used if unset
+    pub seed: Option<u64>,
 }
 
 impl Default for TestConfig {
@@ -72,6 +80,7 @@ impl Default for TestConfig {
             quote_char: '"',
             include_non_arg_text: false,
             randomize_order: false,
+            seed: None,
         }
     }
 }
@@ -121,6 +130,12 @@ impl TestConfig {
         self.randomize_order = randomize;
         self
     }
+    
+    /// Set the seed used to randomize the order of arguments
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
 }
 
 /// Builder for creating test arguments
@@ -228,18 +243,18 @@ impl TestArgsBuilder {
         
         // Randomize order if enabled
         if self.config.randomize_order && result.len() > 1 {
-            use std::collections::HashMap;
-            
             // Fisher-Yates shuffle algorithm
-            let mut rng = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            let mut rng = self.config.seed.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+            });
             
             for i in (1..result.len()).rev() {
-                // Simple PRNG for deterministic shuffling
-                rng = (rng * 48271) % 0x7fffffff;
-                let j = (rng % (i + 1) as u64) as usize;
+                // Simple PRNG for deterministic shuffling; wrapping so large seeds cannot overflow
+                rng = rng.wrapping_mul(48271) % 0x7fffffff;
+                let j = (rng % (i as u64 + 1)) as usize;
                 result.swap(i, j);
             }
         }
@@ -248,39 +263,6 @@ impl TestArgsBuilder {
     }
 }
 
-// Placeholder for ParseResult until it's implemented
-#[doc(hidden)]
-struct ParseResult {
-    // This will be replaced with the actual implementation
-}
-
-impl ParseResult {
-    // Placeholder methods
-    pub fn is_present(&self, _flag: &str) -> bool {
-        false
-    }
-    
-    pub fn value_of<T>(&self, _key: &str) -> Option<T> {
-        None
-    }
-    
-    pub fn has_key(&self, _key: &str) -> bool {
-        false
-    }
-    
-    pub fn non_argument_text(&self) -> Vec<&str> {
-        Vec::new()
-    }
-    
-    pub fn flags(&self) -> Vec<&str> {
-        Vec::new()
-    }
-    
-    pub fn keys(&self) -> Vec<&str> {
-        Vec::new()
-    }
-}
-
 /// Struct for fluent assertions on parse results
 pub struct TestAssertions<'a> {
     parse_result: &'a ParseResult,
@@ -317,8 +299,7 @@ impl<'a> TestAssertions<'a> {
         expected: T,
     ) -> Self
     where
-        T: FromStr,
-        <T as FromStr>::Err: std::fmt::Debug,
+        T: FromArgValue,
     {
         let actual = self.parse_result.value_of::<T>(key.as_ref());
         assert_colored!(
@@ -345,6 +326,18 @@ impl<'a> TestAssertions<'a> {
         self
     }
     
+    /// Assert that a key did not appear in the input
+    ///
+    /// Unlike `assert_value_equals`, a key given without a value counts as
+    /// present and fails this assertion.
+    pub fn assert_absent<S: AsRef<str>>(self, key: S) -> Self {
+        assert_colored!(
+            self.parse_result.is_absent(key.as_ref()),
+            format!("Expected key '{}' to be absent, but it was present", key.as_ref())
+        );
+        self
+    }
+    
     /// Assert that the non-argument text matches the expected value
     pub fn assert_non_arg_text<S: AsRef<str>>(self, expected: &[S]) -> Self {
         let actual = self.parse_result.non_argument_text();
@@ -382,6 +375,118 @@ impl<'a> TestAssertions<'a> {
     }
 }
 
+/// Builder for the expected result of a parse
+///
+/// Used with `assert_parses_to` for table-driven tests.
+pub struct ParseResultBuilder {
+    result: ParseResult,
+}
+
+impl ParseResultBuilder {
+    /// Create a builder for an empty result with the default configuration
+    pub fn new() -> Self {
+        Self::with_config(&ParserConfig::default())
+    }
+    
+    /// Create a builder for an empty result with the given configuration
+    pub fn with_config(config: &ParserConfig) -> Self {
+        Self { result: ParseResult::new(config) }
+    }
+    
+    /// Expect a flag to be present
+    pub fn flag<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.result.add_flag(name.as_ref());
+        self
+    }
+    
+    /// Expect a key with a value
+    pub fn key_value<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+        self.result.add_key_value(key.as_ref(), Some(value.as_ref()));
+        self
+    }
+    
+    /// Expect a key given without a value
+    pub fn key_only<S: AsRef<str>>(mut self, key: S) -> Self {
+        self.result.add_key_value(key.as_ref(), None);
+        self
+    }
+    
+    /// Expect a piece of non-argument text
+    pub fn non_arg_text<S: AsRef<str>>(mut self, text: S) -> Self {
+        self.result.add_non_arg_text(text.as_ref());
+        self
+    }
+    
+    /// Build the expected result
+    pub fn build(self) -> ParseResult {
+        self.result
+    }
+}
+
+impl Default for ParseResultBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The parts of a parse result compared by `assert_parses_to`
+#[derive(Debug, PartialEq)]
+struct ParsedArguments {
+    flags: Vec<String>,
+    key_values: std::collections::BTreeMap<String, Option<String>>,
+    non_arg_text: Vec<String>,
+}
+
+impl From<&ParseResult> for ParsedArguments {
+    fn from(result: &ParseResult) -> Self {
+        Self {
+            flags: result.flags().iter().map(|flag| flag.to_string()).collect(),
+            key_values: result
+                .key_value_store()
+                .inner()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            non_arg_text: result.non_argument_text().iter().map(|text| text.to_string()).collect(),
+        }
+    }
+}
+
+/// Assert that parsing the input gives the expected flags, key-values and text
+///
+/// The result is compared field by field, and a mismatch panics with a colored
+/// message naming each differing field.
+pub fn assert_parses_to<S: AsRef<str>>(parser: &ArgumentParser, input: &[S], expected: &ParseResult) {
+    let args = input.iter().map(|arg| arg.as_ref().to_string()).collect();
+    let actual = match parser.parse(args) {
+        Ok(result) => ParsedArguments::from(&result),
+        Err(e) => panic!("{}", format!("Expected the input to parse, but got {:?}", e).red()),
+    };
+    let expected = ParsedArguments::from(expected);
+    
+    let mut differences = Vec::new();
+    if actual.flags != expected.flags {
+        differences.push(format!("  flags: expected {:?}, got {:?}", expected.flags, actual.flags));
+    }
+    if actual.key_values != expected.key_values {
+        differences.push(format!("  key-values: expected {:?}, got {:?}", expected.key_values, actual.key_values));
+    }
+    if actual.non_arg_text != expected.non_arg_text {
+        differences.push(format!("  non-argument text: expected {:?}, got {:?}", expected.non_arg_text, actual.non_arg_text));
+    }
+    assert_eq_colored!(
+        actual,
+        expected,
+        format!("Parse result differs from the expected result:\n{}", differences.join("\n"))
+    );
+}
+
+impl Default for TestArgsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Utility for simulating error conditions
 pub struct ErrorSimulator {
     config: TestConfig,
@@ -461,22 +566,27 @@ impl ErrorSimulator {
     }
 }
 
-// Placeholder for ArgumentParser until it's implemented
-#[doc(hidden)]
-struct ArgumentParser {
-    // This will be replaced with the actual implementation
-}
-
-impl ArgumentParser {
-    // Placeholder methods
-    fn parse(&self, _args: Vec<String>) -> Result<ParseResult> {
-        Ok(ParseResult {})
+impl Default for ErrorSimulator {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Create a test parser with common configuration
+///
+/// Undefined `KEY=VALUE` and `KEY=` arguments are stored as multi key-value
+/// pairs and everything else is collected as non-argument text.
+#[cfg(test)]
 fn create_test_parser() -> ArgumentParser {
-    ArgumentParser {}
+    let config = ParserConfig::builder()
+        .enable_multi_key_value(true)
+        .multi_key_value_formats(&[
+            AllowedKeyValueFormats::KeyValue,
+            AllowedKeyValueFormats::KeyEquals,
+        ])
+        .collect_non_argument_text(true)
+        .build();
+    ArgumentParser::with_config(config)
 }
 
 /// Create a PAM argument string from a module name and arguments
@@ -579,7 +689,93 @@ pub fn assert_invalid_value<T, S1: AsRef<str>, S2: AsRef<str>>(
     });
 }
 
+/// Messages recorded by the capturing logger, with their level
+static CAPTURED_LOGS: Mutex<Vec<(log::Level, String)>> = Mutex::new(Vec::new());
+
+/// Logger that records every message so tests can assert on log output
+struct CaptureLogger;
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Ok(mut logs) = CAPTURED_LOGS.lock() {
+            logs.push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Returns the log formatting configuration shared by all tests
+///
+/// The configuration is global and can only be set once, so every test
+/// that initializes it must use this one.
+pub fn test_log_config() -> crate::logging::LogConfig {
+    crate::logging::LogConfig {
+        include_timestamps: false,
+        include_component: true,
+        include_operation: false,
+        json_format: true,
+        prefix_style: crate::logging::LogPrefixStyle::Colon,
+        component_operation_separator: " ".to_string(),
+        redact_keys: crate::logging::LogConfig::default().redact_keys,
+    }
+}
+
+/// Installs the capturing logger for the test process
+///
+/// Only warnings and errors are recorded to keep the buffer small, unless
+/// `capture_trace_logs` raises the level. Calling this more than once is
+/// harmless. Tests run in parallel and share the logger, so assertions
+/// should look for messages specific to the test.
+pub fn capture_logs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        crate::logging::logger::init(test_log_config());
+        let _ = crate::logging::init::with_logger(Box::new(CaptureLogger), log::LevelFilter::Trace);
+        log::set_max_level(log::LevelFilter::Warn);
+    });
+}
+
+/// Runs a function while messages of every level are captured
+///
+/// The level is shared by all tests, so messages from tests running at the
+/// same time may be recorded as well.
+pub fn capture_trace_logs<F: FnOnce()>(f: F) {
+    static TRACE: Mutex<()> = Mutex::new(());
+    capture_logs();
+    let _guard = TRACE.lock().unwrap_or_else(|e| e.into_inner());
+    log::set_max_level(log::LevelFilter::Trace);
+    f();
+    log::set_max_level(log::LevelFilter::Warn);
+}
+
+/// Returns every captured message, whatever its level
+pub fn all_captured_logs() -> Vec<String> {
+    CAPTURED_LOGS
+        .lock()
+        .map(|logs| logs.iter().map(|(_, message)| message.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the captured messages logged at the given level
+pub fn captured_logs(level: log::Level) -> Vec<String> {
+    CAPTURED_LOGS
+        .lock()
+        .map(|logs| {
+            logs.iter()
+                .filter(|(logged, _)| *logged == level)
+                .map(|(_, message)| message.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Macro for defining test cases
+#[cfg(test)]
 #[doc(hidden)]
 macro_rules! test_case {
     (args: [$($arg:expr),* $(,)?], assert: $assert_fn:expr) => {
@@ -595,6 +791,7 @@ macro_rules! test_case {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::args::{Flag, KeyValue};
     
     #[test]
     fn test_test_config_default() {
@@ -604,6 +801,7 @@ mod tests {
         assert_eq!(config.quote_char, '"');
         assert!(!config.include_non_arg_text);
         assert!(!config.randomize_order);
+        assert_eq!(config.seed, None);
     }
     
     #[test]
@@ -800,4 +998,77 @@ mod tests {
         let args = simulator.invalid_bool_value("DEBUG");
         assert_eq!(args, vec!["DEBUG=not_a_boolean"]);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_randomize_order_with_large_seed() {
+        for seed in [u64::MAX, u64::MAX / 3, 0x7fffffff, 0] {
+            let config = TestConfig::new().with_randomize_order(true).with_seed(seed);
+            let build = || {
+                TestArgsBuilder::with_config(config.clone())
+                    .add_flags(["A", "B", "C", "D"])
+                    .add_key_value("KEY", "value")
+                    .build()
+            };
+            
+            let mut args = build();
+            assert_eq!(args, build());
+            args.sort();
+            assert_eq!(args, vec!["A", "B", "C", "D", "KEY=value"]);
+        }
+    }
+    
+    #[test]
+    fn test_assert_parses_to() {
+        let parser = create_test_parser();
+        let expected = ParseResultBuilder::with_config(parser.config())
+            .key_value("USER", "admin")
+            .key_value("EMPTY", "")
+            .non_arg_text("hello")
+            .build();
+        assert_parses_to(&parser, &["USER=admin", "EMPTY=", "hello"], &expected);
+        
+        // Key-values are compared regardless of order
+        assert_parses_to(&parser, &["hello", "EMPTY=", "USER=admin"], &expected);
+        
+        let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            assert_parses_to(&parser, &["USER=root", "EMPTY=", "hello"], &expected);
+        }));
+        let message = mismatch.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("key-values: expected"), "{}", message);
+        assert!(message.contains("\"root\""), "{}", message);
+        assert!(!message.contains("flags: expected"), "{}", message);
+        
+        let parser = create_test_parser()
+            .flag(Flag::new("DEBUG", "Enable debug mode"))
+            .key_value(KeyValue::new("VERBOSE", "Verbose output")
+                .allowed_formats(&[AllowedKeyValueFormats::KeyOnly]));
+        let expected = ParseResultBuilder::with_config(parser.config())
+            .flag("DEBUG")
+            .key_only("VERBOSE")
+            .build();
+        assert_parses_to(&parser, &["DEBUG", "VERBOSE"], &expected);
+        
+        let mismatch = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            assert_parses_to(&parser, &["VERBOSE"], &expected);
+        }));
+        let message = mismatch.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("flags: expected [\"DEBUG\"], got []"), "{}", message);
+    }
+    
+    #[test]
+    fn test_test_assertions_on_parse_result() {
+        test_case!(
+            args: ["USER=admin", "WIDTH=80", "EMPTY=", "hello"],
+            assert: |result: &ParseResult| {
+                TestAssertions::new(result)
+                    .assert_value_equals("USER", "admin".to_string())
+                    .assert_value_equals("WIDTH", 80)
+                    .assert_key_not_present("HOST")
+                    .assert_flag_not_present("DEBUG")
+                    .assert_key_value_count(3)
+                    .assert_flag_count(0)
+                    .assert_non_arg_text(&["hello"]);
+            }
+        );
+    }
+}
@@ -1,8 +1,13 @@
-//! # Internal Tokenizer Implementation
+//! # Tokenizer
 //!
-//! This module provides the implementation of the tokenizer component,
-//! which is responsible for processing pre-tokenized input arguments into
-//! structured tokens for further processing.
+//! This module provides the tokenizer component, which is responsible for
+//! processing pre-tokenized input arguments into structured tokens for
+//! further processing. The parser uses it internally; `Tokenizer` and its
+//! types are re-exported at the crate root so callers can pre-tokenize
+//! arguments or inspect how bracketed content is expanded. The configuration
+//! and result types are `#[non_exhaustive]`: start from
+//! `TokenizerConfig::default()` and assign fields rather than using a struct
+//! literal.
 //!
 //! ## Design Notes
 //!
@@ -15,8 +20,11 @@
 //! - `InBracket`: Inside square brackets
 //! - `EscapeSequence`: After a backslash, next character is escaped
 
+use crate::config::ParserConfig;
 use crate::error::{Error, Result};
+use crate::logging::logger::redact_with;
 use log::{debug, trace};
+use std::ops::Range;
 
 /// Represents the current state of the tokenizer during parsing
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -39,6 +47,7 @@ enum TokenizerState {
 
 /// Configuration options for the tokenizer
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct TokenizerConfig {
     /// Character used for escaping special characters
     pub escape_char: char,
@@ -57,32 +66,71 @@ pub struct TokenizerConfig {
     
     /// Delimiter for comma-separated values within brackets
     pub delimiter: char,
+    
+    /// How a delimiter at the end of bracketed content is handled
+    pub trailing_delimiter_policy: TrailingDelimiterPolicy,
+    
+    /// Whether brackets may nest, e.g. `[OUTER=[a,b],X=1]`
+    ///
+    /// Only the outermost brackets are split on the delimiter; inner bracketed
+    /// content is kept verbatim inside its token.
+    pub allow_nested_brackets: bool,
+    
+    /// Whether whitespace around each bracketed element is removed
+    ///
+    /// `[ A = 1 , B ]` yields `["A = 1", "B"]`; whitespace inside quotes and
+    /// escaped whitespace at either end are kept.
+    pub trim_bracket_elements: bool,
+}
+
+/// Handling of a trailing delimiter in bracketed content, e.g. `[a,]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingDelimiterPolicy {
+    /// Keep the trailing empty token: `[a,]` yields `["a", ""]`
+    KeepEmpty,
+    
+    /// Drop all trailing empty tokens: `[a,,]` yields `["a"]`
+    Drop,
+    
+    /// Reject the argument with an error
+    Error,
 }
 
 /// Result of tokenization
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct TokenizationResult {
     /// Tokens extracted from the input
     pub tokens: Vec<String>,
     
     /// Whether the input contained bracket-delimited content
     pub has_bracketed_content: bool,
+    
+    /// Index ranges into `tokens` produced by each bracketed argument, in order
+    pub groups: Vec<Range<usize>>,
+    
+    /// Details of each entry in `tokens`, in the same order
+    pub metadata: Vec<Token>,
 }
 
 /// Represents a token with information about its original delimiters
-#[derive(Debug, Clone)]
-struct Token {
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Token {
     /// The actual content of the token
-    content: String,
+    pub content: String,
     
-    /// The original input string that produced this token
-    original: String,
+    /// The original input argument that produced this token
+    pub original: String,
     
-    /// Whether this token was enclosed in brackets
-    was_bracketed: bool,
+    /// Byte range of the token within `original`
+    pub span: Range<usize>,
     
-    /// Whether this token was enclosed in quotes
-    was_quoted: bool,
+    /// Whether this token came from inside brackets
+    pub was_bracketed: bool,
+    
+    /// Whether this token contains quoted text, e.g. `USER='admin'`
+    pub was_quoted: bool,
 }
 
 /// Main tokenizer struct that handles input processing
@@ -95,9 +143,8 @@ impl Tokenizer {
     ///
     /// # Examples
     ///
-    /// ```ignore
-    /// // This is an internal module, not part of the public API
-    /// use pam_args::internal::tokenizer::Tokenizer;
+    /// ```
+    /// use pam_args::Tokenizer;
     ///
     /// let tokenizer = Tokenizer::new();
     /// ```
@@ -115,17 +162,13 @@ impl Tokenizer {
     ///
     /// # Examples
     ///
-    /// ```ignore
-    /// use pam_args::tokenizer::{Tokenizer, TokenizerConfig};
+    /// ```
+    /// use pam_args::{Tokenizer, TokenizerConfig, TrailingDelimiterPolicy};
     ///
-    /// let config = TokenizerConfig {
-    ///     escape_char: '\\',
-    ///     single_quote: '\'',
-    ///     double_quote: '"',
-    ///     open_bracket: '[',
-    ///     close_bracket: ']',
-    ///     delimiter: ',',
-    /// };
+    /// let mut config = TokenizerConfig::default();
+    /// config.delimiter = ';';
+    /// config.trailing_delimiter_policy = TrailingDelimiterPolicy::Drop;
+    /// config.trim_bracket_elements = true;
     ///
     /// let tokenizer = Tokenizer::with_config(config);
     /// ```
@@ -133,11 +176,33 @@ impl Tokenizer {
         Self { config }
     }
     
+    /// Returns the configuration the tokenizer is using
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pam_args::Tokenizer;
+    ///
+    /// let tokenizer = Tokenizer::new();
+    /// assert_eq!(tokenizer.config().delimiter, ',');
+    /// ```
+    pub fn config(&self) -> &TokenizerConfig {
+        &self.config
+    }
+    
     /// Tokenizes a single pre-tokenized argument
     ///
     /// This method processes a single argument and handles special formats
     /// like bracketed content with comma-separated values.
     ///
+    /// An argument starting with a bracket may hold several back-to-back
+    /// groups, as in `[A,B][C,D]`; each group is expanded in turn and gets its
+    /// own entry in `groups`. Literal text is not combined with groups: an
+    /// argument such as `text[A,B]` that does not start with a bracket is
+    /// returned as a single token, and text after a group, as in `[A,B]text`,
+    /// is an error. An opening bracket that is never closed is an error
+    /// wherever it appears, as in `text[A,B`.
+    ///
     /// # Arguments
     ///
     /// * `arg` - Input argument to tokenize
@@ -150,12 +215,13 @@ impl Tokenizer {
     ///
     /// This function will return an error if:
     /// * A delimiter is unclosed (e.g., a quote or bracket without a matching close)
-    /// * Nested brackets are encountered (which are not supported)
+    /// * Nested brackets are encountered and not allowed
+    /// * Text other than another group follows a closing bracket
     ///
     /// # Examples
     ///
-    /// ```ignore
-    /// use pam_args::tokenizer::Tokenizer;
+    /// ```
+    /// use pam_args::Tokenizer;
     ///
     /// let tokenizer = Tokenizer::new();
     /// let result = tokenizer.tokenize_arg("[DEBUG,HOST=localhost,USER='admin']")?;
@@ -165,30 +231,73 @@ impl Tokenizer {
     /// # Ok::<(), pam_args::Error>(())
     /// ```
     pub fn tokenize_arg(&self, arg: &str) -> Result<TokenizationResult> {
-        trace!("Tokenizing argument: '{}'", arg);
+        trace!("Tokenizing argument: '{}'", self.redacted(arg));
         
         // Check if input is a bracketed argument
         if arg.starts_with(self.config.open_bracket) {
-            if !arg.ends_with(self.config.close_bracket) {
-                return Err(Error::UnclosedDelimiter(format!(
-                    "Unclosed bracket in: {}", arg
-                )));
+            debug!("Processing bracketed content: '{}'", self.redacted(arg));
+            let mut metadata: Vec<Token> = Vec::new();
+            let mut groups = Vec::new();
+            let mut rest = arg;
+            
+            // Expand each group in turn; only another group may follow one
+            while !rest.is_empty() {
+                if !rest.starts_with(self.config.open_bracket) {
+                    return Err(Error::InvalidInput(format!(
+                        "Unexpected content after closing bracket in: {}", arg
+                    )));
+                }
+                
+                let end = match self.find_closing_bracket(rest)? {
+                    Some(index) => index + self.config.close_bracket.len_utf8(),
+                    // An escaped final bracket is reported as a trailing escape
+                    None if rest.ends_with(self.config.close_bracket) => rest.len(),
+                    None => {
+                        return Err(Error::UnclosedDelimiter(format!(
+                            "Unclosed bracket in: {}", arg
+                        )));
+                    },
+                };
+                
+                let offset = arg.len() - rest.len();
+                let group_tokens = self.process_bracketed(&rest[..end])?;
+                groups.push(Range { start: metadata.len(), end: metadata.len() + group_tokens.len() });
+                metadata.extend(group_tokens.into_iter().map(|(content, span)| Token {
+                    was_quoted: self.contains_quotes(&content),
+                    content,
+                    original: arg.to_string(),
+                    span: span.start + offset..span.end + offset,
+                    was_bracketed: true,
+                }));
+                rest = &rest[end..];
             }
             
-            debug!("Processing bracketed content: '{}'", arg);
-            // Process bracketed content
-            let tokens = self.process_bracketed(arg)?;
+            trace!("Expanded {} bracketed group(s) into {} token(s)", groups.len(), metadata.len());
             return Ok(TokenizationResult {
-                tokens,
+                tokens: metadata.iter().map(|token| token.content.clone()).collect(),
                 has_bracketed_content: true,
+                groups,
+                metadata,
             });
         }
         
+        if self.has_unclosed_bracket(arg) {
+            return Err(Error::UnclosedDelimiter(format!("Unclosed bracket in: {}", arg)));
+        }
+        
         // For non-bracketed input, simply return it as a single token
-        trace!("Returning non-bracketed argument as-is: '{}'", arg);
+        trace!("Returning non-bracketed argument as-is: '{}'", self.redacted(arg));
         Ok(TokenizationResult {
             tokens: vec![arg.to_string()],
             has_bracketed_content: false,
+            groups: Vec::new(),
+            metadata: vec![Token {
+                content: arg.to_string(),
+                original: arg.to_string(),
+                span: 0..arg.len(),
+                was_bracketed: false,
+                was_quoted: self.contains_quotes(arg),
+            }],
         })
     }
     
@@ -211,8 +320,8 @@ impl Tokenizer {
     ///
     /// # Examples
     ///
-    /// ```ignore
-    /// use pam_args::tokenizer::Tokenizer;
+    /// ```
+    /// use pam_args::Tokenizer;
     ///
     /// let tokenizer = Tokenizer::new();
     /// let args = vec!["DEBUG", "[HOST=localhost,USER=admin]", "VERBOSE"];
@@ -229,13 +338,19 @@ impl Tokenizer {
     {
         let mut all_tokens = Vec::new();
         let mut has_bracketed = false;
+        let mut groups = Vec::new();
+        let mut metadata = Vec::new();
         
         for arg in args {
             let arg_str = arg.as_ref();
-            debug!("Processing argument in tokenize_args: '{}'", arg_str);
+            debug!("Processing argument in tokenize_args: '{}'", self.redacted(arg_str));
             let result = self.tokenize_arg(arg_str)?;
             
+            // Shift group ranges to their position in the combined token list
+            let offset = all_tokens.len();
+            groups.extend(result.groups.into_iter().map(|g| g.start + offset..g.end + offset));
             all_tokens.extend(result.tokens);
+            metadata.extend(result.metadata);
             has_bracketed = has_bracketed || result.has_bracketed_content;
         }
         
@@ -243,6 +358,8 @@ impl Tokenizer {
         Ok(TokenizationResult {
             tokens: all_tokens,
             has_bracketed_content: has_bracketed,
+            groups,
+            metadata,
         })
     }
     
@@ -257,20 +374,196 @@ impl Tokenizer {
     ///
     /// # Returns
     ///
-    /// Result containing a vector of tokens or an error
+    /// Result containing each token with its byte range within `bracketed`, or an error
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// * The bracketed argument is malformed
     /// * A nested delimiter is unclosed
-    fn process_bracketed(&self, bracketed: &str) -> Result<Vec<String>> {
+    fn process_bracketed(&self, bracketed: &str) -> Result<Vec<(String, Range<usize>)>> {
         // Extract content between brackets
         let content = self.extract_bracket_content(bracketed)?;
-        trace!("Extracted bracket content: '{}'", content);
+        trace!("Extracted bracket content: '{}'", self.redacted(&content));
         
         // Split by commas, respecting quotes and escape sequences
-        self.split_by_commas(content)
+        let offset = self.config.open_bracket.len_utf8();
+        let tokens = self.split_by_commas(content)?;
+        Ok(tokens
+            .into_iter()
+            .map(|(token, span)| {
+                let span = span.start + offset..span.end + offset;
+                if self.config.trim_bracket_elements {
+                    self.trim_element(&token, span)
+                } else {
+                    (token, span)
+                }
+            })
+            .collect())
+    }
+    
+    /// Removes whitespace around a bracketed element, keeping escaped whitespace
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The element as split from the bracketed content
+    /// * `span` - Byte range of the element within the original argument
+    ///
+    /// # Returns
+    ///
+    /// The trimmed element and its narrowed byte range
+    fn trim_element(&self, token: &str, span: Range<usize>) -> (String, Range<usize>) {
+        let start = token.len() - token.trim_start().len();
+        let mut end = token.trim_end().len();
+        
+        // A trailing whitespace character preceded by an odd number of escapes is escaped
+        let escapes = token[..end].chars().rev().take_while(|&c| c == self.config.escape_char).count();
+        if escapes % 2 == 1 {
+            end += token[end..].chars().next().map_or(0, char::len_utf8);
+        }
+        
+        // An element of only whitespace trims to empty
+        let end = end.max(start);
+        (token[start..end].to_string(), span.start + start..span.start + end)
+    }
+    
+    /// Checks whether text contains an unescaped quote character
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Token text, with quotes and escape sequences still in place
+    ///
+    /// # Returns
+    ///
+    /// True if a single or double quote appears that is not escaped
+    fn contains_quotes(&self, text: &str) -> bool {
+        let mut escaped = false;
+        for c in text.chars() {
+            if escaped {
+                escaped = false;
+            } else if c == self.config.escape_char {
+                escaped = true;
+            } else if c == self.config.single_quote || c == self.config.double_quote {
+                return true;
+            }
+        }
+        false
+    }
+    
+    /// Checks whether an unescaped, unquoted opening bracket is never closed
+    ///
+    /// Brackets are counted wherever they appear in the argument, and inner
+    /// bracket pairs are balanced whether or not nesting is allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - Input string to check
+    ///
+    /// # Returns
+    ///
+    /// True if an opening bracket has no matching closing bracket
+    fn has_unclosed_bracket(&self, arg: &str) -> bool {
+        let mut state = TokenizerState::Normal;
+        let mut escape_return = TokenizerState::Normal;
+        let mut depth = 0usize;
+        
+        for c in arg.chars() {
+            match state {
+                TokenizerState::EscapeSequence => state = escape_return,
+                _ if c == self.config.escape_char => {
+                    escape_return = state;
+                    state = TokenizerState::EscapeSequence;
+                },
+                TokenizerState::Normal if c == self.config.single_quote => {
+                    state = TokenizerState::InSingleQuote;
+                },
+                TokenizerState::Normal if c == self.config.double_quote => {
+                    state = TokenizerState::InDoubleQuote;
+                },
+                TokenizerState::Normal if c == self.config.open_bracket => depth += 1,
+                TokenizerState::Normal if c == self.config.close_bracket => {
+                    depth = depth.saturating_sub(1);
+                },
+                TokenizerState::InSingleQuote if c == self.config.single_quote => {
+                    state = TokenizerState::Normal;
+                },
+                TokenizerState::InDoubleQuote if c == self.config.double_quote => {
+                    state = TokenizerState::Normal;
+                },
+                _ => {},
+            }
+        }
+        
+        depth > 0
+    }
+    
+    /// Finds the unescaped, unquoted bracket closing the opening bracket of an argument
+    ///
+    /// When nested brackets are allowed, inner bracket pairs are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `arg` - Input string starting with an opening bracket
+    ///
+    /// # Returns
+    ///
+    /// Result containing the byte index of the closing bracket, or None if there is none
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an unquoted opening bracket is
+    /// found before the closing bracket and nested brackets are not allowed
+    fn find_closing_bracket(&self, arg: &str) -> Result<Option<usize>> {
+        let mut state = TokenizerState::Normal;
+        let mut escape_return = TokenizerState::Normal;
+        let mut depth = 0usize;
+        
+        for (index, c) in arg.char_indices().skip(1) {
+            match state {
+                TokenizerState::EscapeSequence => state = escape_return,
+                _ if c == self.config.escape_char => {
+                    escape_return = state;
+                    state = TokenizerState::EscapeSequence;
+                },
+                TokenizerState::Normal if c == self.config.single_quote => {
+                    state = TokenizerState::InSingleQuote;
+                },
+                TokenizerState::Normal if c == self.config.double_quote => {
+                    state = TokenizerState::InDoubleQuote;
+                },
+                TokenizerState::Normal if c == self.config.open_bracket => {
+                    if !self.config.allow_nested_brackets {
+                        return Err(Error::NestedBrackets(format!(
+                            "Nested brackets are not supported: {}", arg
+                        )));
+                    }
+                    depth += 1;
+                },
+                TokenizerState::Normal if c == self.config.close_bracket => {
+                    if depth == 0 {
+                        return Ok(Some(index));
+                    }
+                    depth -= 1;
+                },
+                TokenizerState::InSingleQuote if c == self.config.single_quote => {
+                    state = TokenizerState::Normal;
+                },
+                TokenizerState::InDoubleQuote if c == self.config.double_quote => {
+                    state = TokenizerState::Normal;
+                },
+                _ => {},
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Masks sensitive values in text before it is logged
+    ///
+    /// Elements are split at the configured brackets and delimiter, so every
+    /// `KEY=VALUE` element of a bracketed group is checked.
+    fn redacted(&self, text: &str) -> String {
+        redact_with(text, &[self.config.open_bracket, self.config.close_bracket, self.config.delimiter])
     }
     
     /// Extracts the content of a bracketed string (without the brackets)
@@ -287,7 +580,10 @@ impl Tokenizer {
     ///
     /// This function will return an error if the input is not properly bracketed
     fn extract_bracket_content(&self, bracketed: &str) -> Result<String> {
-        if !bracketed.starts_with(self.config.open_bracket) ||
+        let open_len = self.config.open_bracket.len_utf8();
+        let close_len = self.config.close_bracket.len_utf8();
+        if bracketed.len() < open_len + close_len ||
+           !bracketed.starts_with(self.config.open_bracket) ||
            !bracketed.ends_with(self.config.close_bracket) {
             return Err(Error::InvalidInput(format!(
                 "Input must start with '{}' and end with '{}'",
@@ -296,9 +592,14 @@ impl Tokenizer {
             )));
         }
         
-        // Check for trailing escape character
-        let content = &bracketed[1..bracketed.len() - 1];
-        if content.ends_with(self.config.escape_char) {
+        // Check for trailing escape character (an escaped escape character is fine)
+        let content = &bracketed[open_len..bracketed.len() - close_len];
+        let trailing_escapes = content
+            .chars()
+            .rev()
+            .take_while(|&c| c == self.config.escape_char)
+            .count();
+        if trailing_escapes % 2 == 1 {
             return Err(Error::InvalidInput(format!(
                 "Trailing escape character in: {}", bracketed
             )));
@@ -316,31 +617,42 @@ impl Tokenizer {
     ///
     /// # Returns
     ///
-    /// Result containing a vector of split values or an error
-    fn split_by_commas(&self, content: String) -> Result<Vec<String>> {
+    /// Result containing each split value with its byte range within `content`, or an error
+    fn split_by_commas(&self, content: String) -> Result<Vec<(String, Range<usize>)>> {
         let mut result = Vec::new();
         let mut current = String::new();
         let mut state = TokenizerState::Normal;
         
+        // State to return to once an escape sequence is complete
+        let mut escape_return = TokenizerState::Normal;
+        
         // Handle empty content case
         if content.is_empty() {
-            return Ok(vec![String::new()]);
+            return Ok(vec![(String::new(), 0..0)]);
         }
         
-        // Check if content ends with a delimiter to handle trailing empty token
-        let ends_with_delimiter = content.ends_with(self.config.delimiter);
+        // Tracks whether the last character was an unescaped, unquoted delimiter
+        let mut ends_with_delimiter = false;
+        
+        // Depth of nested brackets, only tracked when nesting is allowed
+        let mut depth = 0usize;
+        
+        // Byte index where the current token starts
+        let mut start = 0;
         
         // Process character by character to handle delimiters and escape sequences
-        for c in content.chars() {
+        for (index, c) in content.char_indices() {
+            ends_with_delimiter = false;
             match (state, c) {
                 // Handle escape sequences
                 (TokenizerState::Normal, ch) if ch == self.config.escape_char => {
                     current.push(ch);
+                    escape_return = TokenizerState::Normal;
                     state = TokenizerState::EscapeSequence;
                 },
                 (TokenizerState::EscapeSequence, c) => {
                     current.push(c);
-                    state = TokenizerState::Normal;
+                    state = escape_return;
                 },
                 
                 // Handle quotes
@@ -350,6 +662,7 @@ impl Tokenizer {
                 },
                 (TokenizerState::InSingleQuote, ch) if ch == self.config.escape_char => {
                     current.push(ch);
+                    escape_return = TokenizerState::InSingleQuote;
                     state = TokenizerState::EscapeSequence;
                 },
                 (TokenizerState::InSingleQuote, ch) if ch == self.config.single_quote => {
@@ -366,6 +679,7 @@ impl Tokenizer {
                 },
                 (TokenizerState::InDoubleQuote, ch) if ch == self.config.escape_char => {
                     current.push(ch);
+                    escape_return = TokenizerState::InDoubleQuote;
                     state = TokenizerState::EscapeSequence;
                 },
                 (TokenizerState::InDoubleQuote, ch) if ch == self.config.double_quote => {
@@ -383,10 +697,31 @@ impl Tokenizer {
                     )));
                 },
                 
-                // Handle delimiters in normal state
-                (TokenizerState::Normal, ch) if ch == self.config.delimiter => {
-                    result.push(current.to_string());
+                // Track nested brackets, keeping them in the token
+                (TokenizerState::Normal, ch)
+                    if self.config.allow_nested_brackets && ch == self.config.open_bracket =>
+                {
+                    current.push(ch);
+                    depth += 1;
+                },
+                (TokenizerState::Normal, ch)
+                    if self.config.allow_nested_brackets && ch == self.config.close_bracket =>
+                {
+                    if depth == 0 {
+                        return Err(Error::InvalidInput(format!(
+                            "Unmatched closing bracket in: {}", &content
+                        )));
+                    }
+                    current.push(ch);
+                    depth -= 1;
+                },
+                
+                // Handle delimiters in normal state, outside nested brackets
+                (TokenizerState::Normal, ch) if ch == self.config.delimiter && depth == 0 => {
+                    result.push((current.to_string(), start..index));
                     current = String::new();
+                    start = index + ch.len_utf8();
+                    ends_with_delimiter = true;
                 },
                 
                 // Normal character in normal state
@@ -398,18 +733,37 @@ impl Tokenizer {
         
         // Check for unclosed delimiters
         match state {
+            TokenizerState::Normal if depth > 0 => {
+                Err(Error::UnclosedDelimiter(format!("Unclosed nested bracket in: {}", &content)))
+            },
             TokenizerState::Normal => {
                 // Add the last token
                 if !current.is_empty() || result.is_empty() {
-                    result.push(current.to_string());
+                    result.push((current.to_string(), start..content.len()));
                 }
                 
-                // If the content ended with a delimiter, add an empty token
+                // If the content ended with a delimiter, apply the trailing delimiter policy
                 if ends_with_delimiter {
-                    result.push(String::new());
+                    match self.config.trailing_delimiter_policy {
+                        TrailingDelimiterPolicy::KeepEmpty => result.push((String::new(), start..start)),
+                        TrailingDelimiterPolicy::Drop => {
+                            while result.last().is_some_and(|(token, _)| token.is_empty()) {
+                                result.pop();
+                            }
+                        },
+                        TrailingDelimiterPolicy::Error => {
+                            return Err(Error::InvalidInput(format!(
+                                "Trailing delimiter in: {}", &content
+                            )));
+                        },
+                    }
                 }
                 
-                trace!("Split into {} tokens: {:?}", result.len(), result);
+                trace!(
+                    "Split into {} token(s): {:?}",
+                    result.len(),
+                    result.iter().map(|(token, _)| self.redacted(token)).collect::<Vec<_>>()
+                );
                 Ok(result)
             },
             TokenizerState::InSingleQuote => {
@@ -429,6 +783,12 @@ impl Tokenizer {
     }
 }
 
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Default for TokenizerConfig {
     fn default() -> Self {
         Self {
@@ -438,6 +798,25 @@ impl Default for TokenizerConfig {
             open_bracket: '[',
             close_bracket: ']',
             delimiter: ',',
+            trailing_delimiter_policy: TrailingDelimiterPolicy::KeepEmpty,
+            allow_nested_brackets: false,
+            trim_bracket_elements: false,
+        }
+    }
+}
+
+impl From<&ParserConfig> for TokenizerConfig {
+    fn from(config: &ParserConfig) -> Self {
+        Self {
+            escape_char: config.escape_char(),
+            single_quote: config.single_quote(),
+            double_quote: config.double_quote(),
+            open_bracket: config.open_bracket(),
+            close_bracket: config.close_bracket(),
+            delimiter: config.delimiter(),
+            trailing_delimiter_policy: config.trailing_delimiter_policy(),
+            allow_nested_brackets: config.allow_nested_brackets(),
+            trim_bracket_elements: config.trim_values(),
         }
     }
 }
@@ -455,6 +834,7 @@ mod tests {
         assert_eq!(config.open_bracket, '[');
         assert_eq!(config.close_bracket, ']');
         assert_eq!(config.delimiter, ',');
+        assert_eq!(config.trailing_delimiter_policy, TrailingDelimiterPolicy::KeepEmpty);
     }
 
     #[test]
@@ -595,6 +975,27 @@ mod tests {
         assert!(result.has_bracketed_content);
     }
 
+    #[test]
+    fn test_trimmed_bracket_elements() {
+        let config = TokenizerConfig { trim_bracket_elements: true, ..TokenizerConfig::default() };
+        let tokenizer = Tokenizer::with_config(config);
+        
+        let input = "[ KEY1 = value1 , KEY2=' spaced ' ,  , \\\\ ]";
+        let result = tokenizer.tokenize_arg(input).unwrap();
+        assert_eq!(result.tokens, vec!["KEY1 = value1", "KEY2=' spaced '", "", "\\\\"]);
+        for token in &result.metadata {
+            assert_eq!(&input[token.span.clone()], token.content);
+        }
+        
+        // Escaped whitespace at the end is part of the element
+        let result = tokenizer.tokenize_arg("[ A\\\\\\  ,B]").unwrap();
+        assert_eq!(result.tokens, vec!["A\\\\\\ ", "B"]);
+        
+        // Untrimmed elements keep their whitespace, including around quotes
+        let result = Tokenizer::new().tokenize_arg("[ KEY=' x ' ]").unwrap();
+        assert_eq!(result.tokens, vec![" KEY=' x ' "]);
+    }
+
     #[test]
     fn test_mixed_quotes() {
         let tokenizer = Tokenizer::new();
@@ -619,6 +1020,41 @@ mod tests {
         assert!(result.has_bracketed_content);
     }
 
+    #[test]
+    fn test_config_accessor() {
+        assert_eq!(Tokenizer::new().config().open_bracket, '[');
+        
+        let config = TokenizerConfig {
+            escape_char: '&',
+            single_quote: '`',
+            double_quote: '*',
+            open_bracket: '{',
+            close_bracket: '}',
+            delimiter: ';',
+            trailing_delimiter_policy: TrailingDelimiterPolicy::Drop,
+            allow_nested_brackets: true,
+            trim_bracket_elements: true,
+        };
+        let tokenizer = Tokenizer::with_config(config);
+        let active = tokenizer.config();
+        assert_eq!(active.escape_char, '&');
+        assert_eq!(active.single_quote, '`');
+        assert_eq!(active.double_quote, '*');
+        assert_eq!((active.open_bracket, active.close_bracket), ('{', '}'));
+        assert_eq!(active.delimiter, ';');
+        assert_eq!(active.trailing_delimiter_policy, TrailingDelimiterPolicy::Drop);
+        assert!(active.allow_nested_brackets);
+        assert!(active.trim_bracket_elements);
+        
+        let parser_config = ParserConfig::builder().delimiter(';').build();
+        let tokenizer = Tokenizer::with_config(TokenizerConfig::from(&parser_config));
+        assert_eq!(tokenizer.config().delimiter, ';');
+        assert!(tokenizer.config().trim_bracket_elements);
+        
+        let parser_config = ParserConfig::builder().trim_values(false).build();
+        assert!(!TokenizerConfig::from(&parser_config).trim_bracket_elements);
+    }
+    
     #[test]
     fn test_custom_config() {
         let config = TokenizerConfig {
@@ -628,6 +1064,9 @@ mod tests {
             open_bracket: '{',
             close_bracket: '}',
             delimiter: ';',
+            trailing_delimiter_policy: TrailingDelimiterPolicy::KeepEmpty,
+            allow_nested_brackets: false,
+            trim_bracket_elements: false,
         };
         let tokenizer = Tokenizer::with_config(config);
         
@@ -639,4 +1078,196 @@ mod tests {
         assert_eq!(result.tokens, vec!["KEY=*Value; with semicolon*"]);
         assert!(result.has_bracketed_content);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_allowed_nested_brackets() {
+        let tokenizer = Tokenizer::with_config(TokenizerConfig {
+            allow_nested_brackets: true,
+            ..TokenizerConfig::default()
+        });
+        
+        let result = tokenizer.tokenize_arg("[OUTER=[a,b],X=1]").unwrap();
+        assert_eq!(result.tokens, vec!["OUTER=[a,b]", "X=1"]);
+        assert_eq!(result.groups, vec![Range { start: 0, end: 2 }]);
+        
+        // Two levels of nesting, with brackets inside quotes left alone
+        let result = tokenizer.tokenize_arg("[A=[b,[c,d]],E='[',F]").unwrap();
+        assert_eq!(result.tokens, vec!["A=[b,[c,d]]", "E='['", "F"]);
+        
+        // Still off by default
+        let result = Tokenizer::new().tokenize_arg("[OUTER=[a,b],X=1]");
+        assert!(matches!(result, Err(Error::NestedBrackets(_))));
+    }
+    
+    #[test]
+    fn test_unbalanced_nested_brackets() {
+        let tokenizer = Tokenizer::with_config(TokenizerConfig {
+            allow_nested_brackets: true,
+            ..TokenizerConfig::default()
+        });
+        
+        assert!(matches!(tokenizer.tokenize_arg("[OUTER=[a,b]"), Err(Error::UnclosedDelimiter(_))));
+        assert!(matches!(tokenizer.tokenize_arg("[A=[[b]]"), Err(Error::UnclosedDelimiter(_))));
+        assert!(matches!(tokenizer.tokenize_arg("[OUTER=[a]],b]"), Err(Error::InvalidInput(_))));
+        assert!(matches!(tokenizer.tokenize_arg("[OUTER=[a,b]]]"), Err(Error::InvalidInput(_))));
+    }
+    
+    #[test]
+    fn test_trailing_delimiter_policies() {
+        let with_policy = |policy| Tokenizer::with_config(TokenizerConfig {
+            trailing_delimiter_policy: policy,
+            ..TokenizerConfig::default()
+        });
+        
+        let keep = with_policy(TrailingDelimiterPolicy::KeepEmpty);
+        assert_eq!(keep.tokenize_arg("[a,]").unwrap().tokens, vec!["a", ""]);
+        assert_eq!(keep.tokenize_arg("[a,,]").unwrap().tokens, vec!["a", "", ""]);
+        
+        let drop = with_policy(TrailingDelimiterPolicy::Drop);
+        assert_eq!(drop.tokenize_arg("[a,]").unwrap().tokens, vec!["a"]);
+        assert_eq!(drop.tokenize_arg("[a,,]").unwrap().tokens, vec!["a"]);
+        assert_eq!(drop.tokenize_arg("[a,,b]").unwrap().tokens, vec!["a", "", "b"]);
+        
+        let error = with_policy(TrailingDelimiterPolicy::Error);
+        assert!(matches!(error.tokenize_arg("[a,]"), Err(Error::InvalidInput(_))));
+        assert!(matches!(error.tokenize_arg("[a,,]"), Err(Error::InvalidInput(_))));
+        assert_eq!(error.tokenize_arg("[a,b]").unwrap().tokens, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_escape_inside_quotes_stays_quoted() {
+        let tokenizer = Tokenizer::new();
+        let result = tokenizer.tokenize_arg("[KEY='a\\'b, c',OTHER=1]").unwrap();
+        assert_eq!(result.tokens, vec!["KEY='a\\'b, c'", "OTHER=1"]);
+    }
+
+    #[test]
+    fn test_escaped_trailing_characters() {
+        let tokenizer = Tokenizer::new();
+        
+        // An escaped delimiter at the end is content, not a trailing delimiter
+        let result = tokenizer.tokenize_arg("[KEY=a\\,]").unwrap();
+        assert_eq!(result.tokens, vec!["KEY=a\\,"]);
+        
+        // An escaped escape character at the end is not a trailing escape
+        let result = tokenizer.tokenize_arg("[KEY=a\\\\]").unwrap();
+        assert_eq!(result.tokens, vec!["KEY=a\\\\"]);
+    }
+
+    #[test]
+    fn test_multiple_bracket_groups() {
+        let tokenizer = Tokenizer::new();
+        
+        let result = tokenizer.tokenize_arg("[A,B][C=1,D]").unwrap();
+        assert_eq!(result.tokens, vec!["A", "B", "C=1", "D"]);
+        assert!(result.has_bracketed_content);
+        assert_eq!(result.groups, vec![0..2, 2..4]);
+        
+        let result = tokenizer.tokenize_arg("[A][]['x]y']").unwrap();
+        assert_eq!(result.tokens, vec!["A", "", "'x]y'"]);
+        assert_eq!(result.groups, vec![0..1, 1..2, 2..3]);
+        
+        // Text before a group keeps the whole argument as one token
+        let result = tokenizer.tokenize_arg("text[A,B]").unwrap();
+        assert_eq!(result.tokens, vec!["text[A,B]"]);
+        assert!(!result.has_bracketed_content);
+        
+        // Text after or between groups is rejected
+        for input in ["[A,B]text", "[A]x[B]"] {
+            assert!(matches!(tokenizer.tokenize_arg(input), Err(Error::InvalidInput(_))), "{}", input);
+        }
+        
+        // An unbalanced group after text is unclosed, unless it is quoted or escaped
+        for input in ["text[A,B", "KEY=a[b]c[", "x[[A]", "text]A["] {
+            assert!(matches!(tokenizer.tokenize_arg(input), Err(Error::UnclosedDelimiter(_))), "{}", input);
+        }
+        for input in ["text'[A,B'", "text\\[A,B"] {
+            assert_eq!(tokenizer.tokenize_arg(input).unwrap().tokens, vec![input]);
+        }
+        
+        // An unbalanced group in the middle is still unclosed
+        assert!(matches!(tokenizer.tokenize_arg("[A,B][C,D"), Err(Error::UnclosedDelimiter(_))));
+        assert!(matches!(tokenizer.tokenize_arg("[A][B['C]"), Err(Error::NestedBrackets(_))));
+    }
+
+    #[test]
+    fn test_token_metadata() {
+        let tokenizer = Tokenizer::new();
+        let input = "[DEBUG,USER='a,b',PATH=\\'x\\'][\"q\"]";
+        let result = tokenizer.tokenize_arg(input).unwrap();
+
+        let flags: Vec<(bool, bool)> = result.metadata.iter().map(|t| (t.was_bracketed, t.was_quoted)).collect();
+        assert_eq!(flags, vec![(true, false), (true, true), (true, false), (true, true)]);
+        for token in &result.metadata {
+            assert_eq!(token.original, input);
+            assert_eq!(&input[token.span.clone()], token.content);
+        }
+        let contents: Vec<&str> = result.metadata.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, result.tokens);
+
+        // Metadata follows each argument, with spans relative to that argument
+        let result = tokenizer.tokenize_args(["'quoted'", "plain", "[A,]"]).unwrap();
+        let flags: Vec<(bool, bool)> = result.metadata.iter().map(|t| (t.was_bracketed, t.was_quoted)).collect();
+        assert_eq!(flags, vec![(false, true), (false, false), (true, false), (true, false)]);
+        assert_eq!(result.metadata[1].span, 0..5);
+        assert_eq!(result.metadata[2].span, 1..2);
+        assert_eq!(result.metadata[3].span, 3..3);
+        assert_eq!(result.metadata[3].original, "[A,]");
+
+        // Spans stay on character boundaries with multi-byte brackets
+        let tokenizer = Tokenizer::with_config(TokenizerConfig {
+            open_bracket: '«',
+            close_bracket: '»',
+            ..TokenizerConfig::default()
+        });
+        let input = "«A=1,B=ü»«C»";
+        let result = tokenizer.tokenize_arg(input).unwrap();
+        assert_eq!(result.tokens, vec!["A=1", "B=ü", "C"]);
+        for token in &result.metadata {
+            assert_eq!(&input[token.span.clone()], token.content);
+        }
+    }
+
+    #[test]
+    fn test_content_after_closing_bracket() {
+        let tokenizer = Tokenizer::new();
+        
+        for input in ["[]extra", "[a]b", "[a]b]", "[A=1,B=2] C=3"] {
+            match tokenizer.tokenize_arg(input) {
+                Err(Error::InvalidInput(_)) => (),
+                other => panic!("Expected InvalidInput for {}, got {:?}", input, other),
+            }
+        }
+        
+        // A lone opening bracket is still unclosed
+        assert!(matches!(tokenizer.tokenize_arg("["), Err(Error::UnclosedDelimiter(_))));
+        
+        // Quoted and escaped closing brackets are content
+        let result = tokenizer.tokenize_arg("[KEY=']',OTHER=\\]]").unwrap();
+        assert_eq!(result.tokens, vec!["KEY=']'", "OTHER=\\]"]);
+    }
+
+    #[test]
+    fn test_nested_brackets() {
+        let tokenizer = Tokenizer::new();
+        let result = tokenizer.tokenize_arg("[a,[b]]");
+        assert!(matches!(result, Err(Error::NestedBrackets(_))));
+    }
+
+    #[test]
+    fn test_bracket_groups() {
+        let tokenizer = Tokenizer::new();
+        
+        let result = tokenizer.tokenize_arg("DEBUG").unwrap();
+        assert!(result.groups.is_empty());
+        
+        let result = tokenizer.tokenize_arg("[A,B,C]").unwrap();
+        assert_eq!(result.groups, vec![0..3]);
+        
+        let args = vec!["FLAG", "[A=1,B=2,C=3]", "TEXT", "[D]", "[]", "[E,F]"];
+        let result = tokenizer.tokenize_args(args).unwrap();
+        assert_eq!(result.tokens, vec!["FLAG", "A=1", "B=2", "C=3", "TEXT", "D", "", "E", "F"]);
+        assert_eq!(result.groups, vec![1..4, 5..6, 6..7, 7..9]);
+        assert_eq!(result.tokens[result.groups[3].clone()], ["E", "F"]);
+    }
+}
@@ -0,0 +1,94 @@
+//! Property tests for the tokenizer.
+//!
+//! This module generates random bracketed and quoted inputs and checks the
+//! invariants the tokenizer's state machine must uphold: it never panics,
+//! balanced input round-trips through bracket splitting, and unbalanced
+//! quotes or brackets are always rejected.
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::tokenizer::Tokenizer;
+    use proptest::prelude::*;
+
+    /// Plain text that needs no escaping inside brackets
+    fn plain() -> impl Strategy<Value = String> {
+        "[A-Za-z0-9_= ]{0,8}"
+    }
+
+    /// An escape character followed by any character that may need escaping
+    fn escaped() -> impl Strategy<Value = String> {
+        prop::sample::select(vec![',', '\'', '"', '\\', '[', ']', 'n', 't'])
+            .prop_map(|c| format!("\\{}", c))
+    }
+
+    /// Text inside a quoted section; commas and the other quote are allowed
+    fn quoted_body(other_quote: char) -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                "[A-Za-z0-9 ,=]{1,4}",
+                Just(other_quote.to_string()),
+                escaped(),
+            ],
+            0..4,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    /// A single well-formed element of a bracket group
+    fn element() -> impl Strategy<Value = String> {
+        prop::collection::vec(
+            prop_oneof![
+                plain(),
+                escaped(),
+                quoted_body('"').prop_map(|body| format!("'{}'", body)),
+                quoted_body('\'').prop_map(|body| format!("\"{}\"", body)),
+            ],
+            0..4,
+        )
+        .prop_map(|parts| parts.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(input in any::<String>()) {
+            let tokenizer = Tokenizer::new();
+            let _ = tokenizer.tokenize_arg(&input);
+            let _ = tokenizer.tokenize_arg(&format!("[{}]", input));
+        }
+
+        #[test]
+        fn balanced_input_round_trips(elements in prop::collection::vec(element(), 1..6)) {
+            let tokenizer = Tokenizer::new();
+            let input = format!("[{}]", elements.join(","));
+
+            let result = tokenizer.tokenize_arg(&input).unwrap();
+            prop_assert!(result.has_bracketed_content);
+            prop_assert_eq!(result.tokens, elements);
+        }
+
+        #[test]
+        fn unbalanced_quote_is_rejected(
+            prefix in prop::collection::vec(element(), 0..3),
+            quote in prop::sample::select(vec!['\'', '"']),
+            tail in plain(),
+        ) {
+            let tokenizer = Tokenizer::new();
+            let mut elements = prefix;
+            elements.push(format!("{}{}", quote, tail));
+            let input = format!("[{}]", elements.join(","));
+
+            let result = tokenizer.tokenize_arg(&input);
+            prop_assert!(matches!(result, Err(Error::UnclosedDelimiter(_))), "{:?}", result);
+        }
+
+        #[test]
+        fn unclosed_bracket_is_rejected(content in plain()) {
+            let tokenizer = Tokenizer::new();
+            let input = format!("[{}", content);
+
+            let result = tokenizer.tokenize_arg(&input);
+            prop_assert!(matches!(result, Err(Error::UnclosedDelimiter(_))), "{:?}", result);
+        }
+    }
+}
@@ -5,6 +5,7 @@
 //! case conversion, escape sequence handling, and other operations that
 //! are needed by various components.
 
+use crate::config::ParserConfig;
 use crate::error::{Error, Result};
 use log::{debug, trace};
 
@@ -22,6 +23,12 @@ pub(crate) struct TextProcessingConfig {
     
     /// Double quote character
     pub double_quote: char,
+    
+    /// Opening bracket character
+    pub open_bracket: char,
+    
+    /// Closing bracket character
+    pub close_bracket: char,
 }
 
 impl Default for TextProcessingConfig {
@@ -31,12 +38,41 @@ impl Default for TextProcessingConfig {
             escape_char: '\\',
             single_quote: '\'',
             double_quote: '"',
+            open_bracket: '[',
+            close_bracket: ']',
+        }
+    }
+}
+
+impl From<&ParserConfig> for TextProcessingConfig {
+    fn from(config: &ParserConfig) -> Self {
+        Self {
+            case_sensitive: config.is_case_sensitive(),
+            escape_char: config.escape_char(),
+            single_quote: config.single_quote(),
+            double_quote: config.double_quote(),
+            open_bracket: config.open_bracket(),
+            close_bracket: config.close_bracket(),
         }
     }
 }
 
 /// Functions for string case handling
 pub(crate) mod case {
+    use crate::config::KeyNormalizer;
+    
+    /// Key normalizer held by a configuration or parse result
+    ///
+    /// Wraps the function so that its holders can derive `Debug`.
+    #[derive(Clone)]
+    pub(crate) struct Normalizer(pub(crate) KeyNormalizer);
+    
+    impl std::fmt::Debug for Normalizer {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Normalizer(..)")
+        }
+    }
+    
     /// Converts a string to lowercase if case-insensitive mode is enabled
     ///
     /// # Arguments
@@ -132,6 +168,43 @@ pub(crate) mod escaping {
         Ok(result)
     }
     
+    /// Replaces escaped quote characters with the quotes themselves
+    ///
+    /// Unlike `unescape`, every other escape sequence is left untouched, so
+    /// this can be applied to any quoted value without rejecting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string containing escaped quotes
+    /// * `config` - Configuration for text processing
+    ///
+    /// # Returns
+    ///
+    /// The string with each escaped single or double quote replaced by the quote
+    pub(crate) fn unescape_quotes(s: &str, config: &TextProcessingConfig) -> String {
+        let mut result = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        
+        while let Some(c) = chars.next() {
+            if c != config.escape_char {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some(next) if next == config.single_quote || next == config.double_quote => {
+                    result.push(next);
+                },
+                Some(next) => {
+                    result.push(c);
+                    result.push(next);
+                },
+                None => result.push(c),
+            }
+        }
+        
+        result
+    }
+    
     /// Escapes special characters in a string
     ///
     /// # Arguments
@@ -282,6 +355,120 @@ pub(crate) mod strings {
         Ok(result)
     }
     
+    /// Splits a line on whitespace, respecting quotes, brackets and escape sequences
+    ///
+    /// Whitespace inside quotes or brackets does not separate words, so
+    /// `[HOST=a b] USER='x y'` yields two words.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The line to split
+    /// * `config` - Configuration for text processing
+    ///
+    /// # Returns
+    ///
+    /// Result containing a vector of non-empty words or an error
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the line contains unclosed quotes or brackets, or
+    /// ends with an escape character
+    pub(crate) fn split_whitespace(s: &str, config: &TextProcessingConfig) -> Result<Vec<String>> {
+        let mut result = Vec::new();
+        let mut current = String::new();
+        let mut in_single_quote = false;
+        let mut in_double_quote = false;
+        let mut in_escape = false;
+        let mut bracket_depth = 0usize;
+
+        for c in s.chars() {
+            if in_escape {
+                current.push(c);
+                in_escape = false;
+                continue;
+            }
+
+            if c == config.escape_char {
+                current.push(c);
+                in_escape = true;
+                continue;
+            }
+
+            if c == config.single_quote && !in_double_quote {
+                in_single_quote = !in_single_quote;
+            } else if c == config.double_quote && !in_single_quote {
+                in_double_quote = !in_double_quote;
+            } else if !in_single_quote && !in_double_quote {
+                if c == config.open_bracket {
+                    bracket_depth += 1;
+                } else if c == config.close_bracket {
+                    bracket_depth = bracket_depth.saturating_sub(1);
+                } else if c.is_whitespace() && bracket_depth == 0 {
+                    if !current.is_empty() {
+                        result.push(std::mem::take(&mut current));
+                    }
+                    continue;
+                }
+            }
+
+            current.push(c);
+        }
+
+        if in_single_quote {
+            return Err(Error::UnclosedDelimiter(format!(
+                "Unclosed single quote in: {}", s
+            )));
+        }
+
+        if in_double_quote {
+            return Err(Error::UnclosedDelimiter(format!(
+                "Unclosed double quote in: {}", s
+            )));
+        }
+
+        if bracket_depth > 0 {
+            return Err(Error::UnclosedDelimiter(format!(
+                "Unclosed bracket in: {}", s
+            )));
+        }
+
+        if in_escape {
+            return Err(Error::UnclosedDelimiter(
+                "String ends with an escape character".to_string()
+            ));
+        }
+
+        if !current.is_empty() {
+            result.push(current);
+        }
+
+        Ok(result)
+    }
+
+    /// Removes characters that render as nothing
+    ///
+    /// These are zero-width spaces and joiners, the word joiner, the byte
+    /// order mark, the soft hyphen and bidirectional formatting controls.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to clean
+    ///
+    /// # Returns
+    ///
+    /// The string without invisible characters
+    pub(crate) fn strip_invisible(s: &str) -> String {
+        s.chars()
+            .filter(|c| {
+                !matches!(
+                    c,
+                    '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}'
+                        | '\u{2066}'..='\u{2069}' | '\u{FEFF}'
+                )
+            })
+            .collect()
+    }
+    
     /// Checks if a string is a valid key name for arguments
     ///
     /// # Arguments
@@ -383,6 +570,19 @@ mod tests {
         assert!(escaping::unescape("Invalid\\z", &config).is_err());
     }
     
+    #[test]
+    fn test_escaping_unescape_quotes() {
+        let config = TextProcessingConfig::default();
+        
+        assert_eq!(escaping::unescape_quotes(r#"a \"b\" c"#, &config), r#"a "b" c"#);
+        assert_eq!(escaping::unescape_quotes(r"a \'b\' c", &config), "a 'b' c");
+        
+        // Other escape sequences are preserved
+        assert_eq!(escaping::unescape_quotes(r"a\,b\n", &config), r"a\,b\n");
+        assert_eq!(escaping::unescape_quotes(r#"a\\"#, &config), r#"a\\"#);
+        assert_eq!(escaping::unescape_quotes(r"trailing\", &config), r"trailing\");
+    }
+    
     #[test]
     fn test_escaping_escape() {
         let config = TextProcessingConfig::default();
@@ -426,6 +626,13 @@ mod tests {
         assert_eq!(strings::smart_trim(" a ", &config), "a");
     }
     
+    #[test]
+    fn test_strings_strip_invisible() {
+        assert_eq!(strings::strip_invisible("DE\u{200B}BUG"), "DEBUG");
+        assert_eq!(strings::strip_invisible("\u{FEFF}USER=\u{202E}admin\u{00AD}"), "USER=admin");
+        assert_eq!(strings::strip_invisible("caf\u{e9} ok"), "caf\u{e9} ok");
+    }
+    
     #[test]
     fn test_strings_smart_split() {
         let config = TextProcessingConfig::default();
@@ -460,6 +667,34 @@ mod tests {
         assert!(strings::smart_split("Escaped\\", ',', &config).is_err());
     }
     
+    #[test]
+    fn test_strings_split_whitespace() {
+        let config = TextProcessingConfig::default();
+        
+        // Basic splitting collapses repeated whitespace
+        assert_eq!(
+            strings::split_whitespace("  DEBUG   USER=admin ", &config).unwrap(),
+            vec!["DEBUG", "USER=admin"]
+        );
+        
+        // Quotes and brackets keep whitespace together
+        assert_eq!(
+            strings::split_whitespace("MSG='hello world' [A=1, B=2]", &config).unwrap(),
+            vec!["MSG='hello world'", "[A=1, B=2]"]
+        );
+        
+        // Escaped whitespace
+        assert_eq!(
+            strings::split_whitespace("a\\ b c", &config).unwrap(),
+            vec!["a\\ b", "c"]
+        );
+        
+        // Error cases
+        assert!(strings::split_whitespace("MSG='unclosed", &config).is_err());
+        assert!(strings::split_whitespace("[A=1 B", &config).is_err());
+        assert!(strings::split_whitespace("Escaped\\", &config).is_err());
+    }
+    
     #[test]
     fn test_strings_is_valid_key_name() {
         // Valid key names
@@ -0,0 +1,42 @@
+use pam_args::{Tokenizer, TokenizerConfig, TokenizationResult, ParserConfig};
+
+#[test]
+fn test_tokenize_bracketed_argument() {
+    let tokenizer = Tokenizer::new();
+    let result: TokenizationResult = tokenizer.tokenize_arg("[DEBUG,HOST=localhost,USER='admin']").unwrap();
+
+    assert_eq!(result.tokens, vec!["DEBUG", "HOST=localhost", "USER='admin'"]);
+    assert!(result.has_bracketed_content);
+    assert_eq!(result.groups, vec![0..3]);
+}
+
+#[test]
+fn test_tokenize_args() {
+    let tokenizer = Tokenizer::default();
+    let result = tokenizer.tokenize_args(["DEBUG", "[HOST=localhost,USER=admin]"]).unwrap();
+
+    assert_eq!(result.tokens, vec!["DEBUG", "HOST=localhost", "USER=admin"]);
+    assert_eq!(result.groups, vec![1..3]);
+}
+
+#[test]
+fn test_tokenizer_config_from_parser_config() {
+    let parser_config = ParserConfig::builder().delimiter(';').build();
+    let tokenizer = Tokenizer::with_config(TokenizerConfig::from(&parser_config));
+
+    assert_eq!(tokenizer.config().delimiter, ';');
+    let result = tokenizer.tokenize_arg("[A=1;B=2]").unwrap();
+    assert_eq!(result.tokens, vec!["A=1", "B=2"]);
+}
+
+#[test]
+fn test_tokenizer_config_field_assignment() {
+    let mut config = TokenizerConfig::default();
+    config.delimiter = '|';
+    config.trim_bracket_elements = true;
+    let tokenizer = Tokenizer::with_config(config);
+
+    let result = tokenizer.tokenize_arg("[ A=1 | B=2 ]").unwrap();
+    assert_eq!(result.tokens, vec!["A=1", "B=2"]);
+    assert_eq!(result.metadata[1].content, "B=2");
+}